@@ -1,8 +1,9 @@
 #![feature(test)]
 extern crate test;
 
-use belt_block::BeltBlock;
+use belt_block::{BeltBlock, belt_hash, belt_wblock_dec, belt_wblock_enc};
 use cipher::{block_decryptor_bench, block_encryptor_bench};
+use test::Bencher;
 
 block_encryptor_bench!(
     Key: BeltBlock,
@@ -14,3 +15,40 @@ block_decryptor_bench!(
     beltblock_decrypt_block,
     beltblock_decrypt_blocks,
 );
+
+const KEY: [u32; 8] = [0x42; 8];
+
+#[bench]
+fn belt_wblock_enc_512(b: &mut Bencher) {
+    let mut buf = [0u8; 512];
+    b.iter(|| belt_wblock_enc(test::black_box(&mut buf), &KEY).unwrap());
+    b.bytes = buf.len() as u64;
+}
+
+#[bench]
+fn belt_wblock_dec_512(b: &mut Bencher) {
+    let mut buf = [0u8; 512];
+    b.iter(|| belt_wblock_dec(test::black_box(&mut buf), &KEY).unwrap());
+    b.bytes = buf.len() as u64;
+}
+
+#[bench]
+fn belt_wblock_enc_64k(b: &mut Bencher) {
+    let mut buf = vec![0u8; 1 << 16];
+    b.iter(|| belt_wblock_enc(test::black_box(&mut buf), &KEY).unwrap());
+    b.bytes = buf.len() as u64;
+}
+
+#[bench]
+fn belt_wblock_dec_64k(b: &mut Bencher) {
+    let mut buf = vec![0u8; 1 << 16];
+    b.iter(|| belt_wblock_dec(test::black_box(&mut buf), &KEY).unwrap());
+    b.bytes = buf.len() as u64;
+}
+
+#[bench]
+fn belt_hash_1k(b: &mut Bencher) {
+    let buf = [0u8; 1024];
+    b.iter(|| belt_hash(test::black_box(&buf)));
+    b.bytes = buf.len() as u64;
+}
@@ -13,7 +13,7 @@ use cipher::zeroize::{Zeroize, ZeroizeOnDrop};
 /// BelT block cipher.
 #[derive(Clone)]
 pub struct BeltBlock {
-    key: [u32; 8],
+    pub(crate) key: [u32; 8],
 }
 
 impl KeySizeUser for BeltBlock {
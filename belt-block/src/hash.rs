@@ -0,0 +1,137 @@
+use crate::{belt_block_raw, from_u32, to_u32};
+use core::fmt;
+
+/// Initial value of the `belt-hash` state: the first 32 bytes of the S-box
+/// `H` as little-endian words.
+const H0: [u32; 8] = [
+    0xC8BA94B1, 0x3BF5080A, 0x8E006D36, 0xE45D4A58, 0x9DFA0485, 0xACC7B61B, 0xC2722E25, 0x0DCEFD02,
+];
+
+#[inline(always)]
+fn xor4(a: [u32; 4], b: [u32; 4]) -> [u32; 4] {
+    [a[0] ^ b[0], a[1] ^ b[1], a[2] ^ b[2], a[3] ^ b[3]]
+}
+
+#[inline(always)]
+fn concat(a: [u32; 4], b: [u32; 4]) -> [u32; 8] {
+    [a[0], a[1], a[2], a[3], b[0], b[1], b[2], b[3]]
+}
+
+#[inline(always)]
+fn concat16(a: [u32; 8], b: [u32; 8]) -> [u32; 16] {
+    let mut res = [0; 16];
+    res[..8].copy_from_slice(&a);
+    res[8..].copy_from_slice(&b);
+    res
+}
+
+#[inline(always)]
+fn split(x: &[u32]) -> [u32; 4] {
+    x.try_into().unwrap()
+}
+
+/// Compression function `belt-compress` defined in the standard.
+///
+/// Takes the words of `X₁ ‖ X₂ ‖ X₃ ‖ X₄` and returns the pair `(S, Y)` of
+/// the 128-bit and the 256-bit outputs.
+#[inline]
+pub fn belt_compress(x: &[u32; 16]) -> ([u32; 4], [u32; 8]) {
+    let (x1, x2) = (split(&x[..4]), split(&x[4..8]));
+    let (x3, x4) = (split(&x[8..12]), split(&x[12..]));
+
+    // S ← belt-block(X₃ ⊕ X₄, X₁ ‖ X₂) ⊕ X₃ ⊕ X₄
+    let x34 = xor4(x3, x4);
+    let s = xor4(belt_block_raw(x34, &concat(x1, x2)), x34);
+    // Y₁ ← belt-block(X₁, S ‖ X₄) ⊕ X₁
+    let y1 = xor4(belt_block_raw(x1, &concat(s, x4)), x1);
+    // Y₂ ← belt-block(X₂, (S ⊕ 1¹²⁸) ‖ X₃) ⊕ X₂
+    let not_s = s.map(|w| !w);
+    let y2 = xor4(belt_block_raw(x2, &concat(not_s, x3)), x2);
+
+    (s, concat(y1, y2))
+}
+
+/// Hash function `belt-hash` defined in the standard.
+///
+/// The message is processed in 32-byte blocks, the last one padded with
+/// zeros, and the hash is 32 bytes long.
+#[derive(Clone)]
+pub struct BeltHash {
+    /// Length of the message in bytes
+    len: u128,
+    s: [u32; 4],
+    h: [u32; 8],
+    /// Incomplete block of the message and its length
+    buf: [u8; 32],
+    pos: usize,
+}
+
+impl BeltHash {
+    /// Create a new hasher.
+    pub fn new() -> Self {
+        Self {
+            len: 0,
+            s: [0; 4],
+            h: H0,
+            buf: [0; 32],
+            pos: 0,
+        }
+    }
+
+    /// Compress the block in the buffer into the state.
+    fn compress(&mut self) {
+        let x: [u32; 8] = to_u32(&self.buf);
+        // (t, h) ← belt-compress(Xᵢ ‖ h), s ← s ⊕ t
+        let (t, h) = belt_compress(&concat16(x, self.h));
+        self.s = xor4(self.s, t);
+        self.h = h;
+        self.pos = 0;
+    }
+
+    /// Update the hash state with `data`.
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.len = self.len.wrapping_add(data.len() as u128);
+        while !data.is_empty() {
+            let len = data.len().min(32 - self.pos);
+            let (head, rest) = data.split_at(len);
+            self.buf[self.pos..][..len].copy_from_slice(head);
+            self.pos += len;
+            if self.pos == 32 {
+                self.compress();
+            }
+            data = rest;
+        }
+    }
+
+    /// Compute the hash of the data processed so far.
+    pub fn finalize(mut self) -> [u8; 32] {
+        if self.pos != 0 {
+            self.buf[self.pos..].fill(0);
+            self.compress();
+        }
+        // Y ← σ₂(⟨|X|⟩₁₂₈ ‖ s ‖ h)
+        let bits = self.len.wrapping_mul(8);
+        let r: [u32; 4] = to_u32(&bits.to_le_bytes());
+        let (_, y) = belt_compress(&concat16(concat(r, self.s), self.h));
+        from_u32(&y)
+    }
+}
+
+/// Compute `belt-hash` of `data`.
+pub fn belt_hash(data: &[u8]) -> [u8; 32] {
+    let mut hasher = BeltHash::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+impl Default for BeltHash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for BeltHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BeltHash { ... }")
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{InvalidLengthError, belt_compress, from_u32, to_u32};
+
+/// Key expansion `belt-keyexpand` defined in the standard.
+///
+/// Expands a 16, 24 or 32-byte key into the 256-bit key used by
+/// [`belt_block_raw`][crate::belt_block_raw]. Returns [`InvalidLengthError`]
+/// for keys of other lengths.
+#[inline]
+pub fn belt_keyexpand(key: &[u8]) -> Result<[u32; 8], InvalidLengthError> {
+    let mut res = [0u32; 8];
+    match key.len() {
+        // θ ‖ θ
+        16 => {
+            let k: [u32; 4] = to_u32(key);
+            res[..4].copy_from_slice(&k);
+            res[4..].copy_from_slice(&k);
+        }
+        // θ₁ ‖ … ‖ θ₆ ‖ (θ₁ ⊕ θ₂ ⊕ θ₃) ‖ (θ₄ ⊕ θ₅ ⊕ θ₆)
+        24 => {
+            let k: [u32; 6] = to_u32(key);
+            res[..6].copy_from_slice(&k);
+            res[6] = k[0] ^ k[1] ^ k[2];
+            res[7] = k[3] ^ k[4] ^ k[5];
+        }
+        32 => res = to_u32(key),
+        _ => return Err(InvalidLengthError),
+    }
+    Ok(res)
+}
+
+/// Key repetition `belt-keyrep` defined in the standard.
+///
+/// Derives a key of `out.len()` bytes from `key` with the 12-byte `level`
+/// and the 16-byte `header`. Both `key` and `out` must be 16, 24 or 32 bytes
+/// long, and `out` must not be longer than `key`, otherwise
+/// [`InvalidLengthError`] is returned.
+pub fn belt_keyrep(
+    key: &[u8],
+    level: &[u8; 12],
+    header: &[u8; 16],
+    out: &mut [u8],
+) -> Result<(), InvalidLengthError> {
+    // r is a word of the S-box `H` selected by the key lengths
+    let r: u32 = match (key.len(), out.len()) {
+        (16, 16) => 0xC8BA94B1,
+        (24, 16) => 0x12D6E35B,
+        (24, 24) => 0xFFC0B05C,
+        (32, 16) => 0x1ADC2BE1,
+        (32, 24) => 0x3876ABC1,
+        (32, 32) => 0x7B653CF3,
+        _ => return Err(InvalidLengthError),
+    };
+
+    // Y ← Lo(σ₂(r ‖ D ‖ I ‖ belt-keyexpand(X)), m)
+    let mut x = [0u32; 16];
+    x[0] = r;
+    x[1..4].copy_from_slice(&to_u32::<3>(level));
+    x[4..8].copy_from_slice(&to_u32::<4>(header));
+    x[8..].copy_from_slice(&belt_keyexpand(key)?);
+    let (_, y) = belt_compress(&x);
+    let y: [u8; 32] = from_u32(&y);
+    out.copy_from_slice(&y[..out.len()]);
+    Ok(())
+}
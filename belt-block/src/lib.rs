@@ -9,6 +9,12 @@
 //!
 //! USE AT YOUR OWN RISK!
 //!
+//! The crate also implements the `belt-compress` and `belt-hash` functions
+//! and the `belt-keyexpand` and `belt-keyrep` key transformations, which are
+//! available without the `cipher` crate feature. The modes of operation
+//! defined in the standard are implemented in the `modes` module enabled by
+//! the `modes` crate feature.
+//!
 //! [BelT]: https://ru.wikipedia.org/wiki/BelT
 //! [STB 34.101.31-2020]: http://apmi.bsu.by/assets/files/std/belt-spec371.pdf
 
@@ -30,9 +36,16 @@ use core::{mem::swap, num::Wrapping};
 #[cfg(feature = "cipher")]
 mod cipher_impl;
 mod consts;
+mod hash;
+mod keyrep;
+#[cfg(feature = "modes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "modes")))]
+pub mod modes;
 
 #[cfg(feature = "cipher")]
 pub use cipher_impl::BeltBlock;
+pub use hash::{BeltHash, belt_compress, belt_hash};
+pub use keyrep::{belt_keyexpand, belt_keyrep};
 
 macro_rules! g {
     ($($name:ident: ($a:expr, $b:expr, $c:expr, $d:expr)),+) => {
@@ -112,21 +125,22 @@ pub fn belt_wblock_enc(data: &mut [u8], key: &[u32; 8]) -> Result<(), InvalidLen
         return Err(InvalidLengthError);
     }
 
-    let len = data.len();
-    let n = len.div_ceil(BLOCK_SIZE);
+    let mut ring = Ring::new(data);
+    let n = ring.len().div_ceil(BLOCK_SIZE);
+    let mut acc = ring.xor_sum();
     for i in 1..(2 * n + 1) {
-        let s = data[..len - 1]
-            .chunks_exact(BLOCK_SIZE)
-            .fold(Block::default(), xor);
-
-        data.copy_within(BLOCK_SIZE.., 0);
-        let (tail1, tail2) = data[len - 2 * BLOCK_SIZE..].split_at_mut(BLOCK_SIZE);
-        tail2.copy_from_slice(&s);
-
-        let s = belt_block_raw(to_u32(&s), key);
-        xor_set(tail1, &from_u32::<16>(&s));
-        xor_set(tail1, &i.to_le_bytes());
+        // s ← r₁ ⊕ … ⊕ rₙ₋₁
+        let s = acc;
+        // r ← r₂ ‖ … ‖ rₙ ‖ s
+        let r1 = ring.read(0);
+        ring.write(0, &s);
+        ring.rotate_left();
+        acc = xor(xor(acc, &r1), &ring.read(ring.window() - BLOCK_SIZE));
+        // r* ← r* ⊕ belt-block(s, K) ⊕ ⟨i⟩₁₂₈
+        let t = step_mask(&s, i, key);
+        ring.xor_tail(&t, &mut acc);
     }
+    ring.finish();
 
     Ok(())
 }
@@ -140,27 +154,143 @@ pub fn belt_wblock_dec(data: &mut [u8], key: &[u32; 8]) -> Result<(), InvalidLen
         return Err(InvalidLengthError);
     }
 
-    let len = data.len();
-    let n = len.div_ceil(BLOCK_SIZE);
+    let mut ring = Ring::new(data);
+    let n = ring.len().div_ceil(BLOCK_SIZE);
+    let mut acc = ring.xor_sum();
     for i in (1..(2 * n + 1)).rev() {
-        let tail_pos = len - BLOCK_SIZE;
-        let s = Block::try_from(&data[tail_pos..]).unwrap();
-        data.copy_within(..tail_pos, BLOCK_SIZE);
+        // s ← rₙ
+        let s = ring.read(ring.len() - BLOCK_SIZE);
+        // r* ← r* ⊕ belt-block(s, K) ⊕ ⟨i⟩₁₂₈
+        let t = step_mask(&s, i, key);
+        ring.xor_tail(&t, &mut acc);
+        // r ← (s ⊕ r₁ ⊕ … ⊕ rₙ₋₂) ‖ r₁ ‖ … ‖ rₙ₋₁
+        let r1 = xor(xor(acc, &s), &ring.read(ring.window() - BLOCK_SIZE));
+        ring.write(ring.len() - BLOCK_SIZE, &r1);
+        ring.rotate_right();
+        acc = s;
+    }
+    ring.finish();
+
+    Ok(())
+}
+
+/// Compute `belt-block(s, K) ⊕ ⟨i⟩₁₂₈`.
+#[inline(always)]
+fn step_mask(s: &Block, i: usize, key: &[u32; 8]) -> Block {
+    let mut t = from_u32::<16>(&belt_block_raw(to_u32(s), key));
+    xor_set(&mut t, &i.to_le_bytes());
+    t
+}
+
+/// Data of the `belt-wblock` functions viewed as a ring buffer.
+///
+/// The wide block functions shift the data by a block on every step. The
+/// ring buffer turns the shift into a change of the start position, and the
+/// XOR of the blocks `r₁, …, rₙ₋₁` is updated with the blocks which enter and
+/// leave it, so every step takes constant time.
+struct Ring<'a> {
+    data: &'a mut [u8],
+    /// Position of the first byte of the data in the buffer.
+    start: usize,
+}
+
+impl<'a> Ring<'a> {
+    fn new(data: &'a mut [u8]) -> Self {
+        Self { data, start: 0 }
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.data.len()
+    }
 
-        let s_enc = belt_block_raw(to_u32(&s), key);
-        xor_set(&mut data[tail_pos..], &from_u32::<16>(&s_enc));
-        xor_set(&mut data[tail_pos..], &i.to_le_bytes());
+    /// Length of the blocks `r₁, …, rₙ₋₁`.
+    #[inline(always)]
+    fn window(&self) -> usize {
+        (self.len() - 1) / BLOCK_SIZE * BLOCK_SIZE
+    }
 
-        let r1 = data[..len - 1]
+    /// XOR of the blocks `r₁, …, rₙ₋₁`.
+    fn xor_sum(&self) -> Block {
+        self.data[..self.window()]
             .chunks_exact(BLOCK_SIZE)
-            .skip(1)
-            .fold(s, xor);
-        data[..BLOCK_SIZE].copy_from_slice(&r1);
+            .fold(Block::default(), xor)
+    }
+
+    /// Position in the buffer of the byte at position `pos` of the data.
+    #[inline(always)]
+    fn pos(&self, pos: usize) -> usize {
+        let p = self.start + pos;
+        if p >= self.len() { p - self.len() } else { p }
+    }
+
+    /// Parts of the buffer holding the block at position `pos` of the data.
+    #[inline(always)]
+    fn block_mut(&mut self, pos: usize) -> (&mut [u8], &mut [u8]) {
+        let p = self.pos(pos);
+        let len = self.len();
+        if p + BLOCK_SIZE <= len {
+            (&mut self.data[p..][..BLOCK_SIZE], &mut [])
+        } else {
+            let (head, tail) = self.data.split_at_mut(p);
+            (tail, &mut head[..p + BLOCK_SIZE - len])
+        }
+    }
+
+    #[inline(always)]
+    fn read(&mut self, pos: usize) -> Block {
+        let (a, b) = self.block_mut(pos);
+        let mut res = Block::default();
+        let (ra, rb) = res.split_at_mut(a.len());
+        ra.copy_from_slice(a);
+        rb.copy_from_slice(b);
+        res
+    }
+
+    #[inline(always)]
+    fn write(&mut self, pos: usize, val: &Block) {
+        let (a, b) = self.block_mut(pos);
+        let (va, vb) = val.split_at(a.len());
+        a.copy_from_slice(va);
+        b.copy_from_slice(vb);
+    }
+
+    /// XOR `t` into the block `r*` preceding the last block of the data and
+    /// update the XOR `acc` of the blocks `r₁, …, rₙ₋₁` accordingly.
+    #[inline(always)]
+    fn xor_tail(&mut self, t: &Block, acc: &mut Block) {
+        let len = self.len();
+        let (a, b) = self.block_mut(len - 2 * BLOCK_SIZE);
+        a.iter_mut().chain(b).zip(t).for_each(|(a, b)| *a ^= b);
+        // `r*` lies within `r₁ ‖ … ‖ rₙ₋₁`, shifted from the block
+        // boundaries by the length of the last block
+        let shift = len % BLOCK_SIZE;
+        for (k, b) in t.iter().enumerate() {
+            acc[(shift + k) % BLOCK_SIZE] ^= b;
+        }
+    }
+
+    /// Move the start of the data a block forward.
+    #[inline(always)]
+    fn rotate_left(&mut self) {
+        self.start = self.pos(BLOCK_SIZE);
+    }
+
+    /// Move the start of the data a block backward.
+    #[inline(always)]
+    fn rotate_right(&mut self) {
+        self.start = self.pos(self.len() - BLOCK_SIZE);
+    }
+
+    /// Move the data to the start of the buffer.
+    fn finish(self) {
+        self.data.rotate_left(self.start);
     }
-    Ok(())
 }
 
-/// Error used when data smaller than 32 bytes is passed to the `belt-wblock` functions.
+/// Error used when data smaller than 32 bytes is passed to the `belt-wblock`
+/// functions, or keys of invalid length to the `belt-keyexpand` and
+/// `belt-keyrep` functions.
 #[derive(Debug, Copy, Clone)]
 pub struct InvalidLengthError;
 
@@ -0,0 +1,71 @@
+use super::{BLOCK_SIZE, Error, Iv, ctr, decrypt, encrypt, gf, xor};
+use crate::BeltBlock;
+use cipher::{Key, KeyInit};
+use core::fmt;
+
+/// Block disk encryption (`belt-bde`).
+///
+/// Every block of a sector is encrypted as `Yᵢ ← belt-block(Xᵢ ⊕ s, K) ⊕ s`,
+/// where the mask is initialized with the encrypted sector number and
+/// multiplied by the polynomial `x` in GF(2<sup>128</sup>) before every
+/// block. The sector number `n` is used as the IV `S = ⟨n⟩₁₂₈` of the
+/// standard.
+#[derive(Clone)]
+pub struct BeltBde {
+    cipher: BeltBlock,
+}
+
+impl BeltBde {
+    /// Create a new instance from a key.
+    pub fn new(key: &Key<BeltBlock>) -> Self {
+        Self::from_cipher(BeltBlock::new(key))
+    }
+
+    /// Create a new instance from an initialized cipher.
+    pub fn from_cipher(cipher: BeltBlock) -> Self {
+        Self { cipher }
+    }
+
+    /// Encrypt the sector `sector` stored in `buf` in place.
+    ///
+    /// Returns [`Error::InvalidLength`] if the length of `buf` is zero or not
+    /// a multiple of 16 bytes.
+    pub fn encrypt_sector(&self, sector: u128, buf: &mut [u8]) -> Result<(), Error> {
+        self.process(sector, buf, encrypt)
+    }
+
+    /// Decrypt the sector `sector` stored in `buf` in place.
+    ///
+    /// Returns [`Error::InvalidLength`] if the length of `buf` is zero or not
+    /// a multiple of 16 bytes.
+    pub fn decrypt_sector(&self, sector: u128, buf: &mut [u8]) -> Result<(), Error> {
+        self.process(sector, buf, decrypt)
+    }
+
+    fn process(
+        &self,
+        sector: u128,
+        buf: &mut [u8],
+        f: fn(&BeltBlock, &mut [u8]),
+    ) -> Result<(), Error> {
+        if buf.is_empty() || buf.len() % BLOCK_SIZE != 0 {
+            return Err(Error::InvalidLength);
+        }
+        let mut s = ctr::init(&self.cipher, &Iv::from(sector.to_le_bytes()));
+        for block in buf.chunks_exact_mut(BLOCK_SIZE) {
+            // s ← s * C
+            s = gf::mul_x(s);
+            let mask = s.to_le_bytes();
+            xor(block, &mask);
+            f(&self.cipher, block);
+            xor(block, &mask);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for BeltBde {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BeltBde { ... }")
+    }
+}
@@ -0,0 +1,81 @@
+use super::{
+    BLOCK_SIZE, Error, Iv, decrypt,
+    ecb::{split_tail, steal},
+    encrypt, xor,
+};
+use crate::BeltBlock;
+use cipher::{Key, KeyInit};
+use core::fmt;
+
+/// Cipher block chaining mode with ciphertext stealing (`belt-cbc`).
+#[derive(Clone)]
+pub struct BeltCbc {
+    cipher: BeltBlock,
+    iv: Iv,
+}
+
+impl BeltCbc {
+    /// Create a new instance from a key and an IV.
+    pub fn new(key: &Key<BeltBlock>, iv: &Iv) -> Self {
+        Self::from_cipher(BeltBlock::new(key), iv)
+    }
+
+    /// Create a new instance from an initialized cipher and an IV.
+    pub fn from_cipher(cipher: BeltBlock, iv: &Iv) -> Self {
+        Self { cipher, iv: *iv }
+    }
+
+    /// Encrypt `data` in place.
+    ///
+    /// Returns [`Error::InvalidLength`] if `data` is shorter than a block.
+    pub fn encrypt(&self, data: &mut [u8]) -> Result<(), Error> {
+        let tail = split_tail(data)?;
+        let (head, tail) = data.split_at_mut(tail);
+        let mut prev: &[u8] = &self.iv;
+        for block in head.chunks_exact_mut(BLOCK_SIZE) {
+            xor(block, prev);
+            encrypt(&self.cipher, block);
+            prev = block;
+        }
+        xor(&mut tail[..BLOCK_SIZE], prev);
+        encrypt(&self.cipher, &mut tail[..BLOCK_SIZE]);
+        if tail.len() != BLOCK_SIZE {
+            // Yₙ ← Lo(r), Yₙ₋₁ ← belt-block((Xₙ ⊕ Lo(r)) ‖ Hi(r), K)
+            let (block, last) = tail.split_at_mut(BLOCK_SIZE);
+            xor(last, block);
+            steal(tail);
+            encrypt(&self.cipher, &mut tail[..BLOCK_SIZE]);
+        }
+        Ok(())
+    }
+
+    /// Decrypt `data` in place.
+    ///
+    /// Returns [`Error::InvalidLength`] if `data` is shorter than a block.
+    pub fn decrypt(&self, data: &mut [u8]) -> Result<(), Error> {
+        let tail = split_tail(data)?;
+        let (head, tail) = data.split_at_mut(tail);
+        let mut prev = self.iv;
+        for block in head.chunks_exact_mut(BLOCK_SIZE) {
+            let next = Iv::try_from(&*block).unwrap();
+            decrypt(&self.cipher, block);
+            xor(block, &prev);
+            prev = next;
+        }
+        decrypt(&self.cipher, &mut tail[..BLOCK_SIZE]);
+        if tail.len() != BLOCK_SIZE {
+            let (block, last) = tail.split_at_mut(BLOCK_SIZE);
+            xor(block, last);
+            steal(tail);
+            decrypt(&self.cipher, &mut tail[..BLOCK_SIZE]);
+        }
+        xor(&mut tail[..BLOCK_SIZE], &prev);
+        Ok(())
+    }
+}
+
+impl fmt::Debug for BeltCbc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BeltCbc { ... }")
+    }
+}
@@ -0,0 +1,115 @@
+use super::{BLOCK_SIZE, Iv};
+use crate::BeltBlock;
+use cipher::{BlockCipherEncrypt, Key, KeyInit};
+use core::fmt;
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::Zeroize;
+
+/// State shared by the CFB encryptor and decryptor.
+#[derive(Clone)]
+struct Cfb {
+    cipher: BeltBlock,
+    /// Ciphertext block being fed back, keystream block generated from the
+    /// previous one, and the position of the first unused keystream byte.
+    feedback: Iv,
+    buf: Iv,
+    pos: usize,
+}
+
+impl Cfb {
+    fn new(cipher: BeltBlock, iv: &Iv) -> Self {
+        Self {
+            cipher,
+            feedback: *iv,
+            buf: Default::default(),
+            pos: BLOCK_SIZE,
+        }
+    }
+
+    fn process(&mut self, mut data: &mut [u8], decrypt: bool) {
+        while !data.is_empty() {
+            if self.pos == BLOCK_SIZE {
+                self.buf = self.feedback;
+                self.cipher.encrypt_block(&mut self.buf);
+                self.pos = 0;
+            }
+            let len = data.len().min(BLOCK_SIZE - self.pos);
+            let (head, rest) = data.split_at_mut(len);
+            let gamma = &self.buf[self.pos..][..len];
+            let feedback = &mut self.feedback[self.pos..][..len];
+            for ((b, g), f) in head.iter_mut().zip(gamma).zip(feedback) {
+                if decrypt {
+                    *f = *b;
+                    *b ^= g;
+                } else {
+                    *b ^= g;
+                    *f = *b;
+                }
+            }
+            self.pos += len;
+            data = rest;
+        }
+    }
+}
+
+impl Drop for Cfb {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        self.buf.zeroize();
+    }
+}
+
+/// Cipher feedback mode encryptor (`belt-cfb`).
+#[derive(Clone)]
+pub struct BeltCfbEnc(Cfb);
+
+impl BeltCfbEnc {
+    /// Create a new instance from a key and an IV.
+    pub fn new(key: &Key<BeltBlock>, iv: &Iv) -> Self {
+        Self::from_cipher(BeltBlock::new(key), iv)
+    }
+
+    /// Create a new instance from an initialized cipher and an IV.
+    pub fn from_cipher(cipher: BeltBlock, iv: &Iv) -> Self {
+        Self(Cfb::new(cipher, iv))
+    }
+
+    /// Encrypt `data` in place.
+    pub fn encrypt(&mut self, data: &mut [u8]) {
+        self.0.process(data, false);
+    }
+}
+
+impl fmt::Debug for BeltCfbEnc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BeltCfbEnc { ... }")
+    }
+}
+
+/// Cipher feedback mode decryptor (`belt-cfb`).
+#[derive(Clone)]
+pub struct BeltCfbDec(Cfb);
+
+impl BeltCfbDec {
+    /// Create a new instance from a key and an IV.
+    pub fn new(key: &Key<BeltBlock>, iv: &Iv) -> Self {
+        Self::from_cipher(BeltBlock::new(key), iv)
+    }
+
+    /// Create a new instance from an initialized cipher and an IV.
+    pub fn from_cipher(cipher: BeltBlock, iv: &Iv) -> Self {
+        Self(Cfb::new(cipher, iv))
+    }
+
+    /// Decrypt `data` in place.
+    pub fn decrypt(&mut self, data: &mut [u8]) {
+        self.0.process(data, true);
+    }
+}
+
+impl fmt::Debug for BeltCfbDec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BeltCfbDec { ... }")
+    }
+}
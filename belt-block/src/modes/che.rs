@@ -0,0 +1,95 @@
+use super::{Tag, ctr, dwp::compute_tag, gf};
+use crate::BeltBlock;
+use aead::{AeadCore, AeadInOut, Error, Nonce, TagPosition, inout::InOutBuf};
+use cipher::{
+    BlockCipherEncrypt,
+    common::{InnerInit, InnerUser},
+    consts::{U8, U16},
+};
+use core::fmt;
+use subtle::ConstantTimeEq;
+
+/// Authenticated encryption with associated data (`belt-che`).
+///
+/// Unlike [`BeltDwp`][super::BeltDwp], the counter is updated with the
+/// multiplication by the polynomial `x` in GF(2<sup>128</sup>) instead of
+/// the addition, and the encrypted nonce is used both as the initial counter
+/// and as the hash key.
+#[derive(Clone)]
+pub struct BeltChe {
+    cipher: BeltBlock,
+}
+
+impl InnerUser for BeltChe {
+    type Inner = BeltBlock;
+}
+
+impl InnerInit for BeltChe {
+    fn inner_init(cipher: BeltBlock) -> Self {
+        Self { cipher }
+    }
+}
+
+impl AeadCore for BeltChe {
+    type NonceSize = U16;
+    type TagSize = U8;
+    const TAG_POSITION: TagPosition = TagPosition::Postfix;
+}
+
+impl AeadInOut for BeltChe {
+    fn encrypt_inout_detached(
+        &self,
+        nonce: &Nonce<Self>,
+        associated_data: &[u8],
+        mut buffer: InOutBuf<'_, '_, u8>,
+    ) -> Result<Tag, Error> {
+        let s = ctr::init(&self.cipher, nonce);
+        self.apply_keystream(s, buffer.reborrow());
+        compute_tag(&self.cipher, s, associated_data, buffer.get_out())
+    }
+
+    fn decrypt_inout_detached(
+        &self,
+        nonce: &Nonce<Self>,
+        associated_data: &[u8],
+        buffer: InOutBuf<'_, '_, u8>,
+        tag: &Tag,
+    ) -> Result<(), Error> {
+        let s = ctr::init(&self.cipher, nonce);
+        // The tag covers the ciphertext, so it is checked before decryption
+        let expected = compute_tag(&self.cipher, s, associated_data, buffer.get_in())?;
+        if expected.ct_eq(tag).into() {
+            self.apply_keystream(s, buffer);
+            Ok(())
+        } else {
+            Err(Error)
+        }
+    }
+}
+
+impl BeltChe {
+    /// XOR the buffer with the keystream `belt-block(s, K)`, where the
+    /// counter is updated as `s ← (s * x) ⊕ ⟨1⟩₁₂₈` before every block.
+    fn apply_keystream(&self, mut s: u128, buffer: InOutBuf<'_, '_, u8>) {
+        let (blocks, mut tail) = buffer.into_chunks::<U16>();
+        let mut next = || {
+            s = gf::mul_x(s) ^ 1;
+            let mut block = s.to_le_bytes().into();
+            self.cipher.encrypt_block(&mut block);
+            block
+        };
+        for mut block in blocks {
+            block.xor_in2out(&next());
+        }
+        if !tail.is_empty() {
+            let gamma = next();
+            tail.xor_in2out(&gamma[..tail.len()]);
+        }
+    }
+}
+
+impl fmt::Debug for BeltChe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BeltChe { ... }")
+    }
+}
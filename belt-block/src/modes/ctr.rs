@@ -0,0 +1,107 @@
+use super::{BLOCK_SIZE, Iv};
+use crate::BeltBlock;
+use cipher::{BlockCipherEncrypt, InOutBuf, Key, KeyInit, StreamCipher, StreamCipherError};
+use core::fmt;
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::Zeroize;
+
+/// Counter mode (`belt-ctr`).
+///
+/// The counter is initialized with the encrypted IV and incremented modulo
+/// 2<sup>128</sup> as a little-endian integer before every block.
+#[derive(Clone)]
+pub struct BeltCtr {
+    cipher: BeltBlock,
+    ctr: u128,
+    /// Keystream block and the position of its first unused byte.
+    buf: Iv,
+    pos: usize,
+}
+
+impl BeltCtr {
+    /// Create a new instance from a key and an IV.
+    pub fn new(key: &Key<BeltBlock>, iv: &Iv) -> Self {
+        Self::from_cipher(BeltBlock::new(key), iv)
+    }
+
+    /// Create a new instance from an initialized cipher and an IV.
+    pub fn from_cipher(cipher: BeltBlock, iv: &Iv) -> Self {
+        let ctr = init(&cipher, iv);
+        Self {
+            cipher,
+            ctr,
+            buf: Default::default(),
+            pos: BLOCK_SIZE,
+        }
+    }
+}
+
+/// Compute the initial counter `s ← belt-block(S, K)`.
+pub(super) fn init(cipher: &BeltBlock, iv: &Iv) -> u128 {
+    let mut s = *iv;
+    cipher.encrypt_block(&mut s);
+    u128::from_le_bytes(s.0)
+}
+
+/// Increment the counter and generate the next keystream block.
+#[inline]
+pub(super) fn next_block(cipher: &BeltBlock, ctr: &mut u128) -> Iv {
+    *ctr = ctr.wrapping_add(1);
+    let mut block = ctr.to_le_bytes().into();
+    cipher.encrypt_block(&mut block);
+    block
+}
+
+/// XOR `buf` with the keystream starting at the block after `ctr`.
+pub(super) fn apply_keystream(cipher: &BeltBlock, mut ctr: u128, buf: InOutBuf<'_, '_, u8>) {
+    let (blocks, mut tail) = buf.into_chunks::<cipher::consts::U16>();
+    for mut block in blocks {
+        block.xor_in2out(&next_block(cipher, &mut ctr));
+    }
+    if !tail.is_empty() {
+        let gamma = next_block(cipher, &mut ctr);
+        tail.xor_in2out(&gamma[..tail.len()]);
+    }
+}
+
+impl StreamCipher for BeltCtr {
+    fn check_remaining(&self, _data_len: usize) -> Result<(), StreamCipherError> {
+        Ok(())
+    }
+
+    fn unchecked_apply_keystream_inout(&mut self, mut buf: InOutBuf<'_, '_, u8>) {
+        while !buf.is_empty() {
+            if self.pos == BLOCK_SIZE {
+                self.buf = next_block(&self.cipher, &mut self.ctr);
+                self.pos = 0;
+            }
+            let len = buf.len().min(BLOCK_SIZE - self.pos);
+            let (mut head, rest) = buf.split_at(len);
+            head.xor_in2out(&self.buf[self.pos..][..len]);
+            self.pos += len;
+            buf = rest;
+        }
+    }
+
+    fn unchecked_write_keystream(&mut self, buf: &mut [u8]) {
+        buf.fill(0);
+        self.unchecked_apply_keystream(buf);
+    }
+}
+
+impl fmt::Debug for BeltCtr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BeltCtr { ... }")
+    }
+}
+
+impl Drop for BeltCtr {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        {
+            self.ctr.zeroize();
+            self.buf.zeroize();
+        }
+    }
+}
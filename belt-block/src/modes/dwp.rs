@@ -0,0 +1,108 @@
+use super::{Tag, ctr, gf::Hasher};
+use crate::BeltBlock;
+use aead::{AeadCore, AeadInOut, Error, Nonce, TagPosition, inout::InOutBuf};
+use cipher::{
+    BlockCipherEncrypt,
+    common::{InnerInit, InnerUser},
+    consts::{U8, U16},
+};
+use core::fmt;
+use subtle::ConstantTimeEq;
+
+/// Authenticated encryption with associated data (`belt-dwp`).
+///
+/// The message is encrypted in the counter mode, see [`BeltCtr`][super::BeltCtr],
+/// and the associated data and the ciphertext are authenticated with a
+/// polynomial hash in GF(2<sup>128</sup>). The nonce is the IV `S` of the
+/// standard.
+#[derive(Clone)]
+pub struct BeltDwp {
+    cipher: BeltBlock,
+}
+
+impl InnerUser for BeltDwp {
+    type Inner = BeltBlock;
+}
+
+impl InnerInit for BeltDwp {
+    fn inner_init(cipher: BeltBlock) -> Self {
+        Self { cipher }
+    }
+}
+
+impl AeadCore for BeltDwp {
+    type NonceSize = U16;
+    type TagSize = U8;
+    const TAG_POSITION: TagPosition = TagPosition::Postfix;
+}
+
+impl AeadInOut for BeltDwp {
+    fn encrypt_inout_detached(
+        &self,
+        nonce: &Nonce<Self>,
+        associated_data: &[u8],
+        mut buffer: InOutBuf<'_, '_, u8>,
+    ) -> Result<Tag, Error> {
+        let (s, r) = self.init(nonce);
+        ctr::apply_keystream(&self.cipher, s, buffer.reborrow());
+        compute_tag(&self.cipher, r, associated_data, buffer.get_out())
+    }
+
+    fn decrypt_inout_detached(
+        &self,
+        nonce: &Nonce<Self>,
+        associated_data: &[u8],
+        buffer: InOutBuf<'_, '_, u8>,
+        tag: &Tag,
+    ) -> Result<(), Error> {
+        let (s, r) = self.init(nonce);
+        // The tag covers the ciphertext, so it is checked before decryption
+        let expected = compute_tag(&self.cipher, r, associated_data, buffer.get_in())?;
+        if expected.ct_eq(tag).into() {
+            ctr::apply_keystream(&self.cipher, s, buffer);
+            Ok(())
+        } else {
+            Err(Error)
+        }
+    }
+}
+
+impl BeltDwp {
+    /// Compute the initial counter `s ← belt-block(S, K)` and the hash key
+    /// `r ← belt-block(s, K)`.
+    fn init(&self, nonce: &Nonce<Self>) -> (u128, u128) {
+        let s = ctr::init(&self.cipher, nonce);
+        let mut r = s.to_le_bytes().into();
+        self.cipher.encrypt_block(&mut r);
+        (s, u128::from_le_bytes(r.0))
+    }
+}
+
+/// Compute the tag `Lo(belt-block(t, K), 64)` of the associated data and the
+/// ciphertext hashed with the key `r`.
+///
+/// Returns an error if the bit length of the associated data or the
+/// ciphertext does not fit into 64 bits.
+pub(super) fn compute_tag(
+    cipher: &BeltBlock,
+    r: u128,
+    associated_data: &[u8],
+    ciphertext: &[u8],
+) -> Result<Tag, Error> {
+    let bits = |len: usize| u64::try_from(len).ok()?.checked_mul(8);
+    let ad_bits = bits(associated_data.len()).ok_or(Error)?;
+    let ct_bits = bits(ciphertext.len()).ok_or(Error)?;
+
+    let mut hasher = Hasher::new(r);
+    hasher.update(associated_data);
+    hasher.update(ciphertext);
+    let mut t = hasher.finalize(ad_bits, ct_bits).to_le_bytes().into();
+    cipher.encrypt_block(&mut t);
+    Ok(Tag::try_from(&t[..8]).unwrap())
+}
+
+impl fmt::Debug for BeltDwp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BeltDwp { ... }")
+    }
+}
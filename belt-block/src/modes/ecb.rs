@@ -0,0 +1,79 @@
+use super::{BLOCK_SIZE, Error, decrypt, encrypt};
+use crate::BeltBlock;
+use cipher::{Key, KeyInit};
+use core::fmt;
+
+/// Electronic codebook mode with ciphertext stealing (`belt-ecb`).
+#[derive(Clone)]
+pub struct BeltEcb {
+    cipher: BeltBlock,
+}
+
+impl BeltEcb {
+    /// Create a new instance from a key.
+    pub fn new(key: &Key<BeltBlock>) -> Self {
+        Self::from_cipher(BeltBlock::new(key))
+    }
+
+    /// Create a new instance from an initialized cipher.
+    pub fn from_cipher(cipher: BeltBlock) -> Self {
+        Self { cipher }
+    }
+
+    /// Encrypt `data` in place.
+    ///
+    /// Returns [`Error::InvalidLength`] if `data` is shorter than a block.
+    pub fn encrypt(&self, data: &mut [u8]) -> Result<(), Error> {
+        let tail = split_tail(data)?;
+        let (head, tail) = data.split_at_mut(tail);
+        for block in head.chunks_exact_mut(BLOCK_SIZE) {
+            encrypt(&self.cipher, block);
+        }
+        encrypt(&self.cipher, &mut tail[..BLOCK_SIZE]);
+        if tail.len() != BLOCK_SIZE {
+            // (Yₙ₋₁ ‖ Yₙ) ← belt-block(Xₙ ‖ r, K) ‖ Yₙ₋₁*
+            steal(tail);
+            encrypt(&self.cipher, &mut tail[..BLOCK_SIZE]);
+        }
+        Ok(())
+    }
+
+    /// Decrypt `data` in place.
+    ///
+    /// Returns [`Error::InvalidLength`] if `data` is shorter than a block.
+    pub fn decrypt(&self, data: &mut [u8]) -> Result<(), Error> {
+        let tail = split_tail(data)?;
+        let (head, tail) = data.split_at_mut(tail);
+        for block in head.chunks_exact_mut(BLOCK_SIZE) {
+            decrypt(&self.cipher, block);
+        }
+        decrypt(&self.cipher, &mut tail[..BLOCK_SIZE]);
+        if tail.len() != BLOCK_SIZE {
+            steal(tail);
+            decrypt(&self.cipher, &mut tail[..BLOCK_SIZE]);
+        }
+        Ok(())
+    }
+}
+
+/// Position of the last complete block of `data`, which is processed
+/// together with the incomplete block following it.
+pub(super) fn split_tail(data: &[u8]) -> Result<usize, Error> {
+    if data.len() < BLOCK_SIZE {
+        return Err(Error::InvalidLength);
+    }
+    Ok((data.len() - BLOCK_SIZE) / BLOCK_SIZE * BLOCK_SIZE)
+}
+
+/// Swap the incomplete block at the end of `tail` with the beginning of the
+/// complete block preceding it.
+pub(super) fn steal(tail: &mut [u8]) {
+    let (block, last) = tail.split_at_mut(BLOCK_SIZE);
+    block[..last.len()].swap_with_slice(last);
+}
+
+impl fmt::Debug for BeltEcb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BeltEcb { ... }")
+    }
+}
@@ -0,0 +1,167 @@
+//! Arithmetic in the field GF(2^128) of STB 34.101.31.
+//!
+//! The 128-bit words of the standard are read as little-endian integers
+//! where bit `i` is the coefficient of `x^i`, and are multiplied modulo
+//! `x^128 + x^7 + x^2 + x + 1`. Besides the universal hash of `belt-dwp` and
+//! `belt-che`, multiplication by `x` updates the tweaks of `belt-bde` and the
+//! counter of `belt-che`.
+
+/// Initial value of the hash: the first 16 bytes of the S-box `H`.
+const INIT: [u8; 16] = [
+    0xB1, 0x94, 0xBA, 0xC8, 0x0A, 0x08, 0xF5, 0x3B, 0x36, 0x6D, 0x00, 0x8E, 0x58, 0x4A, 0x5D, 0xE4,
+];
+
+/// Multiply `a` by `x`.
+#[inline]
+pub(super) fn mul_x(a: u128) -> u128 {
+    let carry = 0u128.wrapping_sub(a >> 127);
+    (a << 1) ^ (carry & 0x87)
+}
+
+/// Low 64 bits of the carry-less product of 32-bit polynomials, computed
+/// with integer multiplications of numbers with holes (from BearSSL). At most
+/// 8 terms are summed at every bit, so the sums never overflow into the next
+/// bit kept by the masks, and the running time does not depend on the
+/// operands.
+#[inline(always)]
+fn bmul32(x: u32, y: u32) -> u64 {
+    const M0: u32 = 0x1111_1111;
+    const M1: u32 = 0x2222_2222;
+    const M2: u32 = 0x4444_4444;
+    const M3: u32 = 0x8888_8888;
+
+    let m = |x: u32, y: u32| u64::from(x) * u64::from(y);
+    let (x0, x1, x2, x3) = (x & M0, x & M1, x & M2, x & M3);
+    let (y0, y1, y2, y3) = (y & M0, y & M1, y & M2, y & M3);
+
+    let z0 = m(x0, y0) ^ m(x1, y3) ^ m(x2, y2) ^ m(x3, y1);
+    let z1 = m(x0, y1) ^ m(x1, y0) ^ m(x2, y3) ^ m(x3, y2);
+    let z2 = m(x0, y2) ^ m(x1, y1) ^ m(x2, y0) ^ m(x3, y3);
+    let z3 = m(x0, y3) ^ m(x1, y2) ^ m(x2, y1) ^ m(x3, y0);
+
+    (z0 & 0x1111_1111_1111_1111)
+        | (z1 & 0x2222_2222_2222_2222)
+        | (z2 & 0x4444_4444_4444_4444)
+        | (z3 & 0x8888_8888_8888_8888)
+}
+
+/// Carry-less product of 64-bit polynomials with Karatsuba multiplication.
+#[inline(always)]
+fn clmul64(a: u64, b: u64) -> u128 {
+    let (a0, a1) = (a as u32, (a >> 32) as u32);
+    let (b0, b1) = (b as u32, (b >> 32) as u32);
+    let lo = bmul32(a0, b0);
+    let hi = bmul32(a1, b1);
+    let mid = bmul32(a0 ^ a1, b0 ^ b1) ^ lo ^ hi;
+    u128::from(lo) ^ (u128::from(mid) << 32) ^ (u128::from(hi) << 64)
+}
+
+/// Multiply `a` and `b`.
+pub(super) fn mul(a: u128, b: u128) -> u128 {
+    let (a0, a1) = (a as u64, (a >> 64) as u64);
+    let (b0, b1) = (b as u64, (b >> 64) as u64);
+
+    // Karatsuba multiplication
+    let lo = clmul64(a0, b0);
+    let hi = clmul64(a1, b1);
+    let mid = clmul64(a0 ^ a1, b0 ^ b1) ^ lo ^ hi;
+    let lo = lo ^ (mid << 64);
+    let hi = hi ^ (mid >> 64);
+
+    // Reduce by x^128 = x^7 + x^2 + x + 1, the bits shifted out of the
+    // first folding are folded once more
+    let over = (hi >> 127) ^ (hi >> 126) ^ (hi >> 121);
+    let hi = hi ^ over;
+    lo ^ hi ^ (hi << 1) ^ (hi << 2) ^ (hi << 7)
+}
+
+/// Universal hash `t ← (t ⊕ Xᵢ) * r` of the associated data and the
+/// ciphertext shared by `belt-dwp` and `belt-che`.
+pub(super) struct Hasher {
+    r: u128,
+    t: u128,
+}
+
+impl Hasher {
+    /// Create a new hasher with the key `r` and the initial value
+    /// `t ← B194BAC80A08F53B366D008E584A5DE4`.
+    pub(super) fn new(r: u128) -> Self {
+        Self {
+            r,
+            t: u128::from_le_bytes(INIT),
+        }
+    }
+
+    /// Absorb `data` padded with zeros to a multiple of the block size.
+    pub(super) fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut block = [0u8; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.push(u128::from_le_bytes(block));
+        }
+    }
+
+    #[inline]
+    fn push(&mut self, x: u128) {
+        self.t = mul(self.t ^ x, self.r);
+    }
+
+    /// Absorb the bit lengths of the associated data and the ciphertext and
+    /// return the hash.
+    pub(super) fn finalize(mut self, ad_bits: u64, ct_bits: u64) -> u128 {
+        self.push(u128::from(ad_bits) | (u128::from(ct_bits) << 64));
+        self.t
+    }
+}
+
+impl Drop for Hasher {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        {
+            use cipher::zeroize::Zeroize;
+            self.r.zeroize();
+            self.t.zeroize();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{INIT, mul, mul_x};
+
+    /// Bitwise multiplication with Horner's rule over the bits of `b`.
+    fn mul_ref(a: u128, b: u128) -> u128 {
+        let mut c = 0;
+        for i in (0..128).rev() {
+            c = mul_x(c);
+            if (b >> i) & 1 == 1 {
+                c ^= a;
+            }
+        }
+        c
+    }
+
+    #[test]
+    fn mul_matches_reference() {
+        let mut a = u128::from_le_bytes(INIT);
+        let mut b = !0u128;
+        for i in 0..1000 {
+            assert_eq!(mul(a, b), mul_ref(a, b), "a = {a:032x}, b = {b:032x}");
+            a = a.rotate_left(13) ^ mul_x(b);
+            b = b.wrapping_mul(0x9E37_79B9_7F4A_7C15_F39C_C060_5CED_C835) ^ i;
+        }
+        for i in 0..128 {
+            for j in 0..128 {
+                assert_eq!(mul(1 << i, 1 << j), mul_ref(1 << i, 1 << j));
+            }
+        }
+    }
+
+    /// Square of the initial value of the hash, a regression value computed
+    /// with a bitwise implementation
+    #[test]
+    fn mul_regression() {
+        let a = u128::from_le_bytes(INIT);
+        assert_eq!(mul(a, a), 0xA13D_8B2E_5394_D6A5_BBEC_454E_0425_C13C);
+    }
+}
@@ -0,0 +1,72 @@
+use super::{BLOCK_SIZE, Error};
+use crate::{BeltBlock, belt_wblock_dec, belt_wblock_enc};
+use cipher::{Block, Key, KeyInit};
+use core::fmt;
+use subtle::ConstantTimeEq;
+
+/// Header `I` of the wrapped key.
+pub type Header = Block<BeltBlock>;
+
+/// Key wrapping (`belt-kwp`).
+///
+/// The key `X` of at least 16 bytes is wrapped together with the header `I`
+/// as `Y = belt-wblock(X ‖ I, K)`, and the header is checked when the key is
+/// unwrapped. Both operations work in place on a buffer with the key
+/// followed by a block for the header, i.e. 16 bytes longer than the key.
+#[derive(Clone)]
+pub struct BeltKwp {
+    cipher: BeltBlock,
+}
+
+impl BeltKwp {
+    /// Create a new instance from a key.
+    pub fn new(key: &Key<BeltBlock>) -> Self {
+        Self::from_cipher(BeltBlock::new(key))
+    }
+
+    /// Create a new instance from an initialized cipher.
+    pub fn from_cipher(cipher: BeltBlock) -> Self {
+        Self { cipher }
+    }
+
+    /// Wrap the key stored in `buf` except its last 16 bytes, which are
+    /// overwritten with `header`.
+    ///
+    /// Returns [`Error::InvalidLength`] if `buf` is shorter than 32 bytes.
+    pub fn wrap_in_place(&self, buf: &mut [u8], header: &Header) -> Result<(), Error> {
+        let pos = header_pos(buf)?;
+        buf[pos..].copy_from_slice(header);
+        belt_wblock_enc(buf, &self.cipher.key).map_err(|_| Error::InvalidLength)
+    }
+
+    /// Unwrap the wrapped key stored in `buf`. On success, the key is
+    /// stored in `buf` except its last 16 bytes.
+    ///
+    /// Returns [`Error::InvalidLength`] if `buf` is shorter than 32 bytes and
+    /// [`Error::Verification`] if the header does not match `header`, in
+    /// which case `buf` is zeroed.
+    pub fn unwrap_in_place(&self, buf: &mut [u8], header: &Header) -> Result<(), Error> {
+        let pos = header_pos(buf)?;
+        belt_wblock_dec(buf, &self.cipher.key).map_err(|_| Error::InvalidLength)?;
+        if buf[pos..].ct_eq(header).into() {
+            Ok(())
+        } else {
+            buf.fill(0);
+            Err(Error::Verification)
+        }
+    }
+}
+
+/// Position of the header in a buffer of valid length.
+fn header_pos(buf: &[u8]) -> Result<usize, Error> {
+    if buf.len() < 2 * BLOCK_SIZE {
+        return Err(Error::InvalidLength);
+    }
+    Ok(buf.len() - BLOCK_SIZE)
+}
+
+impl fmt::Debug for BeltKwp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BeltKwp { ... }")
+    }
+}
@@ -0,0 +1,116 @@
+use super::{BLOCK_SIZE, Error, Iv, xor};
+use crate::{BeltBlock, from_u32, to_u32};
+use cipher::{Array, BlockCipherEncrypt, Key, KeyInit, consts::U8};
+use core::fmt;
+use subtle::ConstantTimeEq;
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::Zeroize;
+
+/// Tag of [`BeltMac`], [`BeltDwp`][super::BeltDwp] and
+/// [`BeltChe`][super::BeltChe].
+pub type Tag = Array<u8, U8>;
+
+/// Message authentication code (`belt-mac`).
+#[derive(Clone)]
+pub struct BeltMac {
+    cipher: BeltBlock,
+    /// Encrypted zero block `r` used to mask the last block.
+    r: Iv,
+    state: Iv,
+    /// Last block of the message processed so far and its length. It is kept
+    /// until more data arrives, since the last block is processed
+    /// differently.
+    buf: Iv,
+    pos: usize,
+}
+
+impl BeltMac {
+    /// Create a new instance from a key.
+    pub fn new(key: &Key<BeltBlock>) -> Self {
+        Self::from_cipher(BeltBlock::new(key))
+    }
+
+    /// Create a new instance from an initialized cipher.
+    pub fn from_cipher(cipher: BeltBlock) -> Self {
+        let mut r = Iv::default();
+        cipher.encrypt_block(&mut r);
+        Self {
+            cipher,
+            r,
+            state: Default::default(),
+            buf: Default::default(),
+            pos: 0,
+        }
+    }
+
+    /// Update the MAC state with `data`.
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.pos == BLOCK_SIZE {
+                // s ← belt-block(s ⊕ Xᵢ, K)
+                xor(&mut self.state, &self.buf);
+                self.cipher.encrypt_block(&mut self.state);
+                self.pos = 0;
+            }
+            let len = data.len().min(BLOCK_SIZE - self.pos);
+            let (head, rest) = data.split_at(len);
+            self.buf[self.pos..][..len].copy_from_slice(head);
+            self.pos += len;
+            data = rest;
+        }
+    }
+
+    /// Compute the MAC of the data processed so far.
+    pub fn finalize(mut self) -> Tag {
+        let mask = if self.pos == BLOCK_SIZE {
+            phi1(&self.r)
+        } else {
+            self.buf[self.pos] = 0x80;
+            self.buf[self.pos + 1..].fill(0);
+            phi2(&self.r)
+        };
+        xor(&mut self.state, &self.buf);
+        xor(&mut self.state, &mask);
+        self.cipher.encrypt_block(&mut self.state);
+        Tag::try_from(&self.state[..8]).unwrap()
+    }
+
+    /// Verify `tag` of the data processed so far in constant time.
+    pub fn verify(self, tag: &Tag) -> Result<(), Error> {
+        if self.finalize().ct_eq(tag).into() {
+            Ok(())
+        } else {
+            Err(Error::Verification)
+        }
+    }
+}
+
+/// φ₁(u₁ ‖ u₂ ‖ u₃ ‖ u₄) = u₂ ‖ u₃ ‖ u₄ ‖ (u₁ ⊕ u₂)
+fn phi1(r: &Iv) -> [u8; 16] {
+    let [u1, u2, u3, u4] = to_u32(r);
+    from_u32(&[u2, u3, u4, u1 ^ u2])
+}
+
+/// φ₂(u₁ ‖ u₂ ‖ u₃ ‖ u₄) = (u₁ ⊕ u₄) ‖ u₁ ‖ u₂ ‖ u₃
+fn phi2(r: &Iv) -> [u8; 16] {
+    let [u1, u2, u3, u4] = to_u32(r);
+    from_u32(&[u1 ^ u4, u1, u2, u3])
+}
+
+impl fmt::Debug for BeltMac {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BeltMac { ... }")
+    }
+}
+
+impl Drop for BeltMac {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        {
+            self.r.zeroize();
+            self.state.zeroize();
+            self.buf.zeroize();
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Modes of operation of [`BeltBlock`] defined in STB 34.101.31.
+//!
+//! - [`BeltEcb`] and [`BeltCbc`] (`belt-ecb` and `belt-cbc`): the electronic
+//!   codebook and cipher block chaining modes. Data of at least one block is
+//!   processed at once, and data which is not a multiple of the block size
+//!   is handled with ciphertext stealing, so the ciphertext has the same
+//!   length as the plaintext.
+//! - [`BeltCfbEnc`] and [`BeltCfbDec`] (`belt-cfb`): the cipher feedback
+//!   mode.
+//! - [`BeltCtr`] (`belt-ctr`): the counter mode. It implements
+//!   [`StreamCipher`][cipher::StreamCipher].
+//! - [`BeltMac`] (`belt-mac`): the message authentication code with 64-bit
+//!   tags.
+//! - [`BeltDwp`] and [`BeltChe`] (`belt-dwp` and `belt-che`): authenticated
+//!   encryption with associated data and 64-bit tags. Both implement the
+//!   [`aead`] traits.
+//! - [`BeltKwp`] (`belt-kwp`): key wrapping with a 128-bit header, based on
+//!   [`belt_wblock_enc`][crate::belt_wblock_enc].
+//! - [`BeltBde`] and [`BeltSde`] (`belt-bde` and `belt-sde`): disk
+//!   encryption of sectors identified by their numbers, without expansion of
+//!   the data. `belt-sde` encrypts the whole sector as a wide block, so a
+//!   change of any byte of the sector changes all of its ciphertext.
+//!
+//! The CFB and CTR modes and the MAC process data in pieces of any size.
+//!
+//! # Example
+//! ```
+//! use belt_block::modes::{
+//!     BeltCbc, BeltDwp, BeltMac,
+//!     aead::{AeadInOut, KeyInit},
+//! };
+//!
+//! let key = [0x42; 32].into();
+//! let iv = [0x24; 16].into();
+//! let plaintext = *b"message longer than a block";
+//!
+//! let mut buf = plaintext;
+//! let cbc = BeltCbc::new(&key, &iv);
+//! cbc.encrypt(&mut buf).unwrap();
+//! cbc.decrypt(&mut buf).unwrap();
+//! assert_eq!(buf, plaintext);
+//!
+//! let mut mac = BeltMac::new(&key);
+//! mac.update(&buf);
+//! let tag = mac.clone().finalize();
+//! mac.verify(&tag).unwrap();
+//!
+//! let dwp = BeltDwp::new(&key);
+//! let tag = dwp
+//!     .encrypt_inout_detached(&iv, b"header", (&mut buf[..]).into())
+//!     .unwrap();
+//! dwp.decrypt_inout_detached(&iv, b"header", (&mut buf[..]).into(), &tag)
+//!     .unwrap();
+//! assert_eq!(buf, plaintext);
+//! ```
+
+pub use aead;
+
+use crate::BeltBlock;
+use cipher::{Block, BlockCipherDecrypt, BlockCipherEncrypt};
+use core::fmt;
+
+mod bde;
+mod cbc;
+mod cfb;
+mod che;
+mod ctr;
+mod dwp;
+mod ecb;
+mod gf;
+mod kwp;
+mod mac;
+mod sde;
+
+pub use bde::BeltBde;
+pub use cbc::BeltCbc;
+pub use cfb::{BeltCfbDec, BeltCfbEnc};
+pub use che::BeltChe;
+pub use ctr::BeltCtr;
+pub use dwp::BeltDwp;
+pub use ecb::BeltEcb;
+pub use kwp::{BeltKwp, Header};
+pub use mac::{BeltMac, Tag};
+pub use sde::BeltSde;
+
+/// Initialization vector (synchronization message) of the modes.
+pub type Iv = Block<BeltBlock>;
+
+const BLOCK_SIZE: usize = 16;
+
+/// Encrypt the 16-byte `block` in place.
+#[inline]
+fn encrypt(cipher: &BeltBlock, block: &mut [u8]) {
+    let block: &mut Block<BeltBlock> = block.try_into().unwrap();
+    cipher.encrypt_block(block);
+}
+
+/// Decrypt the 16-byte `block` in place.
+#[inline]
+fn decrypt(cipher: &BeltBlock, block: &mut [u8]) {
+    let block: &mut Block<BeltBlock> = block.try_into().unwrap();
+    cipher.decrypt_block(block);
+}
+
+#[inline]
+fn xor(dst: &mut [u8], src: &[u8]) {
+    dst.iter_mut().zip(src).for_each(|(a, b)| *a ^= b);
+}
+
+/// Mode error.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// Data or buffer has an invalid length.
+    InvalidLength,
+    /// MAC or key wrapping header verification failed.
+    Verification,
+}
+
+impl fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(match self {
+            Self::InvalidLength => "invalid data length",
+            Self::Verification => "verification error",
+        })
+    }
+}
+
+impl core::error::Error for Error {}
@@ -5,7 +5,10 @@ use belt_block::{
     BeltBlock,
     cipher::{BlockCipherDecrypt, BlockCipherEncrypt, KeyInit},
 };
-use belt_block::{belt_block_raw, belt_wblock_dec, belt_wblock_enc};
+use belt_block::{
+    BeltHash, InvalidLengthError, belt_block_raw, belt_compress, belt_hash, belt_keyexpand,
+    belt_keyrep, belt_wblock_dec, belt_wblock_enc,
+};
 use hex_literal::hex;
 
 #[test]
@@ -125,6 +128,200 @@ fn belt_wblock() {
     }
 }
 
+#[test]
+fn belt_compress_hash() {
+    // Example of belt-compress
+    let x = hex!(
+        "B194BAC8 0A08F53B 366D008E 584A5DE4"
+        "8504FA9D 1BB6C7AC 252E72C2 02FDCE0D"
+        "5BE3D612 17B96181 FE6786AD 716B890B"
+        "5CB0C0FF 33C356B8 35C405AE D8E07F99"
+    );
+    let s = hex!("46FE7425 C9B181EB 41DFEE3E 72163D5A");
+    let y = hex!(
+        "ED2F5481 D593F40D 87FCE37D 6BC1A2E1"
+        "B7D1A2CC 975C82D3 C0497488 C90D99D8"
+    );
+    let res = belt_compress(&to_u32(&x));
+    assert_eq!(res, (to_u32(&s), to_u32(&y)));
+
+    // Examples of belt-hash
+    let x = &x[..48];
+    let tests = [
+        (
+            &x[..13],
+            hex!(
+                "ABEF9725 D4C5A835 97A367D1 4494CC25"
+                "42F20F65 9DDFECC9 61A3EC55 0CBA8C75"
+            ),
+        ),
+        (
+            &x[..32],
+            hex!(
+                "749E4C36 53AECE5E 48DB4761 227742EB"
+                "6DBE13F4 A80F7BEF F1A9CF8D 10EE7786"
+            ),
+        ),
+        (
+            x,
+            hex!(
+                "9D02EE44 6FB6A29F E5C982D4 B13AF9D3"
+                "E90861BC 4CEF27CF 306BFB0B 174A154A"
+            ),
+        ),
+    ];
+    for (x, y) in tests {
+        assert_eq!(belt_hash(x), y);
+        let mut hasher = BeltHash::new();
+        for chunk in x.chunks(5) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize(), y);
+    }
+}
+
+#[test]
+fn belt_keyrep_vectors() {
+    // Examples of belt-keyrep
+    let x = hex!(
+        "E9DEE72C 8F0C0FA6 2DDB49F4 6F739647"
+        "06075316 ED247A37 39CBA383 03A98BF6"
+    );
+    let d = hex!("01000000 00000000 00000000");
+    let i = hex!("5BE3D612 17B96181 FE6786AD 716B890B");
+    let y = hex!(
+        "76E166E6 AB21256B 6739397B 672B8796"
+        "14B81CF0 5955FC3A B09343A7 45C48F77"
+    );
+    let mut buf = [0u8; 32];
+    belt_keyrep(&x, &d, &i, &mut buf).unwrap();
+    assert_eq!(buf, y);
+    belt_keyrep(&x, &d, &i, &mut buf[..24]).unwrap();
+    assert_eq!(
+        buf[..24],
+        hex!("9A2532A1 8CBAF145 398D5A95 FEEA6C82 5B9C1971 56A00275")
+    );
+    belt_keyrep(&x, &d, &i, &mut buf[..16]).unwrap();
+    assert_eq!(buf[..16], hex!("6BBBC233 6670D31A B83DAA90 D52C0541"));
+
+    // The standard has examples only for 32-byte keys, so all key lengths
+    // are checked against the definition
+    // Y ← Lo(σ₂(r ‖ D ‖ I ‖ belt-keyexpand(X)), m) with the words `r` of the
+    // S-box `H` given in the standard for the lengths of `X` and `Y`
+    for (n, m, r) in [
+        (16, 16, hex!("B194BAC8")),
+        (24, 16, hex!("5BE3D612")),
+        (24, 24, hex!("5CB0C0FF")),
+        (32, 16, hex!("E12BDC1A")),
+        (32, 24, hex!("C1AB7638")),
+        (32, 32, hex!("F33C657B")),
+    ] {
+        let mut block = [0u32; 16];
+        block[0] = u32::from_le_bytes(r);
+        block[1..4].copy_from_slice(&to_u32::<3>(&d));
+        block[4..8].copy_from_slice(&to_u32::<4>(&i));
+        block[8..].copy_from_slice(&belt_keyexpand(&x[..n]).unwrap());
+        let (_, y) = belt_compress(&block);
+        let y: Vec<u8> = y.iter().flat_map(|w| w.to_le_bytes()).collect();
+        belt_keyrep(&x[..n], &d, &i, &mut buf[..m]).unwrap();
+        assert_eq!(buf[..m], y[..m], "key length {n}, output length {m}");
+    }
+
+    assert!(matches!(
+        belt_keyrep(&x[..16], &d, &i, &mut buf[..24]),
+        Err(InvalidLengthError)
+    ));
+    assert!(matches!(
+        belt_keyrep(&x, &d, &i, &mut buf[..20]),
+        Err(InvalidLengthError)
+    ));
+
+    // belt-keyexpand
+    let k = belt_keyexpand(&x[..16]).unwrap();
+    assert_eq!(k[..4], k[4..]);
+    let k = belt_keyexpand(&x[..24]).unwrap();
+    assert_eq!(
+        k,
+        to_u32(&hex!(
+            "E9DEE72C 8F0C0FA6 2DDB49F4 6F739647"
+            "06075316 ED247A37 4B09A17E 8450BF66"
+        ))
+    );
+    assert_eq!(belt_keyexpand(&x).unwrap(), to_u32(&x));
+    assert!(belt_keyexpand(&x[..20]).is_err());
+}
+
+/// Compare the wide block functions with the original implementation, which
+/// shifts the whole buffer on every step, on large buffers.
+#[test]
+fn belt_wblock_large() {
+    let k = to_u32(&hex!(
+        "E9DEE72C 8F0C0FA6 2DDB49F4 6F739647"
+        "06075316 ED247A37 39CBA383 03A98BF6"
+    ));
+    let x: Vec<u8> = (0..16 * 1024 + 15)
+        .map(|i: u32| (i.wrapping_mul(0x9E37_79B9) >> 24) as u8)
+        .collect();
+    for len in [512, 1000, 4096, 4096 + 1, 4096 + 15, 16 * 1024 + 15] {
+        let mut t = x[..len].to_vec();
+        let mut expected = t.clone();
+        belt_wblock_enc(&mut t, &k).unwrap();
+        reference::belt_wblock_enc(&mut expected, &k);
+        assert_eq!(t, expected);
+
+        belt_wblock_dec(&mut t, &k).unwrap();
+        assert_eq!(t, x[..len]);
+        reference::belt_wblock_dec(&mut expected, &k);
+        assert_eq!(expected, x[..len]);
+    }
+}
+
+/// Original quadratic-time implementation of the wide block functions.
+mod reference {
+    use belt_block::belt_block_raw;
+
+    fn xor(mut a: [u8; 16], b: &[u8]) -> [u8; 16] {
+        a.iter_mut().zip(b).for_each(|(a, b)| *a ^= b);
+        a
+    }
+
+    fn enc_block(s: &[u8; 16], key: &[u32; 8]) -> [u8; 16] {
+        let y = belt_block_raw(super::to_u32(s), key);
+        let mut res = [0u8; 16];
+        for (dst, w) in res.chunks_exact_mut(4).zip(y) {
+            dst.copy_from_slice(&w.to_le_bytes());
+        }
+        res
+    }
+
+    pub(super) fn belt_wblock_enc(data: &mut [u8], key: &[u32; 8]) {
+        let len = data.len();
+        let n = len.div_ceil(16);
+        for i in 1..(2 * n + 1) {
+            let s = data[..len - 1].chunks_exact(16).fold([0; 16], xor);
+            data.copy_within(16.., 0);
+            let (tail1, tail2) = data[len - 32..].split_at_mut(16);
+            tail2.copy_from_slice(&s);
+            let t = xor(enc_block(&s, key), &i.to_le_bytes());
+            tail1.copy_from_slice(&xor(t, tail1));
+        }
+    }
+
+    pub(super) fn belt_wblock_dec(data: &mut [u8], key: &[u32; 8]) {
+        let len = data.len();
+        let n = len.div_ceil(16);
+        for i in (1..(2 * n + 1)).rev() {
+            let s: [u8; 16] = data[len - 16..].try_into().unwrap();
+            data.copy_within(..len - 16, 16);
+            let t = xor(enc_block(&s, key), &i.to_le_bytes());
+            let tail: [u8; 16] = data[len - 16..].try_into().unwrap();
+            data[len - 16..].copy_from_slice(&xor(t, &tail));
+            let r1 = data[..len - 1].chunks_exact(16).skip(1).fold(s, xor);
+            data[..16].copy_from_slice(&r1);
+        }
+    }
+}
+
 fn to_u32<const N: usize>(src: &[u8]) -> [u32; N] {
     assert_eq!(src.len(), 4 * N);
     let mut res = [0u32; N];
@@ -0,0 +1,16 @@
+#![feature(test)]
+extern crate test;
+
+use cipher::{block_decryptor_bench, block_encryptor_bench};
+use gift_cipher::Gift64;
+
+block_encryptor_bench!(
+    Key: Gift64,
+    gift64_encrypt_block,
+    gift64_encrypt_blocks,
+);
+block_decryptor_bench!(
+    Key: Gift64,
+    gift64_decrypt_block,
+    gift64_decrypt_blocks,
+);
@@ -6,3 +6,12 @@ pub(crate) const GIFT_RC: [u32; 40] = [
     0x80000021, 0x10000080, 0x0001c000, 0x51000002, 0x03010180, 0x8000002e, 0x10088800, 0x60012000,
     0x40500002, 0x01030080, 0x80000006, 0x10008808, 0xc001a000, 0x14500002, 0x01020181, 0x8000001a,
 ];
+
+/// Gift-64 round constants in the bitsliced representation of two blocks,
+/// including the constant bit at position 63
+pub(crate) const GIFT64_RC: [u32; 28] = [
+    0x80018001, 0x80038003, 0x80078007, 0x800f800f, 0x801f801f, 0x803e803e, 0x803d803d, 0x803b803b,
+    0x80378037, 0x802f802f, 0x801e801e, 0x803c803c, 0x80398039, 0x80338033, 0x80278027, 0x800e800e,
+    0x801d801d, 0x803a803a, 0x80358035, 0x802b802b, 0x80168016, 0x802c802c, 0x80188018, 0x80308030,
+    0x80218021, 0x80028002, 0x80058005, 0x800b800b,
+];
@@ -0,0 +1,143 @@
+//! Bitsliced Gift-64 processing two blocks at once.
+//!
+//! Every state word holds one bit slice (bit `j` of every nibble) of both
+//! blocks: the first block in the lower 16 bits and the second block in the
+//! upper 16 bits. In this representation the bit permutation keeps every bit
+//! in its slice and can be computed with a few shifts and masks.
+
+use crate::consts::GIFT64_RC;
+use crate::primitives::{
+    byte_ror_4, half_ror_4, half_ror_8, half_ror_12, inv_sbox, sbox, swapmovesingle,
+};
+
+#[inline]
+fn dup(x: u16) -> u32 {
+    (x as u32) | ((x as u32) << 16)
+}
+
+pub fn precompute_rkeys(key: &[u8; 16]) -> [u32; 56] {
+    let mut k = [0u16; 8];
+    for (i, k) in k.iter_mut().enumerate() {
+        *k = u16::from_be_bytes([key[14 - 2 * i], key[15 - 2 * i]]);
+    }
+
+    let mut rkey = [0u32; 56];
+    for r in 0..28 {
+        rkey[2 * r] = dup(k[0]);
+        rkey[2 * r + 1] = dup(k[1]);
+        let (k0, k1) = (k[0], k[1]);
+        k.copy_within(2.., 0);
+        k[6] = k0.rotate_right(12);
+        k[7] = k1.rotate_right(2);
+    }
+    rkey
+}
+
+/// Move bit `4 * i + j` of every 32-bit half of a block to bit `8 * j + i`.
+#[inline]
+fn transpose(x: &mut u32) {
+    swapmovesingle(x, 0x0a0a0a0a, 3);
+    swapmovesingle(x, 0x00cc00cc, 6);
+    swapmovesingle(x, 0x0000f0f0, 12);
+    swapmovesingle(x, 0x0000ff00, 8);
+}
+
+#[inline]
+fn inv_transpose(x: &mut u32) {
+    swapmovesingle(x, 0x0000ff00, 8);
+    swapmovesingle(x, 0x0000f0f0, 12);
+    swapmovesingle(x, 0x00cc00cc, 6);
+    swapmovesingle(x, 0x0a0a0a0a, 3);
+}
+
+#[inline]
+pub(crate) fn packing(state: &mut [u32; 4], b0: &[u8; 8], b1: &[u8; 8]) {
+    *state = [0; 4];
+    for (b, shift) in [(b0, 0), (b1, 16)] {
+        let x = u64::from_be_bytes(*b);
+        let mut lo = x as u32;
+        let mut hi = (x >> 32) as u32;
+        transpose(&mut lo);
+        transpose(&mut hi);
+        for (j, s) in state.iter_mut().enumerate() {
+            let slice = ((lo >> (8 * j)) & 0xff) | (((hi >> (8 * j)) & 0xff) << 8);
+            *s |= slice << shift;
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn unpacking(state: &[u32; 4], b0: &mut [u8; 8], b1: &mut [u8; 8]) {
+    for (b, shift) in [(b0, 0), (b1, 16)] {
+        let mut lo = 0u32;
+        let mut hi = 0u32;
+        for (j, s) in state.iter().enumerate() {
+            let slice = s >> shift;
+            lo |= (slice & 0xff) << (8 * j);
+            hi |= ((slice >> 8) & 0xff) << (8 * j);
+        }
+        inv_transpose(&mut lo);
+        inv_transpose(&mut hi);
+        *b = ((lo as u64) | ((hi as u64) << 32)).to_be_bytes();
+    }
+}
+
+/// Transpose every slice as a 4x4 bit matrix and reverse its rows, which is
+/// the part of the bit permutation shared by all slices.
+#[inline]
+fn perm_common(x: u32) -> u32 {
+    let mut x = x;
+    swapmovesingle(&mut x, 0x0a0a0a0a, 3);
+    swapmovesingle(&mut x, 0x00cc00cc, 6);
+    byte_ror_4(&x)
+}
+
+#[inline]
+fn inv_perm_common(x: u32) -> u32 {
+    let mut x = byte_ror_4(&x);
+    swapmovesingle(&mut x, 0x00cc00cc, 6);
+    swapmovesingle(&mut x, 0x0a0a0a0a, 3);
+    x
+}
+
+#[inline]
+fn perm(s: &mut [u32; 4]) {
+    s[0] = half_ror_4(&perm_common(s[0]));
+    s[1] = perm_common(s[1]);
+    s[2] = half_ror_12(&perm_common(s[2]));
+    s[3] = half_ror_8(&perm_common(s[3]));
+}
+
+#[inline]
+fn inv_perm(s: &mut [u32; 4]) {
+    s[0] = inv_perm_common(half_ror_12(&s[0]));
+    s[1] = inv_perm_common(s[1]);
+    s[2] = inv_perm_common(half_ror_4(&s[2]));
+    s[3] = inv_perm_common(half_ror_8(&s[3]));
+}
+
+#[inline]
+pub(crate) fn encrypt(state: &mut [u32; 4], rkey: &[u32; 56]) {
+    for (rk, rc) in rkey.chunks_exact(2).zip(GIFT64_RC) {
+        let [s0, s1, s2, s3] = state;
+        sbox(s0, s1, s2, s3);
+        state.swap(0, 3);
+        perm(state);
+        state[0] ^= rk[0];
+        state[1] ^= rk[1];
+        state[3] ^= rc;
+    }
+}
+
+#[inline]
+pub(crate) fn decrypt(state: &mut [u32; 4], rkey: &[u32; 56]) {
+    for (rk, rc) in rkey.chunks_exact(2).zip(GIFT64_RC).rev() {
+        state[0] ^= rk[0];
+        state[1] ^= rk[1];
+        state[3] ^= rc;
+        inv_perm(state);
+        state.swap(0, 3);
+        let [s0, s1, s2, s3] = state;
+        inv_sbox(s3, s1, s2, s0);
+    }
+}
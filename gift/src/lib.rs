@@ -1,5 +1,10 @@
 //! Pure Rust implementation of the [Gift] block cipher.
 //!
+//! Both [`Gift64`] and [`Gift128`] use bitsliced implementations without
+//! secret-dependent table lookups or branches. `Gift128` is based on the
+//! [fixsliced] representation by Adomnicai and Peyrin. Both ciphers process
+//! two blocks per call when encrypting or decrypting several blocks at once.
+//!
 //! # ⚠️ Security Warning: Hazmat!
 //!
 //! This crate implements only the low-level block cipher function, and is intended
@@ -31,6 +36,7 @@
 //! ```
 //!
 //! [Gift]: https://eprint.iacr.org/2017/622.pdf
+//! [fixsliced]: https://eprint.iacr.org/2020/412
 
 #![no_std]
 #![doc(
@@ -44,8 +50,8 @@
 use cipher::{
     AlgorithmName, Block, BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherDecrypt,
     BlockCipherEncBackend, BlockCipherEncClosure, BlockCipherEncrypt, BlockSizeUser, InOut, Key,
-    KeyInit, KeySizeUser, ParBlocksSizeUser,
-    consts::{U1, U16},
+    KeyInit, KeySizeUser, ParBlocks, ParBlocksSizeUser,
+    consts::{U2, U8, U16},
 };
 use core::fmt;
 
@@ -54,12 +60,20 @@ use cipher::zeroize::{Zeroize, ZeroizeOnDrop};
 
 pub use cipher;
 
+#[cfg(feature = "cofb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cofb")))]
+pub mod cofb;
 mod consts;
+mod gift64;
 mod key_schedule;
 mod primitives;
 
 use consts::GIFT_RC;
-use primitives::{inv_quintuple_round, packing, quintuple_round, unpacking};
+#[cfg(feature = "cofb")]
+use primitives::u32big;
+use primitives::{
+    Word, inv_quintuple_round, packing, packing_par, quintuple_round, unpacking, unpacking_par,
+};
 
 /// Gift-128 block cipher instance.
 #[derive(Clone)]
@@ -67,6 +81,40 @@ pub struct Gift128 {
     k: [u32; 80],
 }
 
+impl Gift128 {
+    #[inline]
+    fn encrypt_state<W: Word>(&self, state: &mut [W; 4]) {
+        for i in (0..40).step_by(5) {
+            quintuple_round(state, &self.k[i * 2..], &GIFT_RC[i..]);
+        }
+    }
+
+    #[inline]
+    fn decrypt_state<W: Word>(&self, state: &mut [W; 4]) {
+        let mut i: usize = 35;
+        while i > 0 {
+            inv_quintuple_round(state, &self.k[i * 2..], &GIFT_RC[i..]);
+            i -= 5;
+        }
+        inv_quintuple_round(state, &self.k[i * 2..], &GIFT_RC[i..]);
+    }
+
+    /// Encrypt a block loaded in the bitsliced order used by GIFT-COFB, which
+    /// matches the fixsliced state without packing.
+    #[cfg(feature = "cofb")]
+    #[inline]
+    pub(crate) fn encrypt_bitsliced(&self, block: &mut Block<Self>) {
+        let mut state = [0u32; 4];
+        for (s, b) in state.iter_mut().zip(block.chunks_exact(4)) {
+            *s = u32big(b);
+        }
+        self.encrypt_state(&mut state);
+        for (s, b) in state.iter().zip(block.chunks_exact_mut(4)) {
+            b.copy_from_slice(&s.to_be_bytes());
+        }
+    }
+}
+
 impl KeySizeUser for Gift128 {
     type KeySize = U16;
 }
@@ -84,7 +132,7 @@ impl BlockSizeUser for Gift128 {
 }
 
 impl ParBlocksSizeUser for Gift128 {
-    type ParBlocksSize = U1;
+    type ParBlocksSize = U2;
 }
 
 impl BlockCipherEncrypt for Gift128 {
@@ -100,11 +148,19 @@ impl BlockCipherEncBackend for Gift128 {
         let b = block.get_in();
         let mut state = [0u32; 4];
         packing(&mut state, b.into());
-        for i in (0..40).step_by(5) {
-            quintuple_round(&mut state, &self.k[i * 2..], &GIFT_RC[i..]);
-        }
+        self.encrypt_state(&mut state);
         unpacking(&state, block.get_out().into());
     }
+
+    #[inline]
+    fn encrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let b = blocks.get_in();
+        let mut state = [0u64; 4];
+        packing_par(&mut state, (&b[0]).into(), (&b[1]).into());
+        self.encrypt_state(&mut state);
+        let [b0, b1] = blocks.get_out().as_mut();
+        unpacking_par(&state, b0.into(), b1.into());
+    }
 }
 
 impl BlockCipherDecrypt for Gift128 {
@@ -120,14 +176,19 @@ impl BlockCipherDecBackend for Gift128 {
         let b = block.get_in();
         let mut state = [0u32; 4];
         packing(&mut state, b.into());
-        let mut i: usize = 35;
-        while i > 0 {
-            inv_quintuple_round(&mut state, &self.k[i * 2..], &GIFT_RC[i..]);
-            i -= 5;
-        }
-        inv_quintuple_round(&mut state, &self.k[i * 2..], &GIFT_RC[i..]);
+        self.decrypt_state(&mut state);
         unpacking(&state, block.get_out().into());
     }
+
+    #[inline]
+    fn decrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let b = blocks.get_in();
+        let mut state = [0u64; 4];
+        packing_par(&mut state, (&b[0]).into(), (&b[1]).into());
+        self.decrypt_state(&mut state);
+        let [b0, b1] = blocks.get_out().as_mut();
+        unpacking_par(&state, b0.into(), b1.into());
+    }
 }
 
 impl AlgorithmName for Gift128 {
@@ -151,3 +212,105 @@ impl Drop for Gift128 {
 
 #[cfg(feature = "zeroize")]
 impl ZeroizeOnDrop for Gift128 {}
+
+/// Gift-64 block cipher instance.
+#[derive(Clone)]
+pub struct Gift64 {
+    k: [u32; 56],
+}
+
+impl KeySizeUser for Gift64 {
+    type KeySize = U16;
+}
+
+impl KeyInit for Gift64 {
+    fn new(key: &Key<Self>) -> Self {
+        Self {
+            k: gift64::precompute_rkeys(key.into()),
+        }
+    }
+}
+
+impl BlockSizeUser for Gift64 {
+    type BlockSize = U8;
+}
+
+impl ParBlocksSizeUser for Gift64 {
+    type ParBlocksSize = U2;
+}
+
+impl BlockCipherEncrypt for Gift64 {
+    #[inline]
+    fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = Self::BlockSize>) {
+        f.call(self)
+    }
+}
+
+impl BlockCipherEncBackend for Gift64 {
+    #[inline]
+    fn encrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
+        let mut state = [0u32; 4];
+        gift64::packing(&mut state, block.get_in().into(), &[0; 8]);
+        gift64::encrypt(&mut state, &self.k);
+        gift64::unpacking(&state, block.get_out().into(), &mut [0; 8]);
+    }
+
+    #[inline]
+    fn encrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let b = blocks.get_in();
+        let mut state = [0u32; 4];
+        gift64::packing(&mut state, (&b[0]).into(), (&b[1]).into());
+        gift64::encrypt(&mut state, &self.k);
+        let [b0, b1] = blocks.get_out().as_mut();
+        gift64::unpacking(&state, b0.into(), b1.into());
+    }
+}
+
+impl BlockCipherDecrypt for Gift64 {
+    #[inline]
+    fn decrypt_with_backend(&self, f: impl BlockCipherDecClosure<BlockSize = Self::BlockSize>) {
+        f.call(self)
+    }
+}
+
+impl BlockCipherDecBackend for Gift64 {
+    #[inline]
+    fn decrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
+        let mut state = [0u32; 4];
+        gift64::packing(&mut state, block.get_in().into(), &[0; 8]);
+        gift64::decrypt(&mut state, &self.k);
+        gift64::unpacking(&state, block.get_out().into(), &mut [0; 8]);
+    }
+
+    #[inline]
+    fn decrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let b = blocks.get_in();
+        let mut state = [0u32; 4];
+        gift64::packing(&mut state, (&b[0]).into(), (&b[1]).into());
+        gift64::decrypt(&mut state, &self.k);
+        let [b0, b1] = blocks.get_out().as_mut();
+        gift64::unpacking(&state, b0.into(), b1.into());
+    }
+}
+
+impl AlgorithmName for Gift64 {
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Gift64")
+    }
+}
+
+impl fmt::Debug for Gift64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Gift64 { ... }")
+    }
+}
+
+impl Drop for Gift64 {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        self.k.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Gift64 {}
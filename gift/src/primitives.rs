@@ -1,56 +1,103 @@
+use core::ops::{BitAnd, BitOr, BitXor, BitXorAssign, Not, Shl, Shr};
+
+/// Word holding a bitsliced state row for one or more blocks.
+///
+/// `u32` holds a single block, `u64` holds two blocks in its 32-bit lanes.
+pub(crate) trait Word:
+    Copy
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + BitXorAssign
+    + Not<Output = Self>
+    + Shl<u8, Output = Self>
+    + Shr<u8, Output = Self>
+{
+    /// Broadcast `x` to every lane.
+    fn splat(x: u32) -> Self;
+
+    /// Rotate every 32-bit lane right by `n` bits.
+    fn ror(self, n: u8) -> Self;
+}
+
+impl Word for u32 {
+    #[inline(always)]
+    fn splat(x: u32) -> Self {
+        x
+    }
+
+    #[inline(always)]
+    fn ror(self, n: u8) -> Self {
+        self.rotate_right(n as u32)
+    }
+}
+
+impl Word for u64 {
+    #[inline(always)]
+    fn splat(x: u32) -> Self {
+        (x as u64) | ((x as u64) << 32)
+    }
+
+    #[inline(always)]
+    fn ror(self, n: u8) -> Self {
+        let lo = Self::splat(u32::MAX >> n);
+        ((self >> n) & lo) | ((self << (32 - n)) & !lo)
+    }
+}
+
 #[inline]
 pub(crate) fn u32big(x: &[u8]) -> u32 {
     ((x[0] as u32) << 24) | ((x[1] as u32) << 16) | ((x[2] as u32) << 8) | (x[3] as u32)
 }
 
 #[inline]
-pub(crate) fn ror(x: &u32, y: &u32) -> u32 {
-    ((*x) >> (*y)) | (*x << (32 - (*y)))
+pub(crate) fn ror<W: Word>(x: &W, y: &u8) -> W {
+    x.ror(*y)
 }
 
 #[inline]
-pub(crate) fn byte_ror_2(x: &u32) -> u32 {
-    (((x) >> 2) & 0x3f3f3f3f) | (((x) & 0x03030303) << 6)
+pub(crate) fn byte_ror_2<W: Word>(&x: &W) -> W {
+    (((x) >> 2) & W::splat(0x3f3f3f3f)) | (((x) & W::splat(0x03030303)) << 6)
 }
 
 #[inline]
-pub(crate) fn byte_ror_4(x: &u32) -> u32 {
-    (((x) >> 4) & 0x0f0f0f0f) | (((x) & 0x0f0f0f0f) << 4)
+pub(crate) fn byte_ror_4<W: Word>(&x: &W) -> W {
+    (((x) >> 4) & W::splat(0x0f0f0f0f)) | (((x) & W::splat(0x0f0f0f0f)) << 4)
 }
 
 #[inline]
-pub(crate) fn byte_ror_6(x: &u32) -> u32 {
-    (((x) >> 6) & 0x03030303) | (((x) & 0x3f3f3f3f) << 2)
+pub(crate) fn byte_ror_6<W: Word>(&x: &W) -> W {
+    (((x) >> 6) & W::splat(0x03030303)) | (((x) & W::splat(0x3f3f3f3f)) << 2)
 }
 
 #[inline]
-pub(crate) fn half_ror_4(&x: &u32) -> u32 {
-    (((x) >> 4) & 0x0fff0fff) | (((x) & 0x000f000f) << 12)
+pub(crate) fn half_ror_4<W: Word>(&x: &W) -> W {
+    (((x) >> 4) & W::splat(0x0fff0fff)) | (((x) & W::splat(0x000f000f)) << 12)
 }
 
 #[inline]
-pub(crate) fn half_ror_8(x: &u32) -> u32 {
-    (((x) >> 8) & 0x00ff00ff) | (((x) & 0x00ff00ff) << 8)
+pub(crate) fn half_ror_8<W: Word>(&x: &W) -> W {
+    (((x) >> 8) & W::splat(0x00ff00ff)) | (((x) & W::splat(0x00ff00ff)) << 8)
 }
 
 #[inline]
-pub(crate) fn half_ror_12(&x: &u32) -> u32 {
-    (((x) >> 12) & 0x000f000f) | (((x) & 0x0fff0fff) << 4)
+pub(crate) fn half_ror_12<W: Word>(&x: &W) -> W {
+    (((x) >> 12) & W::splat(0x000f000f)) | (((x) & W::splat(0x0fff0fff)) << 4)
 }
 
 #[inline]
-pub(crate) fn nibble_ror_1(x: &u32) -> u32 {
-    (((x) >> 1) & 0x77777777) | (((x) & 0x11111111) << 3)
+pub(crate) fn nibble_ror_1<W: Word>(&x: &W) -> W {
+    (((x) >> 1) & W::splat(0x77777777)) | (((x) & W::splat(0x11111111)) << 3)
 }
 
 #[inline]
-pub(crate) fn nibble_ror_2(x: &u32) -> u32 {
-    (((x) >> 2) & 0x33333333) | (((x) & 0x33333333) << 2)
+pub(crate) fn nibble_ror_2<W: Word>(&x: &W) -> W {
+    (((x) >> 2) & W::splat(0x33333333)) | (((x) & W::splat(0x33333333)) << 2)
 }
 
 #[inline]
-pub(crate) fn nibble_ror_3(&x: &u32) -> u32 {
-    (((x) >> 3) & 0x11111111) | (((x) & 0x77777777) << 1)
+pub(crate) fn nibble_ror_3<W: Word>(&x: &W) -> W {
+    (((x) >> 3) & W::splat(0x11111111)) | (((x) & W::splat(0x77777777)) << 1)
 }
 
 #[inline]
@@ -61,27 +108,27 @@ pub(crate) fn swapmove(a: &mut u32, b: &mut u32, mask: u32, n: u8) {
 }
 
 #[inline]
-pub(crate) fn swapmovesingle(a: &mut u32, mask: u32, n: u8) {
-    let tmp = (*a ^ (*a >> n)) & mask;
+pub(crate) fn swapmovesingle<W: Word>(a: &mut W, mask: u32, n: u8) {
+    let tmp = (*a ^ (*a >> n)) & W::splat(mask);
     *a ^= tmp;
     *a ^= tmp << n;
 }
 
 #[inline]
-pub(crate) fn sbox(s0: &mut u32, s1: &mut u32, s2: &mut u32, s3: &mut u32) {
+pub(crate) fn sbox<W: Word>(s0: &mut W, s1: &mut W, s2: &mut W, s3: &mut W) {
     *s1 ^= *s0 & *s2;
     *s0 ^= *s1 & *s3;
     *s2 ^= *s0 | *s1;
     *s3 ^= *s2;
     *s1 ^= *s3;
-    *s3 ^= 0xffffffff;
+    *s3 = !*s3;
     *s2 ^= *s0 & *s1;
 }
 
 #[inline]
-pub(crate) fn inv_sbox(s0: &mut u32, s1: &mut u32, s2: &mut u32, s3: &mut u32) {
+pub(crate) fn inv_sbox<W: Word>(s0: &mut W, s1: &mut W, s2: &mut W, s3: &mut W) {
     *s2 ^= *s3 & *s1;
-    *s0 ^= 0xffffffff;
+    *s0 = !*s0;
     *s1 ^= *s0;
     *s0 ^= *s2;
     *s2 ^= *s3 | *s1;
@@ -163,8 +210,28 @@ pub(crate) fn unpacking(state: &[u32], output: &mut [u8; 16]) {
     output[15] = (s0 & 0xff) as u8;
 }
 
+/// Pack two blocks into the 32-bit lanes of the state.
+#[inline]
+pub(crate) fn packing_par(state: &mut [u64; 4], b0: &[u8; 16], b1: &[u8; 16]) {
+    let mut s0 = [0u32; 4];
+    let mut s1 = [0u32; 4];
+    packing(&mut s0, b0);
+    packing(&mut s1, b1);
+    for (s, (s0, s1)) in state.iter_mut().zip(s0.into_iter().zip(s1)) {
+        *s = (s0 as u64) | ((s1 as u64) << 32);
+    }
+}
+
+#[inline]
+pub(crate) fn unpacking_par(state: &[u64; 4], b0: &mut [u8; 16], b1: &mut [u8; 16]) {
+    let s0 = state.map(|s| s as u32);
+    let s1 = state.map(|s| (s >> 32) as u32);
+    unpacking(&s0, b0);
+    unpacking(&s1, b1);
+}
+
 #[inline]
-pub(crate) fn quintuple_round(state: &mut [u32; 4], rkey: &[u32], rconst: &[u32]) {
+pub(crate) fn quintuple_round<W: Word>(state: &mut [W; 4], rkey: &[u32], rconst: &[u32]) {
     let mut s0 = state[0];
     let mut s1 = state[1];
     let mut s2 = state[2];
@@ -173,39 +240,39 @@ pub(crate) fn quintuple_round(state: &mut [u32; 4], rkey: &[u32], rconst: &[u32]
     s3 = nibble_ror_1(&s3);
     s1 = nibble_ror_2(&s1);
     s2 = nibble_ror_3(&s2);
-    s1 ^= rkey[0];
-    s2 ^= rkey[1];
-    s0 ^= rconst[0];
+    s1 ^= W::splat(rkey[0]);
+    s2 ^= W::splat(rkey[1]);
+    s0 ^= W::splat(rconst[0]);
     sbox(&mut s3, &mut s1, &mut s2, &mut s0);
     s0 = half_ror_4(&s0);
     s1 = half_ror_8(&s1);
     s2 = half_ror_12(&s2);
-    s1 ^= rkey[2];
-    s2 ^= rkey[3];
-    s3 ^= rconst[1];
+    s1 ^= W::splat(rkey[2]);
+    s2 ^= W::splat(rkey[3]);
+    s3 ^= W::splat(rconst[1]);
     sbox(&mut s0, &mut s1, &mut s2, &mut s3);
     s3 = ror(&s3, &16);
     s2 = ror(&s2, &16);
     swapmovesingle(&mut s1, 0x55555555, 1);
     swapmovesingle(&mut s2, 0x00005555, 1);
     swapmovesingle(&mut s3, 0x55550000, 1);
-    s1 ^= rkey[4];
-    s2 ^= rkey[5];
-    s0 ^= rconst[2];
+    s1 ^= W::splat(rkey[4]);
+    s2 ^= W::splat(rkey[5]);
+    s0 ^= W::splat(rconst[2]);
     sbox(&mut s3, &mut s1, &mut s2, &mut s0);
     s0 = byte_ror_6(&s0);
     s1 = byte_ror_4(&s1);
     s2 = byte_ror_2(&s2);
-    s1 ^= rkey[6];
-    s2 ^= rkey[7];
-    s3 ^= rconst[3];
+    s1 ^= W::splat(rkey[6]);
+    s2 ^= W::splat(rkey[7]);
+    s3 ^= W::splat(rconst[3]);
     sbox(&mut s0, &mut s1, &mut s2, &mut s3);
     s3 = ror(&s3, &24);
     s1 = ror(&s1, &16);
     s2 = ror(&s2, &8);
-    s1 ^= rkey[8];
-    s2 ^= rkey[9];
-    s0 ^= rconst[4];
+    s1 ^= W::splat(rkey[8]);
+    s2 ^= W::splat(rkey[9]);
+    s0 ^= W::splat(rconst[4]);
     core::mem::swap(&mut s0, &mut s3);
     state[0] = s0;
     state[1] = s1;
@@ -214,45 +281,45 @@ pub(crate) fn quintuple_round(state: &mut [u32; 4], rkey: &[u32], rconst: &[u32]
 }
 
 #[inline]
-pub(crate) fn inv_quintuple_round(state: &mut [u32; 4], rkey: &[u32], rconst: &[u32]) {
+pub(crate) fn inv_quintuple_round<W: Word>(state: &mut [W; 4], rkey: &[u32], rconst: &[u32]) {
     let mut s0 = state[0];
     let mut s1 = state[1];
     let mut s2 = state[2];
     let mut s3 = state[3];
     core::mem::swap(&mut s0, &mut s3);
-    s1 ^= rkey[8];
-    s2 ^= rkey[9];
-    s0 ^= rconst[4];
+    s1 ^= W::splat(rkey[8]);
+    s2 ^= W::splat(rkey[9]);
+    s0 ^= W::splat(rconst[4]);
     s3 = ror(&s3, &8);
     s1 = ror(&s1, &16);
     s2 = ror(&s2, &24);
     inv_sbox(&mut s3, &mut s1, &mut s2, &mut s0);
-    s1 ^= rkey[6];
-    s2 ^= rkey[7];
-    s3 ^= rconst[3];
+    s1 ^= W::splat(rkey[6]);
+    s2 ^= W::splat(rkey[7]);
+    s3 ^= W::splat(rconst[3]);
     s0 = byte_ror_2(&s0);
     s1 = byte_ror_4(&s1);
     s2 = byte_ror_6(&s2);
     inv_sbox(&mut s0, &mut s1, &mut s2, &mut s3);
-    s1 ^= rkey[4];
-    s2 ^= rkey[5];
-    s0 ^= rconst[2];
+    s1 ^= W::splat(rkey[4]);
+    s2 ^= W::splat(rkey[5]);
+    s0 ^= W::splat(rconst[2]);
     swapmovesingle(&mut s3, 0x55550000, 1);
     swapmovesingle(&mut s1, 0x55555555, 1);
     swapmovesingle(&mut s2, 0x00005555, 1);
     s3 = ror(&s3, &16);
     s2 = ror(&s2, &16);
     inv_sbox(&mut s3, &mut s1, &mut s2, &mut s0);
-    s1 ^= rkey[2];
-    s2 ^= rkey[3];
-    s3 ^= rconst[1];
+    s1 ^= W::splat(rkey[2]);
+    s2 ^= W::splat(rkey[3]);
+    s3 ^= W::splat(rconst[1]);
     s0 = half_ror_12(&s0);
     s1 = half_ror_8(&s1);
     s2 = half_ror_4(&s2);
     inv_sbox(&mut s0, &mut s1, &mut s2, &mut s3);
-    s1 ^= rkey[0];
-    s2 ^= rkey[1];
-    s0 ^= rconst[0];
+    s1 ^= W::splat(rkey[0]);
+    s2 ^= W::splat(rkey[1]);
+    s0 ^= W::splat(rconst[0]);
     s3 = nibble_ror_3(&s3);
     s1 = nibble_ror_2(&s1);
     s2 = nibble_ror_1(&s2);
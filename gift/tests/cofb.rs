@@ -0,0 +1,156 @@
+//! Entries of the GIFT-COFB NIST LWC known answer test file
+//! `LWC_AEAD_KAT_128_128.txt`. Every entry uses `Key` and `Nonce` equal to
+//! `000102...0F`, while `PT` and `AD` are `00 01 02 ...` of the given length.
+#![cfg(feature = "cofb")]
+
+use aead::{AeadInOut, KeyInit, arrayvec::ArrayVec, inout::InOutBuf};
+use gift_cipher::cofb::{GiftCofb, Nonce, Tag};
+use hex_literal::hex;
+
+/// (Count, PT length, AD length, CT)
+const KAT: &[(usize, usize, usize, &[u8])] = &[
+    (1, 0, 0, &hex!("368965836D36614DE2FC24D0F801B9AF")),
+    (2, 0, 1, &hex!("AE5DCDD1285D5177FE251DEB99D727DC")),
+    (17, 0, 16, &hex!("709657D81DDC509AA20DC66F18FF9907")),
+    (18, 0, 17, &hex!("6591AB7E3CACB1A5B0BE50633E735296")),
+    (33, 0, 32, &hex!("F4938D33A80FB4E4443695F60244969B")),
+    (34, 1, 0, &hex!("5DF96DB329E92688242EF4E06F94FE1BD9")),
+    (
+        511,
+        15,
+        15,
+        &hex!("63C244A171D6F7A407C8D8F90DCD2FC86529409B0787BE227808DDAC2AA6F7"),
+    ),
+    (
+        529,
+        16,
+        0,
+        &hex!("5D595FC00A309301719B30AD9E6D720FEDE74D8C9D1332ADA0413FC514E14918"),
+    ),
+    (
+        545,
+        16,
+        16,
+        &hex!("3BFF715A56CBA49D1F7AC0691A966FDCBF77814044BF3FC9A9DEBBD393F545D4"),
+    ),
+    (
+        579,
+        17,
+        17,
+        &hex!("54B63042B7680D22824EFFE3DA23161C2D82C5C511B0433543A0DA30559C079228"),
+    ),
+    (
+        1089,
+        32,
+        32,
+        &hex!(
+            "BAF563C60FBEDDC5662995F4C678BE80A7F7DE9B3AD8C97AA6CA17016D2AE650"
+            "8E6FB3F79B412A1627AB7DFA755E0A22"
+        ),
+    ),
+];
+
+fn kat_input(len: usize) -> Vec<u8> {
+    (0..len as u8).collect()
+}
+
+fn cipher() -> (GiftCofb, Nonce) {
+    let key = hex!("000102030405060708090A0B0C0D0E0F");
+    (GiftCofb::new(&key.into()), Nonce::from(key))
+}
+
+#[test]
+fn kat_detached() {
+    let (cipher, nonce) = cipher();
+    for &(count, pt_len, ad_len, ct) in KAT {
+        let pt = kat_input(pt_len);
+        let ad = kat_input(ad_len);
+
+        let mut buf = pt.clone();
+        let tag = cipher
+            .encrypt_inout_detached(&nonce, &ad, buf.as_mut_slice().into())
+            .unwrap();
+        assert_eq!(buf, ct[..pt_len], "Count = {count}");
+        assert_eq!(tag[..], ct[pt_len..], "Count = {count}");
+
+        cipher
+            .decrypt_inout_detached(&nonce, &ad, buf.as_mut_slice().into(), &tag)
+            .unwrap();
+        assert_eq!(buf, pt, "Count = {count}");
+    }
+}
+
+#[test]
+fn kat_attached() {
+    let (cipher, nonce) = cipher();
+    for &(count, pt_len, ad_len, ct) in KAT {
+        let pt = kat_input(pt_len);
+        let ad = kat_input(ad_len);
+
+        let mut buf = pt.clone();
+        cipher.encrypt_in_place(&nonce, &ad, &mut buf).unwrap();
+        assert_eq!(buf, ct, "Count = {count}");
+        cipher.decrypt_in_place(&nonce, &ad, &mut buf).unwrap();
+        assert_eq!(buf, pt, "Count = {count}");
+
+        // `no_std` buffer with just enough capacity for the tag
+        let mut buf = ArrayVec::<u8, 48>::new();
+        buf.try_extend_from_slice(&pt).unwrap();
+        cipher.encrypt_in_place(&nonce, &ad, &mut buf).unwrap();
+        assert_eq!(&buf[..], ct, "Count = {count}");
+        cipher.decrypt_in_place(&nonce, &ad, &mut buf).unwrap();
+        assert_eq!(&buf[..], pt, "Count = {count}");
+    }
+}
+
+#[test]
+fn separate_buffers() {
+    let (cipher, nonce) = cipher();
+    let &(_, pt_len, ad_len, ct) = KAT.last().unwrap();
+    let pt = kat_input(pt_len);
+    let ad = kat_input(ad_len);
+
+    let mut out = vec![0u8; pt_len];
+    let buf = InOutBuf::new(&pt, &mut out).unwrap();
+    let tag = cipher.encrypt_inout_detached(&nonce, &ad, buf).unwrap();
+    assert_eq!(out, ct[..pt_len]);
+    assert_eq!(tag[..], ct[pt_len..]);
+
+    let mut out2 = vec![0u8; pt_len];
+    let buf = InOutBuf::new(&out, &mut out2).unwrap();
+    cipher
+        .decrypt_inout_detached(&nonce, &ad, buf, &tag)
+        .unwrap();
+    assert_eq!(out2, pt);
+}
+
+#[test]
+fn reject_modified() {
+    let (cipher, nonce) = cipher();
+    let &(_, pt_len, ad_len, ct) = KAT.last().unwrap();
+    let ad = kat_input(ad_len);
+    let tag = Tag::try_from(&ct[pt_len..]).unwrap();
+
+    for i in 0..ct.len() {
+        let mut buf = ct[..pt_len].to_vec();
+        let mut tag = tag;
+        if i < pt_len {
+            buf[i] ^= 0x01;
+        } else {
+            tag[i - pt_len] ^= 0x80;
+        }
+        let res = cipher.decrypt_inout_detached(&nonce, &ad, buf.as_mut_slice().into(), &tag);
+        assert!(res.is_err());
+        assert!(buf.iter().all(|&b| b == 0));
+    }
+
+    let mut buf = ct[..pt_len].to_vec();
+    let res = cipher.decrypt_inout_detached(&nonce, &ad[1..], buf.as_mut_slice().into(), &tag);
+    assert!(res.is_err());
+
+    let mut nonce = nonce;
+    nonce[15] ^= 1;
+    let mut buf = ct[..pt_len].to_vec();
+    let res = cipher.decrypt_inout_detached(&nonce, &ad, buf.as_mut_slice().into(), &tag);
+    assert!(res.is_err());
+}
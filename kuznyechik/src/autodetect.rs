@@ -0,0 +1,191 @@
+//! Runtime selection of the x86 backend.
+//!
+//! Backends are tried in the order AVX-512, AVX2, SSSE3 and SSE2, and the
+//! first one supported by the CPU is used. The SSE2 backend with fused lookup
+//! tables is always available.
+//!
+//! The AVX-512 backend is only compiled with the `kuznyechik_avx512`
+//! configuration flag: its intrinsics and target features were stabilized in
+//! Rust 1.89, while the crate supports Rust 1.85.
+
+#[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+use crate::avx512;
+use crate::{
+    Backend, BlockSize, Key,
+    byte_sliced::{
+        self,
+        avx2::{self, Avx2},
+        ssse3::{self, Ssse3},
+    },
+    sse2,
+};
+use cipher::{
+    BlockCipherDecClosure, BlockCipherDecrypt, BlockCipherEncClosure, BlockCipherEncrypt,
+};
+
+#[derive(Clone)]
+pub(crate) enum EncDecKeys {
+    Sse2(sse2::EncDecKeys),
+    Ssse3(byte_sliced::EncDecKeys),
+    Avx2(byte_sliced::EncDecKeys),
+    #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+    Avx512(avx512::RoundKeys),
+}
+
+#[derive(Clone)]
+pub(crate) enum EncKeys {
+    Sse2(sse2::EncKeys),
+    Ssse3(byte_sliced::EncKeys),
+    Avx2(byte_sliced::EncKeys),
+    #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+    Avx512(avx512::RoundKeys),
+}
+
+#[derive(Clone)]
+pub(crate) enum DecKeys {
+    Sse2(sse2::DecKeys),
+    Ssse3(byte_sliced::DecKeys),
+    Avx2(byte_sliced::DecKeys),
+    #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+    Avx512(avx512::RoundKeys),
+}
+
+pub(crate) fn backend() -> Backend {
+    #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+    if avx512::init_get().1 {
+        return Backend::Avx512;
+    }
+    if avx2::init_get().1 {
+        Backend::Avx2
+    } else if ssse3::init_get().1 {
+        Backend::Ssse3
+    } else {
+        Backend::Sse2
+    }
+}
+
+impl EncKeys {
+    pub fn new(key: &Key) -> Self {
+        #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+        if avx512::init_get().1 {
+            // SAFETY: support of the target features was checked above
+            return Self::Avx512(unsafe { avx512::expand_enc_keys(key) });
+        }
+        if avx2::init_get().1 {
+            Self::Avx2(byte_sliced::EncKeys::new(key))
+        } else if ssse3::init_get().1 {
+            Self::Ssse3(byte_sliced::EncKeys::new(key))
+        } else {
+            Self::Sse2(sse2::EncKeys::new(key))
+        }
+    }
+
+    pub fn backend(&self) -> Backend {
+        match self {
+            Self::Sse2(_) => Backend::Sse2,
+            Self::Ssse3(_) => Backend::Ssse3,
+            Self::Avx2(_) => Backend::Avx2,
+            #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+            Self::Avx512(_) => Backend::Avx512,
+        }
+    }
+}
+
+impl EncDecKeys {
+    pub fn backend(&self) -> Backend {
+        match self {
+            Self::Sse2(_) => Backend::Sse2,
+            Self::Ssse3(_) => Backend::Ssse3,
+            Self::Avx2(_) => Backend::Avx2,
+            #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+            Self::Avx512(_) => Backend::Avx512,
+        }
+    }
+}
+
+impl DecKeys {
+    pub fn backend(&self) -> Backend {
+        match self {
+            Self::Sse2(_) => Backend::Sse2,
+            Self::Ssse3(_) => Backend::Ssse3,
+            Self::Avx2(_) => Backend::Avx2,
+            #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+            Self::Avx512(_) => Backend::Avx512,
+        }
+    }
+}
+
+impl From<EncKeys> for EncDecKeys {
+    fn from(enc: EncKeys) -> Self {
+        match enc {
+            EncKeys::Sse2(keys) => Self::Sse2(keys.into()),
+            EncKeys::Ssse3(keys) => Self::Ssse3(keys.into()),
+            EncKeys::Avx2(keys) => Self::Avx2(keys.into()),
+            #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+            EncKeys::Avx512(keys) => Self::Avx512(keys),
+        }
+    }
+}
+
+impl From<EncKeys> for DecKeys {
+    fn from(enc: EncKeys) -> Self {
+        match enc {
+            EncKeys::Sse2(keys) => Self::Sse2(keys.into()),
+            EncKeys::Ssse3(keys) => Self::Ssse3(keys.into()),
+            EncKeys::Avx2(keys) => Self::Avx2(keys.into()),
+            #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+            EncKeys::Avx512(keys) => Self::Avx512(keys),
+        }
+    }
+}
+
+// The variants other than `Sse2` are only constructed after the CPU features
+// of their backends were checked, so creating the backends from them is sound.
+
+impl BlockCipherEncrypt for crate::Kuznyechik {
+    fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = BlockSize>) {
+        match &self.keys {
+            EncDecKeys::Sse2(keys) => f.call(&keys.enc_backend()),
+            EncDecKeys::Ssse3(keys) => f.call(&keys.enc_backend::<Ssse3>()),
+            EncDecKeys::Avx2(keys) => f.call(&keys.enc_backend::<Avx2>()),
+            #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+            EncDecKeys::Avx512(keys) => f.call(&avx512::EncBackend(keys)),
+        }
+    }
+}
+
+impl BlockCipherDecrypt for crate::Kuznyechik {
+    fn decrypt_with_backend(&self, f: impl BlockCipherDecClosure<BlockSize = BlockSize>) {
+        match &self.keys {
+            EncDecKeys::Sse2(keys) => f.call(&keys.dec_backend()),
+            EncDecKeys::Ssse3(keys) => f.call(&keys.dec_backend::<Ssse3>()),
+            EncDecKeys::Avx2(keys) => f.call(&keys.dec_backend::<Avx2>()),
+            #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+            EncDecKeys::Avx512(keys) => f.call(&avx512::DecBackend(keys)),
+        }
+    }
+}
+
+impl BlockCipherEncrypt for crate::KuznyechikEnc {
+    fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = BlockSize>) {
+        match &self.keys {
+            EncKeys::Sse2(keys) => f.call(&keys.enc_backend()),
+            EncKeys::Ssse3(keys) => f.call(&keys.enc_backend::<Ssse3>()),
+            EncKeys::Avx2(keys) => f.call(&keys.enc_backend::<Avx2>()),
+            #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+            EncKeys::Avx512(keys) => f.call(&avx512::EncBackend(keys)),
+        }
+    }
+}
+
+impl BlockCipherDecrypt for crate::KuznyechikDec {
+    fn decrypt_with_backend(&self, f: impl BlockCipherDecClosure<BlockSize = BlockSize>) {
+        match &self.keys {
+            DecKeys::Sse2(keys) => f.call(&keys.dec_backend()),
+            DecKeys::Ssse3(keys) => f.call(&keys.dec_backend::<Ssse3>()),
+            DecKeys::Avx2(keys) => f.call(&keys.dec_backend::<Avx2>()),
+            #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+            DecKeys::Avx512(keys) => f.call(&avx512::DecBackend(keys)),
+        }
+    }
+}
@@ -0,0 +1,247 @@
+// This module is only compiled with the `kuznyechik_avx512` flag, which
+// requires Rust 1.89
+#![allow(clippy::incompatible_msrv, unsafe_op_in_unsafe_fn)]
+
+use super::{KEYGEN, L_COLS, L_INV_COLS, PHI_INV_MATRIX, PHI_MATRIX, RoundKeys, SBOX, SBOX_INV};
+use crate::{Block, Key, utils::Align16};
+use cipher::{
+    BlockCipherDecBackend, BlockCipherEncBackend, BlockSizeUser, ParBlocks, ParBlocksSizeUser,
+    consts::{U8, U16},
+    inout::InOut,
+};
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+/// Number of blocks in a 512-bit register.
+const LANES: usize = 4;
+
+/// Number of registers processed in parallel by `encrypt_par_blocks` and
+/// `decrypt_par_blocks`.
+const PAR: usize = 2;
+
+type ParBlocksSize = U8;
+
+/// S-box and linear transform constants loaded into registers.
+struct Tables {
+    sbox: [__m512i; 4],
+    cols: [__m512i; 16],
+}
+
+impl Tables {
+    #[inline(always)]
+    unsafe fn load(sbox: &[u8; 256], cols: &[Align16<[u8; 16]>; 16]) -> Self {
+        let p = sbox.as_ptr().cast::<__m512i>();
+        let mut t = Self {
+            sbox: [_mm512_setzero_si512(); 4],
+            cols: [_mm512_setzero_si512(); 16],
+        };
+        for i in 0..4 {
+            t.sbox[i] = _mm512_load_si512(p.add(i));
+        }
+        for i in 0..16 {
+            t.cols[i] = broadcast(&cols[i].0);
+        }
+        t
+    }
+
+    /// Substitute every byte of `x` using the S-box.
+    #[inline(always)]
+    unsafe fn sub_bytes(&self, x: __m512i) -> __m512i {
+        let [t0, t1, t2, t3] = self.sbox;
+        let lo = _mm512_permutex2var_epi8(t0, x, t1);
+        let hi = _mm512_permutex2var_epi8(t2, x, t3);
+        _mm512_mask_blend_epi8(_mm512_movepi8_mask(x), lo, hi)
+    }
+
+    /// Multiply every block of `x` by the matrix of the linear transform.
+    #[inline(always)]
+    unsafe fn linear(&self, x: __m512i) -> __m512i {
+        let mut t = [_mm512_setzero_si512(); 16];
+        for i in 0..16 {
+            let xi = _mm512_shuffle_epi8(x, _mm512_set1_epi8(i as i8));
+            t[i] = _mm512_gf2p8mul_epi8(xi, self.cols[i]);
+        }
+        // Sum the products with a tree to shorten the dependency chain
+        let mut n = 16;
+        while n > 1 {
+            n /= 2;
+            for i in 0..n {
+                t[i] = _mm512_xor_si512(t[i], t[i + n]);
+            }
+        }
+        t[0]
+    }
+}
+
+#[inline(always)]
+unsafe fn broadcast(block: &[u8; 16]) -> __m512i {
+    _mm512_broadcast_i32x4(_mm_loadu_si128(block.as_ptr().cast()))
+}
+
+#[inline(always)]
+unsafe fn to_aes_field(x: __m512i) -> __m512i {
+    _mm512_gf2p8affine_epi64_epi8::<0>(x, _mm512_set1_epi64(PHI_MATRIX as i64))
+}
+
+#[inline(always)]
+unsafe fn from_aes_field(x: __m512i) -> __m512i {
+    _mm512_gf2p8affine_epi64_epi8::<0>(x, _mm512_set1_epi64(PHI_INV_MATRIX as i64))
+}
+
+#[inline(always)]
+unsafe fn encrypt<const N: usize>(keys: &RoundKeys, x: &mut [__m512i; N]) {
+    let t = Tables::load(&SBOX.0, &L_COLS);
+    for x in x.iter_mut() {
+        *x = to_aes_field(*x);
+    }
+    for k in &keys[..9] {
+        let k = broadcast(k);
+        for x in x.iter_mut() {
+            *x = t.linear(t.sub_bytes(_mm512_xor_si512(*x, k)));
+        }
+    }
+    let k = broadcast(&keys[9]);
+    for x in x.iter_mut() {
+        *x = from_aes_field(_mm512_xor_si512(*x, k));
+    }
+}
+
+#[inline(always)]
+unsafe fn decrypt<const N: usize>(keys: &RoundKeys, x: &mut [__m512i; N]) {
+    let t = Tables::load(&SBOX_INV.0, &L_INV_COLS);
+    let k = broadcast(&keys[9]);
+    for x in x.iter_mut() {
+        *x = _mm512_xor_si512(to_aes_field(*x), k);
+    }
+    for k in keys[..9].iter().rev() {
+        let k = broadcast(k);
+        for x in x.iter_mut() {
+            *x = _mm512_xor_si512(t.sub_bytes(t.linear(*x)), k);
+        }
+    }
+    for x in x.iter_mut() {
+        *x = from_aes_field(*x);
+    }
+}
+
+macro_rules! impl_block_fns {
+    ($block_fn:ident, $par_fn:ident, $f:ident) => {
+        #[target_feature(enable = "avx512f,avx512bw,avx512vbmi,gfni")]
+        unsafe fn $block_fn(keys: &RoundKeys, in_ptr: *const Block, out_ptr: *mut Block) {
+            let mut x = [_mm512_zextsi128_si512(_mm_loadu_si128(in_ptr.cast()))];
+            $f(keys, &mut x);
+            _mm_storeu_si128(out_ptr.cast(), _mm512_castsi512_si128(x[0]));
+        }
+
+        #[target_feature(enable = "avx512f,avx512bw,avx512vbmi,gfni")]
+        unsafe fn $par_fn(keys: &RoundKeys, in_ptr: *const Block, out_ptr: *mut Block) {
+            let mut x = [_mm512_setzero_si512(); PAR];
+            for i in 0..PAR {
+                x[i] = _mm512_loadu_si512(in_ptr.add(LANES * i).cast());
+            }
+            $f(keys, &mut x);
+            for i in 0..PAR {
+                _mm512_storeu_si512(out_ptr.add(LANES * i).cast(), x[i]);
+            }
+        }
+    };
+}
+
+impl_block_fns!(encrypt_block, encrypt_par_blocks, encrypt);
+impl_block_fns!(decrypt_block, decrypt_par_blocks, decrypt);
+
+/// Expand the key into round keys in the AES field representation.
+///
+/// # Safety
+/// The CPU must support the target features checked by [`super::init_get`].
+#[target_feature(enable = "avx512f,avx512bw,avx512vbmi,gfni")]
+pub(crate) unsafe fn expand_enc_keys(key: &Key) -> RoundKeys {
+    let t = Tables::load(&SBOX.0, &L_COLS);
+    let p = key.as_ptr().cast::<__m128i>();
+    let mut k1 = to_aes_field(_mm512_castsi128_si512(_mm_loadu_si128(p)));
+    let mut k2 = to_aes_field(_mm512_castsi128_si512(_mm_loadu_si128(p.add(1))));
+
+    let mut keys = RoundKeys::default();
+    _mm_storeu_si128(keys[0].as_mut_ptr().cast(), _mm512_castsi512_si128(k1));
+    _mm_storeu_si128(keys[1].as_mut_ptr().cast(), _mm512_castsi512_si128(k2));
+
+    let mut cidx = 0;
+    for i in 1..5 {
+        for _ in 0..4 {
+            let c = broadcast(&KEYGEN[cidx].0);
+            k2 = _mm512_xor_si512(k2, t.linear(t.sub_bytes(_mm512_xor_si512(k1, c))));
+            let c = broadcast(&KEYGEN[cidx + 1].0);
+            k1 = _mm512_xor_si512(k1, t.linear(t.sub_bytes(_mm512_xor_si512(k2, c))));
+            cidx += 2;
+        }
+        _mm_storeu_si128(keys[2 * i].as_mut_ptr().cast(), _mm512_castsi512_si128(k1));
+        _mm_storeu_si128(
+            keys[2 * i + 1].as_mut_ptr().cast(),
+            _mm512_castsi512_si128(k2),
+        );
+    }
+    keys
+}
+
+/// Encryption backend, which may only be created after checking the CPU
+/// features with [`super::init_get`].
+pub(crate) struct EncBackend<'a>(pub(crate) &'a RoundKeys);
+
+impl BlockSizeUser for EncBackend<'_> {
+    type BlockSize = U16;
+}
+
+impl ParBlocksSizeUser for EncBackend<'_> {
+    type ParBlocksSize = ParBlocksSize;
+}
+
+impl BlockCipherEncBackend for EncBackend<'_> {
+    #[inline]
+    fn encrypt_block(&self, block: InOut<'_, '_, Block>) {
+        unsafe {
+            let (in_ptr, out_ptr) = block.into_raw();
+            encrypt_block(self.0, in_ptr, out_ptr);
+        }
+    }
+
+    #[inline]
+    fn encrypt_par_blocks(&self, blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        unsafe {
+            let (in_ptr, out_ptr) = blocks.into_raw();
+            encrypt_par_blocks(self.0, in_ptr.cast(), out_ptr.cast());
+        }
+    }
+}
+
+/// Decryption backend, which may only be created after checking the CPU
+/// features with [`super::init_get`].
+pub(crate) struct DecBackend<'a>(pub(crate) &'a RoundKeys);
+
+impl BlockSizeUser for DecBackend<'_> {
+    type BlockSize = U16;
+}
+
+impl ParBlocksSizeUser for DecBackend<'_> {
+    type ParBlocksSize = ParBlocksSize;
+}
+
+impl BlockCipherDecBackend for DecBackend<'_> {
+    #[inline]
+    fn decrypt_block(&self, block: InOut<'_, '_, Block>) {
+        unsafe {
+            let (in_ptr, out_ptr) = block.into_raw();
+            decrypt_block(self.0, in_ptr, out_ptr);
+        }
+    }
+
+    #[inline]
+    fn decrypt_par_blocks(&self, blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        unsafe {
+            let (in_ptr, out_ptr) = blocks.into_raw();
+            decrypt_par_blocks(self.0, in_ptr.cast(), out_ptr.cast());
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! AVX-512 implementation without lookup tables in memory.
+//!
+//! The Kuznyechik field GF(2^8) with the polynomial `x^8 + x^7 + x^6 + x + 1`
+//! is mapped to the AES field used by the GFNI instructions with a field
+//! isomorphism `phi`. The whole cipher state is kept in the AES field
+//! representation: the S-box is evaluated with `VPERMI2B` from tables held in
+//! registers, and the linear transform is computed as a matrix-vector product
+//! with `GF2P8MULB`.
+
+use crate::{
+    consts::{P, P_INV},
+    gft::mul_gf256,
+    utils::{Align16, l_step},
+};
+
+mod backends;
+
+pub(crate) use backends::{DecBackend, EncBackend, expand_enc_keys};
+
+/// Round keys in the AES field representation.
+pub(crate) type RoundKeys = [[u8; 16]; 10];
+
+cpufeatures::new!(avx512_gfni, "avx512f", "avx512bw", "avx512vbmi", "gfni");
+
+pub(crate) use avx512_gfni::init_get;
+
+/// Multiplication in the AES field.
+const fn mul_aes(mut a: u8, mut b: u8) -> u8 {
+    let mut c = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            c ^= a;
+        }
+        a = (a << 1) ^ if a & 0x80 != 0 { 0x1B } else { 0x00 };
+        b >>= 1;
+    }
+    c
+}
+
+/// Powers `beta^0, ..., beta^7` of a root `beta` of the Kuznyechik field
+/// polynomial in the AES field.
+const BETA_POWERS: [u8; 8] = {
+    let mut beta = 2u8;
+    loop {
+        let mut pows = [1u8; 9];
+        let mut i = 1;
+        while i < 9 {
+            pows[i] = mul_aes(pows[i - 1], beta);
+            i += 1;
+        }
+        // x^8 + x^7 + x^6 + x + 1
+        if pows[8] ^ pows[7] ^ pows[6] ^ pows[1] ^ pows[0] == 0 {
+            let mut res = [0u8; 8];
+            let mut i = 0;
+            while i < 8 {
+                res[i] = pows[i];
+                i += 1;
+            }
+            break res;
+        }
+        beta += 1;
+    }
+};
+
+/// The isomorphism from the Kuznyechik field to the AES field.
+const PHI: [u8; 256] = {
+    let mut t = [0u8; 256];
+    let mut v = 0;
+    while v < 256 {
+        let mut i = 0;
+        while i < 8 {
+            if v & (1 << i) != 0 {
+                t[v] ^= BETA_POWERS[i];
+            }
+            i += 1;
+        }
+        v += 1;
+    }
+    t
+};
+
+/// The inverse of [`PHI`].
+const PHI_INV: [u8; 256] = {
+    let mut t = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        t[PHI[i] as usize] = i as u8;
+        i += 1;
+    }
+    t
+};
+
+/// Encode a linear map of bytes given by its lookup table as a bit matrix
+/// for `GF2P8AFFINEQB`, which keeps the row of output bit `i` in byte `7 - i`.
+const fn affine_matrix(table: &[u8; 256]) -> u64 {
+    let mut res = 0u64;
+    let mut col = 0;
+    while col < 8 {
+        let v = table[1 << col];
+        let mut row = 0;
+        while row < 8 {
+            if v & (1 << row) != 0 {
+                res |= 1 << (8 * (7 - row) + col);
+            }
+            row += 1;
+        }
+        col += 1;
+    }
+    res
+}
+
+const PHI_MATRIX: u64 = affine_matrix(&PHI);
+const PHI_INV_MATRIX: u64 = affine_matrix(&PHI_INV);
+
+/// Conjugate an S-box with [`PHI`].
+const fn map_sbox(sbox: &[u8; 256]) -> [u8; 256] {
+    let mut t = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        t[i] = PHI[sbox[PHI_INV[i] as usize] as usize];
+        i += 1;
+    }
+    t
+}
+
+#[repr(align(64))]
+struct Align64<T>(T);
+
+static SBOX: Align64<[u8; 256]> = Align64(map_sbox(&P));
+static SBOX_INV: Align64<[u8; 256]> = Align64(map_sbox(&P_INV));
+
+/// Apply the inverse linear transform to `block`.
+const fn l_inv(mut block: [u8; 16]) -> [u8; 16] {
+    let mut n = 0;
+    while n < 16 {
+        let mut x = block[0];
+        let mut i = 1;
+        while i < 16 {
+            x ^= mul_gf256(L_COEFFS[i - 1], block[i]);
+            i += 1;
+        }
+        let mut k = 0;
+        while k < 15 {
+            block[k] = block[k + 1];
+            k += 1;
+        }
+        block[15] = x;
+        n += 1;
+    }
+    block
+}
+
+/// Coefficients of the linear feedback function, see the `l_step` function.
+const L_COEFFS: [u8; 15] = [
+    148, 32, 133, 16, 194, 192, 1, 251, 1, 192, 194, 16, 133, 32, 148,
+];
+
+/// Columns of the linear transform (or its inverse) in the AES field:
+/// `L(x) = sum x_i * cols[i]`.
+const fn l_columns(inverse: bool) -> [Align16<[u8; 16]>; 16] {
+    let mut res = [Align16([0u8; 16]); 16];
+    let mut i = 0;
+    while i < 16 {
+        let mut col = [0u8; 16];
+        col[i] = 1;
+        if inverse {
+            col = l_inv(col);
+        } else {
+            let mut j = 0;
+            while j < 16 {
+                col = l_step(col, j);
+                j += 1;
+            }
+        }
+        let mut j = 0;
+        while j < 16 {
+            res[i].0[j] = PHI[col[j] as usize];
+            j += 1;
+        }
+        i += 1;
+    }
+    res
+}
+
+static L_COLS: [Align16<[u8; 16]>; 16] = l_columns(false);
+static L_INV_COLS: [Align16<[u8; 16]>; 16] = l_columns(true);
+
+/// Round key generation constants in the AES field.
+static KEYGEN: [Align16<[u8; 16]>; 32] = {
+    let mut res = [Align16([0u8; 16]); 32];
+    let mut n = 0;
+    while n < res.len() {
+        let mut block = [0u8; 16];
+        block[15] = (n + 1) as u8;
+
+        let mut i = 0;
+        while i < 16 {
+            block = l_step(block, i);
+            i += 1;
+        }
+        let mut j = 0;
+        while j < 16 {
+            res[n].0[j] = PHI[block[j] as usize];
+            j += 1;
+        }
+        n += 1;
+    }
+    res
+};
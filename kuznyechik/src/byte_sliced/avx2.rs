@@ -0,0 +1,93 @@
+//! AVX2 backend which processes 32 blocks in 256-bit vectors, with blocks
+//! `i` and `i + 16` in the two 128-bit halves of a vector.
+
+use super::{Batch, Bytes, RoundKeys, decrypt, encrypt, transpose};
+use crate::Block;
+use cipher::consts::U32;
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+cpufeatures::new!(avx2_cpuid, "avx2");
+
+pub(crate) use avx2_cpuid::init_get;
+
+impl Bytes for __m256i {
+    #[inline(always)]
+    unsafe fn splat(v: u8) -> Self {
+        _mm256_set1_epi8(v as i8)
+    }
+
+    #[inline(always)]
+    unsafe fn table(table: &[u8; 16]) -> Self {
+        _mm256_broadcastsi128_si256(_mm_loadu_si128(table.as_ptr().cast()))
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        _mm256_xor_si256(self, rhs)
+    }
+
+    #[inline(always)]
+    unsafe fn and(self, rhs: Self) -> Self {
+        _mm256_and_si256(self, rhs)
+    }
+
+    #[inline(always)]
+    unsafe fn shr4(self) -> Self {
+        _mm256_srli_epi16::<4>(self)
+    }
+
+    #[inline(always)]
+    unsafe fn adds(self, rhs: Self) -> Self {
+        _mm256_adds_epu8(self, rhs)
+    }
+
+    #[inline(always)]
+    unsafe fn lookup(self, table: Self) -> Self {
+        _mm256_shuffle_epi8(table, self)
+    }
+
+    #[inline(always)]
+    unsafe fn unpacklo(self, rhs: Self) -> Self {
+        _mm256_unpacklo_epi8(self, rhs)
+    }
+
+    #[inline(always)]
+    unsafe fn unpackhi(self, rhs: Self) -> Self {
+        _mm256_unpackhi_epi8(self, rhs)
+    }
+}
+
+/// Batches of 32 blocks.
+pub(crate) struct Avx2;
+
+impl Batch for Avx2 {
+    type Size = U32;
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn crypt<const DEC: bool>(keys: &RoundKeys, in_ptr: *const Block, out_ptr: *mut Block) {
+        let mut s = [_mm256_setzero_si256(); 16];
+        for (i, s) in s.iter_mut().enumerate() {
+            let lo = _mm_loadu_si128(in_ptr.add(i).cast());
+            let hi = _mm_loadu_si128(in_ptr.add(i + 16).cast());
+            *s = _mm256_inserti128_si256::<1>(_mm256_castsi128_si256(lo), hi);
+        }
+        transpose(&mut s);
+        if DEC {
+            decrypt(keys, &mut s);
+        } else {
+            encrypt(keys, &mut s);
+        }
+        transpose(&mut s);
+        for (i, s) in s.iter().enumerate() {
+            _mm_storeu_si128(out_ptr.add(i).cast(), _mm256_castsi256_si128(*s));
+            _mm_storeu_si128(
+                out_ptr.add(i + 16).cast(),
+                _mm256_extracti128_si256::<1>(*s),
+            );
+        }
+    }
+}
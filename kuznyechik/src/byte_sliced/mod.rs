@@ -0,0 +1,420 @@
+//! Byte-sliced SSSE3 and AVX2 implementations using byte shuffles.
+//!
+//! Blocks are transposed so that lane `n` of vector `j` holds byte `j` of
+//! block `n`. In this layout every coefficient of the linear transform is the
+//! same in all lanes: the transform is computed with the recurrence of
+//! [`l_step`] and products with constants are looked up with `PSHUFB` from
+//! two 16-entry tables indexed by the nibbles of a byte. The S-box is
+//! evaluated with one `PSHUFB` lookup for each of its 16 rows. No memory
+//! access depends on the processed data.
+//!
+//! The SSSE3 backend processes 16 blocks in 128-bit vectors and the AVX2
+//! backend processes 32 blocks, 16 in each 128-bit half of its vectors.
+//! A batch takes the same time regardless of the number of blocks in it, so
+//! single blocks, including the tails shorter than a batch, and the key
+//! schedule use the SSE2 code with fused lookup tables.
+//!
+//! [`l_step`]: crate::utils::l_step
+#![allow(unsafe_op_in_unsafe_fn)]
+
+use crate::{
+    Block, Key,
+    consts::{P, P_INV},
+    gft::mul_gf256,
+    sse2,
+};
+use cipher::{
+    BlockCipherDecBackend, BlockCipherEncBackend, BlockSizeUser, ParBlocks, ParBlocksSizeUser,
+    array::ArraySize, consts::U16, inout::InOut,
+};
+use core::marker::PhantomData;
+
+pub(crate) mod avx2;
+pub(crate) mod ssse3;
+
+#[rustfmt::skip]
+macro_rules! unroll16 {
+    ($var:ident, $body:block) => {
+        { let $var: usize = 0; $body; }
+        { let $var: usize = 1; $body; }
+        { let $var: usize = 2; $body; }
+        { let $var: usize = 3; $body; }
+        { let $var: usize = 4; $body; }
+        { let $var: usize = 5; $body; }
+        { let $var: usize = 6; $body; }
+        { let $var: usize = 7; $body; }
+        { let $var: usize = 8; $body; }
+        { let $var: usize = 9; $body; }
+        { let $var: usize = 10; $body; }
+        { let $var: usize = 11; $body; }
+        { let $var: usize = 12; $body; }
+        { let $var: usize = 13; $body; }
+        { let $var: usize = 14; $body; }
+        { let $var: usize = 15; $body; }
+    };
+}
+
+/// Round keys, which are the same for encryption and decryption.
+type RoundKeys = [[u8; 16]; 10];
+
+/// Implementation of batches of blocks.
+pub(crate) trait Batch {
+    type Size: ArraySize;
+
+    /// Encrypt (or decrypt if `DEC` is true) the `Size` blocks at `in_ptr`
+    /// into `out_ptr`.
+    ///
+    /// # Safety
+    /// The CPU must support the target features of the implementation.
+    unsafe fn crypt<const DEC: bool>(keys: &RoundKeys, in_ptr: *const Block, out_ptr: *mut Block);
+}
+
+/// Vector of byte lanes.
+///
+/// All methods require CPU support for the instruction set used by the
+/// implementation.
+trait Bytes: Copy {
+    unsafe fn splat(v: u8) -> Self;
+    /// Load `table` into every 128-bit half of the vector.
+    unsafe fn table(table: &[u8; 16]) -> Self;
+    unsafe fn xor(self, rhs: Self) -> Self;
+    unsafe fn and(self, rhs: Self) -> Self;
+    /// Shift every 16-bit lane right by 4 bits.
+    unsafe fn shr4(self) -> Self;
+    /// Add every byte of `rhs` with unsigned saturation.
+    unsafe fn adds(self, rhs: Self) -> Self;
+    /// Look up every byte of `self` in `table` with `PSHUFB`: bytes with the
+    /// most significant bit set give zero, otherwise the low 4 bits are used
+    /// as the index into the matching 128-bit half of `table`.
+    unsafe fn lookup(self, table: Self) -> Self;
+    /// Interleave the low 8 bytes of every 128-bit half with `rhs`.
+    unsafe fn unpacklo(self, rhs: Self) -> Self;
+    /// Interleave the high 8 bytes of every 128-bit half with `rhs`.
+    unsafe fn unpackhi(self, rhs: Self) -> Self;
+}
+
+/// Split an S-box into rows of 16 entries and XOR every row with the next row
+/// of the same half, see [`Tables::sub_bytes`].
+const fn rows(sbox: &[u8; 256]) -> [[u8; 16]; 16] {
+    let mut res = [[0u8; 16]; 16];
+    let mut i = 0;
+    while i < 256 {
+        res[i / 16][i % 16] = sbox[i];
+        if i % 128 < 112 {
+            res[i / 16][i % 16] ^= sbox[i + 16];
+        }
+        i += 1;
+    }
+    res
+}
+
+static SBOX: [[u8; 16]; 16] = rows(&P);
+static SBOX_INV: [[u8; 16]; 16] = rows(&P_INV);
+
+/// Offsets added to the bytes for the S-box rows `0..7` of each half.
+static OFFSETS: [[u8; 16]; 7] = {
+    let mut res = [[0u8; 16]; 7];
+    let mut h = 0;
+    while h < 7 {
+        res[h] = [0x70 - 16 * h as u8; 16];
+        h += 1;
+    }
+    res
+};
+
+// Constants are loaded from rows in memory: broadcasting a single byte needs
+// a shuffle, which is the bottleneck of these implementations.
+static MSB: [u8; 16] = [0x80; 16];
+static LOW_NIBBLE: [u8; 16] = [0x0F; 16];
+
+/// Coefficients of the linear feedback function other than 1. The function
+/// is symmetric, so the first six are used for pairs of bytes.
+const COEFFS: [u8; 7] = [148, 32, 133, 16, 194, 192, 251];
+
+/// Products of [`COEFFS`] with the low and the high nibble of a byte.
+static MUL: [[[u8; 16]; 2]; 7] = {
+    let mut res = [[[0u8; 16]; 2]; 7];
+    let mut i = 0;
+    while i < COEFFS.len() {
+        let mut n = 0;
+        while n < 16 {
+            res[i][0][n] = mul_gf256(COEFFS[i], n as u8);
+            res[i][1][n] = mul_gf256(COEFFS[i], (n as u8) << 4);
+            n += 1;
+        }
+        i += 1;
+    }
+    res
+};
+
+/// S-box and multiplication tables loaded into vectors.
+struct Tables<V> {
+    sbox: [V; 16],
+    mul: [[V; 2]; 7],
+}
+
+impl<V: Bytes> Tables<V> {
+    #[inline(always)]
+    unsafe fn load(sbox: &[[u8; 16]; 16]) -> Self {
+        let mut t = Self {
+            sbox: [V::splat(0); 16],
+            mul: [[V::splat(0); 2]; 7],
+        };
+        for (v, row) in t.sbox.iter_mut().zip(sbox) {
+            *v = V::table(row);
+        }
+        for (v, [lo, hi]) in t.mul.iter_mut().zip(&MUL) {
+            *v = [V::table(lo), V::table(hi)];
+        }
+        t
+    }
+
+    /// Substitute every byte of `x`.
+    ///
+    /// Adding `0x70 - 16 * h` with unsigned saturation maps the bytes in the
+    /// row `h < 8` to indices `0x70..0x80` and the bytes of later rows to
+    /// indices with the most significant bit set, which look up zero. Bytes of
+    /// earlier rows look up entries as well, so the tables hold the difference
+    /// to the next row and the lookups of the rows `h..8` sum up to row `h`.
+    /// The rows `8..16` are handled in the same way after flipping the most
+    /// significant bit, which moves the first half out of range.
+    #[inline(always)]
+    unsafe fn sub_bytes(&self, x: V) -> V {
+        let y = x.xor(V::table(&MSB));
+        let mut res = x.lookup(self.sbox[7]).xor(y.lookup(self.sbox[15]));
+        for (h, c) in OFFSETS.iter().enumerate() {
+            let c = V::table(c);
+            res = res.xor(x.adds(c).lookup(self.sbox[h]));
+            res = res.xor(y.adds(c).lookup(self.sbox[8 + h]));
+        }
+        res
+    }
+
+    /// Multiply every byte of `x` by `COEFFS[i]`.
+    #[inline(always)]
+    unsafe fn mul(&self, x: V, i: usize) -> V {
+        let mask = V::table(&LOW_NIBBLE);
+        let [lo, hi] = self.mul[i];
+        let lo = x.and(mask).lookup(lo);
+        let hi = x.shr4().and(mask).lookup(hi);
+        lo.xor(hi)
+    }
+
+    /// Apply the linear transform as 16 steps of [`l_step`], which overwrite
+    /// the byte at index `15 - i` in step `i`.
+    ///
+    /// [`l_step`]: crate::utils::l_step
+    #[inline(always)]
+    unsafe fn linear(&self, s: &mut [V; 16]) {
+        unroll16!(i, {
+            let b = |k: usize| s[k.wrapping_sub(i) & 15];
+            let mut x = b(15).xor(b(8)).xor(b(6)).xor(self.mul(b(7), 6));
+            for k in 0..6 {
+                x = x.xor(self.mul(b(14 - k).xor(b(k)), k));
+            }
+            s[15usize.wrapping_sub(i) & 15] = x;
+        });
+    }
+
+    /// Apply the inverse linear transform. Step `n` computes the byte which
+    /// is shifted in at index 15 and stores it at index `n`, the place of the
+    /// byte shifted out.
+    #[inline(always)]
+    unsafe fn linear_inv(&self, s: &mut [V; 16]) {
+        unroll16!(n, {
+            let b = |k: usize| s[(k + n) & 15];
+            let mut x = b(0).xor(b(7)).xor(b(9)).xor(self.mul(b(8), 6));
+            for k in 0..6 {
+                x = x.xor(self.mul(b(1 + k).xor(b(15 - k)), k));
+            }
+            s[n] = x;
+        });
+    }
+}
+
+#[inline(always)]
+unsafe fn add_key<V: Bytes>(s: &mut [V; 16], key: &[u8; 16]) {
+    for (v, &k) in s.iter_mut().zip(key) {
+        *v = v.xor(V::splat(k));
+    }
+}
+
+/// Transpose the bytes of the 16x16 matrix held in every 128-bit half of the
+/// vectors. Each pass rotates the bits of the row index followed by the
+/// column index left by one, so four passes swap the two indices.
+#[inline(always)]
+unsafe fn transpose<V: Bytes>(x: &mut [V; 16]) {
+    for _ in 0..4 {
+        let t = *x;
+        for i in 0..8 {
+            x[2 * i] = t[i].unpacklo(t[i + 8]);
+            x[2 * i + 1] = t[i].unpackhi(t[i + 8]);
+        }
+    }
+}
+
+#[inline(always)]
+unsafe fn encrypt<V: Bytes>(keys: &RoundKeys, s: &mut [V; 16]) {
+    let t = Tables::load(&SBOX);
+    for k in &keys[..9] {
+        add_key(s, k);
+        for x in s.iter_mut() {
+            *x = t.sub_bytes(*x);
+        }
+        t.linear(s);
+    }
+    add_key(s, &keys[9]);
+}
+
+#[inline(always)]
+unsafe fn decrypt<V: Bytes>(keys: &RoundKeys, s: &mut [V; 16]) {
+    let t = Tables::load(&SBOX_INV);
+    add_key(s, &keys[9]);
+    for k in keys[..9].iter().rev() {
+        t.linear_inv(s);
+        for x in s.iter_mut() {
+            *x = t.sub_bytes(*x);
+        }
+        add_key(s, k);
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct EncDecKeys {
+    tables: sse2::EncDecKeys,
+    round_keys: RoundKeys,
+}
+
+#[derive(Clone)]
+pub(crate) struct EncKeys {
+    tables: sse2::EncKeys,
+    round_keys: RoundKeys,
+}
+
+#[derive(Clone)]
+pub(crate) struct DecKeys {
+    tables: sse2::DecKeys,
+    round_keys: RoundKeys,
+}
+
+impl EncKeys {
+    pub fn new(key: &Key) -> Self {
+        let tables = sse2::EncKeys::new(key);
+        Self {
+            round_keys: tables.to_bytes(),
+            tables,
+        }
+    }
+}
+
+impl From<EncKeys> for EncDecKeys {
+    fn from(enc: EncKeys) -> Self {
+        Self {
+            tables: enc.tables.into(),
+            round_keys: enc.round_keys,
+        }
+    }
+}
+
+impl From<EncKeys> for DecKeys {
+    fn from(enc: EncKeys) -> Self {
+        Self {
+            tables: enc.tables.into(),
+            round_keys: enc.round_keys,
+        }
+    }
+}
+
+// The backends may only be created after checking the CPU features of `B`.
+
+impl EncDecKeys {
+    pub(crate) fn enc_backend<B: Batch>(&self) -> EncBackend<'_, B> {
+        EncBackend {
+            tables: self.tables.enc_backend(),
+            round_keys: &self.round_keys,
+            batch: PhantomData,
+        }
+    }
+
+    pub(crate) fn dec_backend<B: Batch>(&self) -> DecBackend<'_, B> {
+        DecBackend {
+            tables: self.tables.dec_backend(),
+            round_keys: &self.round_keys,
+            batch: PhantomData,
+        }
+    }
+}
+
+impl EncKeys {
+    pub(crate) fn enc_backend<B: Batch>(&self) -> EncBackend<'_, B> {
+        EncBackend {
+            tables: self.tables.enc_backend(),
+            round_keys: &self.round_keys,
+            batch: PhantomData,
+        }
+    }
+}
+
+impl DecKeys {
+    pub(crate) fn dec_backend<B: Batch>(&self) -> DecBackend<'_, B> {
+        DecBackend {
+            tables: self.tables.dec_backend(),
+            round_keys: &self.round_keys,
+            batch: PhantomData,
+        }
+    }
+}
+
+pub(crate) struct EncBackend<'a, B> {
+    tables: sse2::EncBackend<'a>,
+    round_keys: &'a RoundKeys,
+    batch: PhantomData<B>,
+}
+
+impl<B> BlockSizeUser for EncBackend<'_, B> {
+    type BlockSize = U16;
+}
+
+impl<B: Batch> ParBlocksSizeUser for EncBackend<'_, B> {
+    type ParBlocksSize = B::Size;
+}
+
+impl<B: Batch> BlockCipherEncBackend for EncBackend<'_, B> {
+    #[inline]
+    fn encrypt_block(&self, block: InOut<'_, '_, Block>) {
+        self.tables.encrypt_block(block);
+    }
+
+    #[inline]
+    fn encrypt_par_blocks(&self, blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let (in_ptr, out_ptr) = blocks.into_raw();
+        unsafe { B::crypt::<false>(self.round_keys, in_ptr.cast(), out_ptr.cast()) }
+    }
+}
+
+pub(crate) struct DecBackend<'a, B> {
+    tables: sse2::DecBackend<'a>,
+    round_keys: &'a RoundKeys,
+    batch: PhantomData<B>,
+}
+
+impl<B> BlockSizeUser for DecBackend<'_, B> {
+    type BlockSize = U16;
+}
+
+impl<B: Batch> ParBlocksSizeUser for DecBackend<'_, B> {
+    type ParBlocksSize = B::Size;
+}
+
+impl<B: Batch> BlockCipherDecBackend for DecBackend<'_, B> {
+    #[inline]
+    fn decrypt_block(&self, block: InOut<'_, '_, Block>) {
+        self.tables.decrypt_block(block);
+    }
+
+    #[inline]
+    fn decrypt_par_blocks(&self, blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let (in_ptr, out_ptr) = blocks.into_raw();
+        unsafe { B::crypt::<true>(self.round_keys, in_ptr.cast(), out_ptr.cast()) }
+    }
+}
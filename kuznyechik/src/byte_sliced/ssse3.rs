@@ -0,0 +1,86 @@
+//! SSSE3 backend which processes 16 blocks in 128-bit vectors.
+
+use super::{Batch, Bytes, RoundKeys, decrypt, encrypt, transpose};
+use crate::Block;
+use cipher::consts::U16;
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+cpufeatures::new!(ssse3_cpuid, "ssse3");
+
+pub(crate) use ssse3_cpuid::init_get;
+
+impl Bytes for __m128i {
+    #[inline(always)]
+    unsafe fn splat(v: u8) -> Self {
+        _mm_set1_epi8(v as i8)
+    }
+
+    #[inline(always)]
+    unsafe fn table(table: &[u8; 16]) -> Self {
+        _mm_loadu_si128(table.as_ptr().cast())
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        _mm_xor_si128(self, rhs)
+    }
+
+    #[inline(always)]
+    unsafe fn and(self, rhs: Self) -> Self {
+        _mm_and_si128(self, rhs)
+    }
+
+    #[inline(always)]
+    unsafe fn shr4(self) -> Self {
+        _mm_srli_epi16::<4>(self)
+    }
+
+    #[inline(always)]
+    unsafe fn adds(self, rhs: Self) -> Self {
+        _mm_adds_epu8(self, rhs)
+    }
+
+    #[inline(always)]
+    unsafe fn lookup(self, table: Self) -> Self {
+        _mm_shuffle_epi8(table, self)
+    }
+
+    #[inline(always)]
+    unsafe fn unpacklo(self, rhs: Self) -> Self {
+        _mm_unpacklo_epi8(self, rhs)
+    }
+
+    #[inline(always)]
+    unsafe fn unpackhi(self, rhs: Self) -> Self {
+        _mm_unpackhi_epi8(self, rhs)
+    }
+}
+
+/// Batches of 16 blocks.
+pub(crate) struct Ssse3;
+
+impl Batch for Ssse3 {
+    type Size = U16;
+
+    #[target_feature(enable = "ssse3")]
+    unsafe fn crypt<const DEC: bool>(keys: &RoundKeys, in_ptr: *const Block, out_ptr: *mut Block) {
+        let mut s = [_mm_setzero_si128(); 16];
+        for (i, s) in s.iter_mut().enumerate() {
+            *s = _mm_loadu_si128(in_ptr.add(i).cast());
+        }
+        transpose(&mut s);
+        if DEC {
+            decrypt(keys, &mut s);
+        } else {
+            encrypt(keys, &mut s);
+        }
+        transpose(&mut s);
+        for (i, s) in s.iter().enumerate() {
+            _mm_storeu_si128(out_ptr.add(i).cast(), *s);
+        }
+    }
+}
@@ -0,0 +1,426 @@
+use crate::{
+    Block, Key,
+    consts::{P, P_INV},
+    gft::mul_gf256,
+    utils::{KEYGEN, l_step},
+};
+use cipher::{
+    BlockCipherDecBackend, BlockCipherEncBackend, BlockSizeUser, InOut, ParBlocks,
+    ParBlocksSizeUser, consts,
+};
+
+/// Bitsliced state of four blocks.
+type State = [u64; 8];
+
+/// Round keys in the bitsliced representation, replicated for all blocks.
+pub(super) type RoundKeys = [State; 10];
+
+/// Bits of the lowest byte position of every block.
+const LSB: u64 = 0x0001_0001_0001_0001;
+
+/// Algebraic normal form of an S-box split by the monomials of the high and
+/// low nibbles of the input: bit `l` of `anf[h][c]` is the coefficient of the
+/// monomial `prod_{i in 16 * h + l} x_i` in output bit `c`.
+type Anf = [[u16; 8]; 16];
+
+const fn anf(sbox: &[u8; 256]) -> Anf {
+    // Moebius transform of the truth tables of all output bits
+    let mut t = *sbox;
+    let mut i = 0;
+    while i < 8 {
+        let mut x = 0;
+        while x < 256 {
+            if x & (1 << i) != 0 {
+                t[x] ^= t[x ^ (1 << i)];
+            }
+            x += 1;
+        }
+        i += 1;
+    }
+
+    let mut res = [[0u16; 8]; 16];
+    let mut m = 0;
+    while m < 256 {
+        let mut c = 0;
+        while c < 8 {
+            if t[m] & (1 << c) != 0 {
+                res[m >> 4][c] |= 1 << (m & 0xF);
+            }
+            c += 1;
+        }
+        m += 1;
+    }
+    res
+}
+
+static ANF: Anf = anf(&P);
+static ANF_INV: Anf = anf(&P_INV);
+
+/// Matrix of the linear transform (or its inverse) over GF(2) in the
+/// bitsliced representation: `table[j][b][c]` is the word `c` of the image of
+/// the vector with only bit `b` of byte `j` set, replicated for all blocks.
+type LinearTable = [[State; 8]; 16];
+
+const fn linear_table(inverse: bool) -> LinearTable {
+    let mut res = [[[0u64; 8]; 8]; 16];
+    let mut j = 0;
+    while j < 16 {
+        let mut col = [0u8; 16];
+        col[j] = 1;
+        let mut i = 0;
+        while i < 16 {
+            col = if inverse {
+                l_step(col, 15 - i)
+            } else {
+                l_step(col, i)
+            };
+            i += 1;
+        }
+
+        let mut b = 0;
+        while b < 8 {
+            let mut k = 0;
+            while k < 16 {
+                let v = mul_gf256(col[k], 1 << b);
+                let mut c = 0;
+                while c < 8 {
+                    if v & (1 << c) != 0 {
+                        res[j][b][c] |= LSB << k;
+                    }
+                    c += 1;
+                }
+                k += 1;
+            }
+            b += 1;
+        }
+        j += 1;
+    }
+    res
+}
+
+static L: LinearTable = linear_table(false);
+static L_INV: LinearTable = linear_table(true);
+
+/// Matrix of the linear transform (or its inverse) for a single block:
+/// `table[q][b][c]` holds in lane `n` the word `c` of the image of the vector
+/// with only bit `b` of byte `4 * n + q` set.
+type LinearTable1 = [[State; 8]; 4];
+
+const fn linear_table1(table: &LinearTable) -> LinearTable1 {
+    let mut res = [[[0u64; 8]; 8]; 4];
+    let mut j = 0;
+    while j < 16 {
+        let mut b = 0;
+        while b < 8 {
+            let mut c = 0;
+            while c < 8 {
+                res[j % 4][b][c] |= (table[j][b][c] & 0xFFFF) << (16 * (j / 4));
+                c += 1;
+            }
+            b += 1;
+        }
+        j += 1;
+    }
+    res
+}
+
+static L1: LinearTable1 = linear_table1(&L);
+static L1_INV: LinearTable1 = linear_table1(&L_INV);
+
+/// Transpose the 8x8 bit matrix with rows stored in bytes of `x`.
+#[inline(always)]
+fn transpose(mut x: u64) -> u64 {
+    let t = (x ^ (x >> 7)) & 0x00AA_00AA_00AA_00AA;
+    x ^= t ^ (t << 7);
+    let t = (x ^ (x >> 14)) & 0x0000_CCCC_0000_CCCC;
+    x ^= t ^ (t << 14);
+    let t = (x ^ (x >> 28)) & 0x0000_0000_F0F0_F0F0;
+    x ^= t ^ (t << 28);
+    x
+}
+
+#[inline(always)]
+fn bitslice(blocks: &[Block; 4]) -> State {
+    let mut s = State::default();
+    for g in 0..8 {
+        let (n, j) = (g / 2, 8 * (g % 2));
+        let w = transpose(u64::from_le_bytes(blocks[n][j..][..8].try_into().unwrap()));
+        for b in 0..8 {
+            s[b] |= ((w >> (8 * b)) & 0xFF) << (8 * g);
+        }
+    }
+    s
+}
+
+/// Bitslice a single block into the lowest lane of the state.
+#[inline(always)]
+fn bitslice1(block: &Block) -> State {
+    let mut s = State::default();
+    for g in 0..2 {
+        let w = transpose(u64::from_le_bytes(block[8 * g..][..8].try_into().unwrap()));
+        for b in 0..8 {
+            s[b] |= ((w >> (8 * b)) & 0xFF) << (8 * g);
+        }
+    }
+    s
+}
+
+#[inline(always)]
+fn unbitslice1(s: &State) -> Block {
+    let mut block = Block::default();
+    for g in 0..2 {
+        let mut w = 0;
+        for b in 0..8 {
+            w |= ((s[b] >> (8 * g)) & 0xFF) << (8 * b);
+        }
+        block[8 * g..][..8].copy_from_slice(&transpose(w).to_le_bytes());
+    }
+    block
+}
+
+#[inline(always)]
+fn unbitslice(s: &State) -> [Block; 4] {
+    let mut blocks = [Block::default(); 4];
+    for g in 0..8 {
+        let (n, j) = (g / 2, 8 * (g % 2));
+        let mut w = 0;
+        for b in 0..8 {
+            w |= ((s[b] >> (8 * g)) & 0xFF) << (8 * b);
+        }
+        blocks[n][j..][..8].copy_from_slice(&transpose(w).to_le_bytes());
+    }
+    blocks
+}
+
+#[inline(always)]
+fn x(a: &mut State, b: &State) {
+    for i in 0..8 {
+        a[i] ^= b[i];
+    }
+}
+
+/// Compute all monomials of four variables.
+#[inline(always)]
+fn monomials(x: &[u64]) -> [u64; 16] {
+    let mut mons = [!0; 16];
+    for m in 1..16usize {
+        let i = m.trailing_zeros() as usize;
+        mons[m] = mons[m & (m - 1)] & x[i];
+    }
+    mons
+}
+
+/// Compute XORs of all subsets of four words.
+#[inline(always)]
+fn subset_sums(x: &[u64]) -> [u64; 16] {
+    let mut sums = [0; 16];
+    for m in 1..16usize {
+        let i = m.trailing_zeros() as usize;
+        sums[m] = sums[m & (m - 1)] ^ x[i];
+    }
+    sums
+}
+
+/// Evaluate the S-box given by its algebraic normal form.
+///
+/// Tables are indexed only by the public coefficients of the S-box.
+#[inline(always)]
+fn s(state: &mut State, anf: &Anf) {
+    let lo = monomials(&state[..4]);
+    let hi = monomials(&state[4..]);
+    let sums: [[u64; 16]; 4] = core::array::from_fn(|g| subset_sums(&lo[4 * g..][..4]));
+    let mut res = State::default();
+    for h in 0..16 {
+        for c in 0..8 {
+            let coeffs = usize::from(anf[h][c]);
+            let mut t = 0;
+            for (g, sums) in sums.iter().enumerate() {
+                t ^= sums[(coeffs >> (4 * g)) & 0xF];
+            }
+            res[c] ^= hi[h] & t;
+        }
+    }
+    *state = res;
+}
+
+/// Multiply the state by the matrix of a linear transform.
+#[inline(always)]
+fn l(state: &mut State, table: &LinearTable) {
+    let mut res = State::default();
+    for j in 0..16 {
+        for b in 0..8 {
+            // Spread bit `b` of byte `j` of every block over the whole block
+            let t = (state[b] >> j) & LSB;
+            let mask = (t << 16).wrapping_sub(t);
+            for c in 0..8 {
+                res[c] ^= mask & table[j][b][c];
+            }
+        }
+    }
+    *state = res;
+}
+
+/// Multiply the lowest lane of the state by the matrix of a linear transform.
+///
+/// The input bits of four bytes are spread over the four lanes at once, and
+/// the lanes of the result are summed at the end.
+#[inline(always)]
+fn l1(state: &mut State, table: &LinearTable1) {
+    let mut res = State::default();
+    for b in 0..8 {
+        // Lane `n` holds the bits of bytes `4 * n..`
+        let v = state[b] & 0xFFFF;
+        let v = v | ((v >> 4) << 16) | ((v >> 8) << 32) | ((v >> 12) << 48);
+        for q in 0..4 {
+            let t = (v >> q) & LSB;
+            let mask = (t << 16).wrapping_sub(t);
+            for c in 0..8 {
+                res[c] ^= mask & table[q][b][c];
+            }
+        }
+    }
+    for c in 0..8 {
+        let r = res[c] ^ (res[c] >> 32);
+        state[c] = (r ^ (r >> 16)) & 0xFFFF;
+    }
+}
+
+#[inline(always)]
+fn lsx(state: &mut State, key: &State) {
+    x(state, key);
+    s(state, &ANF);
+    l(state, &L);
+}
+
+#[inline(always)]
+fn lsx_inv(state: &mut State, key: &State) {
+    x(state, key);
+    l(state, &L_INV);
+    s(state, &ANF_INV);
+}
+
+fn encrypt(keys: &RoundKeys, blocks: &[Block; 4]) -> [Block; 4] {
+    let mut state = bitslice(blocks);
+    for i in 0..9 {
+        lsx(&mut state, &keys[i]);
+    }
+    x(&mut state, &keys[9]);
+    unbitslice(&state)
+}
+
+fn decrypt(keys: &RoundKeys, blocks: &[Block; 4]) -> [Block; 4] {
+    let mut state = bitslice(blocks);
+    for i in 0..9 {
+        lsx_inv(&mut state, &keys[9 - i]);
+    }
+    x(&mut state, &keys[0]);
+    unbitslice(&state)
+}
+
+/// Encrypt a single block in the lowest lane of the state. The other lanes
+/// hold values derived from the round keys, which `l1` ignores.
+fn encrypt1(keys: &RoundKeys, block: &Block) -> Block {
+    let mut state = bitslice1(block);
+    for key in &keys[..9] {
+        x(&mut state, key);
+        s(&mut state, &ANF);
+        l1(&mut state, &L1);
+    }
+    x(&mut state, &keys[9]);
+    unbitslice1(&state)
+}
+
+/// Decrypt a single block in the lowest lane of the state.
+fn decrypt1(keys: &RoundKeys, block: &Block) -> Block {
+    let mut state = bitslice1(block);
+    x(&mut state, &keys[9]);
+    l1(&mut state, &L1_INV);
+    for key in keys[1..9].iter().rev() {
+        s(&mut state, &ANF_INV);
+        x(&mut state, key);
+        l1(&mut state, &L1_INV);
+    }
+    s(&mut state, &ANF_INV);
+    x(&mut state, &keys[0]);
+    unbitslice1(&state)
+}
+
+/// Bitslice a block replicated for all blocks of the state.
+fn replicate(block: &Block) -> State {
+    bitslice(&[*block; 4])
+}
+
+pub(super) fn expand(key: &Key) -> RoundKeys {
+    let mut keys = RoundKeys::default();
+
+    let mut k1 = replicate(key[..16].try_into().unwrap());
+    let mut k2 = replicate(key[16..].try_into().unwrap());
+
+    keys[0] = k1;
+    keys[1] = k2;
+
+    for i in 1..5 {
+        for j in 0..4 {
+            let n = 8 * (i - 1) + 2 * j;
+
+            let mut t = k1;
+            lsx(&mut t, &replicate(&KEYGEN[n].0.into()));
+            x(&mut k2, &t);
+
+            let mut t = k2;
+            lsx(&mut t, &replicate(&KEYGEN[n + 1].0.into()));
+            x(&mut k1, &t);
+        }
+        keys[2 * i] = k1;
+        keys[2 * i + 1] = k2;
+    }
+    keys
+}
+
+pub(crate) struct EncBackend<'a>(pub(crate) &'a RoundKeys);
+
+impl BlockSizeUser for EncBackend<'_> {
+    type BlockSize = consts::U16;
+}
+
+impl ParBlocksSizeUser for EncBackend<'_> {
+    type ParBlocksSize = consts::U4;
+}
+
+impl BlockCipherEncBackend for EncBackend<'_> {
+    #[inline]
+    fn encrypt_block(&self, mut block: InOut<'_, '_, Block>) {
+        let res = encrypt1(self.0, block.get_in());
+        *block.get_out() = res;
+    }
+
+    #[inline]
+    fn encrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let res = encrypt(self.0, &blocks.get_in().0);
+        blocks.get_out().0 = res;
+    }
+}
+
+pub(crate) struct DecBackend<'a>(pub(crate) &'a RoundKeys);
+
+impl BlockSizeUser for DecBackend<'_> {
+    type BlockSize = consts::U16;
+}
+
+impl ParBlocksSizeUser for DecBackend<'_> {
+    type ParBlocksSize = consts::U4;
+}
+
+impl BlockCipherDecBackend for DecBackend<'_> {
+    #[inline]
+    fn decrypt_block(&self, mut block: InOut<'_, '_, Block>) {
+        let res = decrypt1(self.0, block.get_in());
+        *block.get_out() = res;
+    }
+
+    #[inline]
+    fn decrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let res = decrypt(self.0, &blocks.get_in().0);
+        blocks.get_out().0 = res;
+    }
+}
@@ -0,0 +1,91 @@
+//! Constant-time bitsliced implementation.
+//!
+//! Four blocks are processed at once in a bitsliced representation: bit `b`
+//! of byte `j` of block `n` is stored in bit `16 * n + j` of the `b`-th word
+//! of the state. The S-box is evaluated from its algebraic normal form and
+//! the linear transform is computed as a product with the matrix of the
+//! transform over GF(2), so no memory accesses depend on secret data.
+//!
+//! Single blocks are stored in the lowest lane of the state, and the matrix
+//! product is split over the four lanes with the partial results summed at
+//! the end.
+
+use crate::{Backend, BlockSize, Key};
+use cipher::{
+    BlockCipherDecClosure, BlockCipherDecrypt, BlockCipherEncClosure, BlockCipherEncrypt,
+};
+
+mod backends;
+
+use backends::{DecBackend, EncBackend, RoundKeys, expand};
+
+#[derive(Clone)]
+pub(crate) struct EncDecKeys(RoundKeys);
+#[derive(Clone)]
+pub(crate) struct EncKeys(RoundKeys);
+#[derive(Clone)]
+pub(crate) struct DecKeys(RoundKeys);
+
+impl From<EncKeys> for EncDecKeys {
+    fn from(enc: EncKeys) -> Self {
+        Self(enc.0)
+    }
+}
+
+impl From<EncKeys> for DecKeys {
+    fn from(enc: EncKeys) -> Self {
+        Self(enc.0)
+    }
+}
+
+impl EncKeys {
+    pub fn new(key: &Key) -> Self {
+        Self(expand(key))
+    }
+}
+
+pub(crate) fn backend() -> Backend {
+    Backend::Ct
+}
+
+impl EncDecKeys {
+    pub fn backend(&self) -> Backend {
+        backend()
+    }
+}
+
+impl EncKeys {
+    pub fn backend(&self) -> Backend {
+        backend()
+    }
+}
+
+impl DecKeys {
+    pub fn backend(&self) -> Backend {
+        backend()
+    }
+}
+
+impl BlockCipherEncrypt for crate::Kuznyechik {
+    fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = BlockSize>) {
+        f.call(&EncBackend(&self.keys.0));
+    }
+}
+
+impl BlockCipherDecrypt for crate::Kuznyechik {
+    fn decrypt_with_backend(&self, f: impl BlockCipherDecClosure<BlockSize = BlockSize>) {
+        f.call(&DecBackend(&self.keys.0));
+    }
+}
+
+impl BlockCipherEncrypt for crate::KuznyechikEnc {
+    fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = BlockSize>) {
+        f.call(&EncBackend(&self.keys.0));
+    }
+}
+
+impl BlockCipherDecrypt for crate::KuznyechikDec {
+    fn decrypt_with_backend(&self, f: impl BlockCipherDecClosure<BlockSize = BlockSize>) {
+        f.call(&DecBackend(&self.keys.0));
+    }
+}
@@ -8,7 +8,7 @@ pub(crate) const GFT_192: [u8; 256] = mul_table_gf256(192);
 pub(crate) const GFT_194: [u8; 256] = mul_table_gf256(194);
 pub(crate) const GFT_251: [u8; 256] = mul_table_gf256(251);
 
-const fn mul_gf256(mut a: u8, mut b: u8) -> u8 {
+pub(crate) const fn mul_gf256(mut a: u8, mut b: u8) -> u8 {
     let mut c = 0;
     while b != 0 {
         if b & 1 != 0 {
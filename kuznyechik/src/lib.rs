@@ -15,11 +15,47 @@
 //!
 //! - `soft`: use software backend with big fused tables.
 //! - `compact_soft`: use software backend with small tables and slower performance.
+//! - `ct`: use constant-time bitsliced software backend without lookup tables
+//!   indexed by secret data. It is significantly slower than other backends.
 //!
 //! The flag can be enabled using `RUSTFLAGS` environment variable
 //! (e.g. `RUSTFLAGS='--cfg kuznyechik_backend="soft"'`) or by modifying
 //! `.cargo/config`.
 //!
+//! The `kuznyechik_avx512` configuration flag enables the AVX-512 backend on
+//! x86-64 targets. It requires Rust 1.89 or later, which stabilized the
+//! AVX-512 intrinsics, so the backend is not built by default while the crate
+//! supports Rust 1.85: without the flag, CPUs with AVX-512 use the AVX2
+//! backend. Opt in with `RUSTFLAGS='--cfg kuznyechik_avx512'`.
+//!
+//! # Backends
+//!
+//! Without the `kuznyechik_backend` flag, x86 and x86-64 targets with SSE2
+//! select the backend at runtime, in the following order:
+//!
+//! - AVX-512 with the VBMI and GFNI extensions, if enabled with the
+//!   `kuznyechik_avx512` flag: the S-box is computed with byte permutations of
+//!   registers and the linear transform with GF(2^8) multiplications.
+//! - AVX2 and SSSE3: batches of 32 or 16 blocks are transposed and processed
+//!   with byte shuffles, without lookups in memory tables. Single blocks,
+//!   including tails shorter than a batch, and the key schedule use the SSE2
+//!   code. The SSSE3 backend has a lower throughput than the SSE2 backend.
+//! - SSE2 with fused lookup tables.
+//!
+//! AArch64 targets with NEON use the NEON backend, and other targets use the
+//! portable software backend.
+//!
+//! The [`backend`] function returns the backend selected on the current CPU,
+//! and the `backend` method of the cipher types returns the backend used by
+//! an instance:
+//!
+//! ```
+//! use kuznyechik::{Kuznyechik, cipher::KeyInit};
+//!
+//! let cipher = Kuznyechik::new(&[0x42; 32].into());
+//! assert_eq!(cipher.backend(), kuznyechik::backend());
+//! ```
+//!
 //! [Kuznyechik]: https://en.wikipedia.org/wiki/Kuznyechik
 //! [GOST R 34.12-2015]: https://tc26.ru/standard/gost/GOST_R_3412-2015.pdf
 #![no_std]
@@ -42,27 +78,48 @@ use core::fmt;
 #[cfg(feature = "zeroize")]
 use cipher::zeroize::{ZeroizeOnDrop, zeroize_flat_type};
 
+#[cfg(feature = "acpkm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "acpkm")))]
+pub mod acpkm;
 mod consts;
 pub(crate) mod gft;
+#[cfg(feature = "mgm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mgm")))]
+pub mod mgm;
 pub(crate) mod utils;
 
 cfg_if::cfg_if!(
     if #[cfg(all(
         any(target_arch = "x86_64", target_arch = "x86"),
         target_feature = "sse2",
-        not(any(kuznyechik_backend = "soft", kuznyechik_backend = "compact_soft")),
+        not(any(
+            kuznyechik_backend = "soft",
+            kuznyechik_backend = "compact_soft",
+            kuznyechik_backend = "ct",
+        )),
     ))] {
+        mod autodetect;
+        #[cfg(all(target_arch = "x86_64", kuznyechik_avx512))]
+        mod avx512;
+        mod byte_sliced;
         mod fused_tables;
         mod sse2;
-        use sse2 as imp;
+        use autodetect as imp;
     } else if #[cfg(all(
         target_arch = "aarch64",
         target_feature = "neon",
-        not(any(kuznyechik_backend = "soft", kuznyechik_backend = "compact_soft")),
+        not(any(
+            kuznyechik_backend = "soft",
+            kuznyechik_backend = "compact_soft",
+            kuznyechik_backend = "ct",
+        )),
     ))] {
         mod fused_tables;
         mod neon;
         use neon as imp;
+    } else if #[cfg(kuznyechik_backend = "ct")] {
+        mod ct;
+        use ct as imp;
     } else if #[cfg(kuznyechik_backend = "compact_soft")] {
         mod compact_soft;
         use compact_soft as imp;
@@ -75,6 +132,39 @@ cfg_if::cfg_if!(
 
 use imp::{DecKeys, EncDecKeys, EncKeys};
 
+/// Kuznyechik implementation used by a cipher instance.
+///
+/// See the [crate documentation][crate#backends] for details on how the
+/// backend is selected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// Portable implementation with fused lookup tables.
+    Soft,
+    /// Portable implementation with small lookup tables.
+    CompactSoft,
+    /// SSE2 implementation with fused lookup tables.
+    Sse2,
+    /// NEON implementation with fused lookup tables.
+    Neon,
+    /// SSSE3 implementation of 16 blocks in parallel without lookup tables in
+    /// memory, which uses the SSE2 implementation for single blocks.
+    Ssse3,
+    /// AVX2 implementation of 32 blocks in parallel without lookup tables in
+    /// memory, which uses the SSE2 implementation for single blocks.
+    Avx2,
+    /// AVX-512 implementation without lookup tables in memory, which requires
+    /// the VBMI and GFNI extensions.
+    Avx512,
+    /// Constant-time bitsliced implementation.
+    Ct,
+}
+
+/// Returns the backend used by ciphers initialized on the current CPU.
+pub fn backend() -> Backend {
+    imp::backend()
+}
+
 type BlockSize = U16;
 type KeySize = U32;
 
@@ -121,6 +211,13 @@ impl From<&KuznyechikEnc> for Kuznyechik {
     }
 }
 
+impl Kuznyechik {
+    /// Returns the backend used by this instance.
+    pub fn backend(&self) -> Backend {
+        self.keys.backend()
+    }
+}
+
 impl fmt::Debug for Kuznyechik {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         f.write_str("Kuznyechik { ... }")
@@ -166,6 +263,13 @@ impl KeyInit for KuznyechikEnc {
     }
 }
 
+impl KuznyechikEnc {
+    /// Returns the backend used by this instance.
+    pub fn backend(&self) -> Backend {
+        self.keys.backend()
+    }
+}
+
 impl fmt::Debug for KuznyechikEnc {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         f.write_str("KuznyechikEnc { ... }")
@@ -228,6 +332,13 @@ impl From<&KuznyechikEnc> for KuznyechikDec {
     }
 }
 
+impl KuznyechikDec {
+    /// Returns the backend used by this instance.
+    pub fn backend(&self) -> Backend {
+        self.keys.backend()
+    }
+}
+
 impl fmt::Debug for KuznyechikDec {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         f.write_str("KuznyechikDec { ... }")
@@ -0,0 +1,26 @@
+#![allow(unsafe_code)]
+
+use core::arch::aarch64::*;
+
+use super::{Clmul, Field, mul_sum_with};
+
+struct Pmull;
+
+impl Clmul for Pmull {
+    #[inline(always)]
+    fn clmul(a: u64, b: u64) -> u128 {
+        // SAFETY: only called from `mul_sum`, which enables the features
+        unsafe { pmull(a, b) }
+    }
+}
+
+#[target_feature(enable = "neon", enable = "aes")]
+#[inline]
+unsafe fn pmull(a: u64, b: u64) -> u128 {
+    vmull_p64(a, b)
+}
+
+#[target_feature(enable = "neon", enable = "aes")]
+pub(super) unsafe fn mul_sum<F: Field>(h: &[u128], x: &[u128]) -> u128 {
+    mul_sum_with::<F, Pmull>(h, x)
+}
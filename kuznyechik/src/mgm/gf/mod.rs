@@ -0,0 +1,165 @@
+//! Constant-time multiplication in GF(2^128) and GF(2^64) used by MGM.
+//!
+//! Field elements are big-endian integers where bit `i` is the coefficient of
+//! `x^i`. The carry-less 64x64-bit multiplication is computed with PCLMULQDQ
+//! on x86, PMULL on AArch64 (both detected at runtime), or with integer
+//! multiplications on numbers with holes otherwise.
+
+/// Carry-less multiplication of two 64-bit polynomials.
+pub trait Clmul {
+    fn clmul(a: u64, b: u64) -> u128;
+}
+
+/// Field parameters of a block size.
+pub trait Field {
+    /// Multiply `a` and `b` using the carry-less multiplication `M`.
+    fn mul<M: Clmul>(a: u128, b: u128) -> u128;
+}
+
+/// GF(2^128) with the polynomial `x^128 + x^7 + x^2 + x + 1`.
+pub struct Gf128;
+
+/// GF(2^64) with the polynomial `x^64 + x^4 + x^3 + x + 1`.
+pub struct Gf64;
+
+impl Field for Gf128 {
+    #[inline(always)]
+    fn mul<M: Clmul>(a: u128, b: u128) -> u128 {
+        let (a0, a1) = (a as u64, (a >> 64) as u64);
+        let (b0, b1) = (b as u64, (b >> 64) as u64);
+
+        // Karatsuba multiplication
+        let lo = M::clmul(a0, b0);
+        let hi = M::clmul(a1, b1);
+        let mid = M::clmul(a0 ^ a1, b0 ^ b1) ^ lo ^ hi;
+        let lo = lo ^ (mid << 64);
+        let hi = hi ^ (mid >> 64);
+
+        // Reduce by x^128 = x^7 + x^2 + x + 1, the bits shifted out of the
+        // first folding are folded once more
+        let over = (hi >> 127) ^ (hi >> 126) ^ (hi >> 121);
+        let hi = hi ^ over;
+        lo ^ hi ^ (hi << 1) ^ (hi << 2) ^ (hi << 7)
+    }
+}
+
+impl Field for Gf64 {
+    #[inline(always)]
+    fn mul<M: Clmul>(a: u128, b: u128) -> u128 {
+        let p = M::clmul(a as u64, b as u64);
+        let (lo, hi) = (p as u64, (p >> 64) as u64);
+
+        // Reduce by x^64 = x^4 + x^3 + x + 1
+        let over = (hi >> 63) ^ (hi >> 61) ^ (hi >> 60);
+        let hi = hi ^ over;
+        (lo ^ hi ^ (hi << 1) ^ (hi << 3) ^ (hi << 4)) as u128
+    }
+}
+
+/// Sum of `h[i] * x[i]` over all `i`.
+#[inline(always)]
+fn mul_sum_with<F: Field, M: Clmul>(h: &[u128], x: &[u128]) -> u128 {
+    h.iter()
+        .zip(x)
+        .fold(0, |acc, (&h, &x)| acc ^ F::mul::<M>(h, x))
+}
+
+mod soft {
+    use super::Clmul;
+
+    pub(crate) struct Soft;
+
+    /// Low 64 bits of the carry-less product, computed with integer
+    /// multiplications of numbers with holes (from BearSSL). The count of
+    /// terms summed at any of the low 64 bits never overflows into the
+    /// next non-hole bit.
+    #[inline(always)]
+    fn bmul64(x: u64, y: u64) -> u64 {
+        const M0: u64 = 0x1111_1111_1111_1111;
+        const M1: u64 = 0x2222_2222_2222_2222;
+        const M2: u64 = 0x4444_4444_4444_4444;
+        const M3: u64 = 0x8888_8888_8888_8888;
+
+        let (x0, x1, x2, x3) = (x & M0, x & M1, x & M2, x & M3);
+        let (y0, y1, y2, y3) = (y & M0, y & M1, y & M2, y & M3);
+
+        let z0 =
+            x0.wrapping_mul(y0) ^ x1.wrapping_mul(y3) ^ x2.wrapping_mul(y2) ^ x3.wrapping_mul(y1);
+        let z1 =
+            x0.wrapping_mul(y1) ^ x1.wrapping_mul(y0) ^ x2.wrapping_mul(y3) ^ x3.wrapping_mul(y2);
+        let z2 =
+            x0.wrapping_mul(y2) ^ x1.wrapping_mul(y1) ^ x2.wrapping_mul(y0) ^ x3.wrapping_mul(y3);
+        let z3 =
+            x0.wrapping_mul(y3) ^ x1.wrapping_mul(y2) ^ x2.wrapping_mul(y1) ^ x3.wrapping_mul(y0);
+
+        (z0 & M0) | (z1 & M1) | (z2 & M2) | (z3 & M3)
+    }
+
+    impl Clmul for Soft {
+        #[inline(always)]
+        fn clmul(a: u64, b: u64) -> u128 {
+            let lo = bmul64(a, b);
+            // The high half is the low half of the product of the bit
+            // reversed operands, bit reversed back
+            let hi = bmul64(a.reverse_bits(), b.reverse_bits()).reverse_bits() >> 1;
+            (lo as u128) | ((hi as u128) << 64)
+        }
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(all(
+        any(target_arch = "x86_64", target_arch = "x86"),
+        not(any(kuznyechik_backend = "soft", kuznyechik_backend = "compact_soft")),
+    ))] {
+        mod x86;
+        cpufeatures::new!(clmul_intrinsics, "pclmulqdq", "sse2");
+        use x86::mul_sum as arch_mul_sum;
+    } else if #[cfg(all(
+        target_arch = "aarch64",
+        not(any(kuznyechik_backend = "soft", kuznyechik_backend = "compact_soft")),
+    ))] {
+        mod aarch64;
+        cpufeatures::new!(clmul_intrinsics, "aes");
+        use aarch64::mul_sum as arch_mul_sum;
+    } else {
+        mod clmul_intrinsics {
+            #[derive(Copy, Clone, Debug)]
+            pub(crate) struct InitToken(());
+
+            impl InitToken {
+                #[inline(always)]
+                pub(crate) fn get(&self) -> bool {
+                    false
+                }
+            }
+
+            pub(crate) fn init() -> InitToken {
+                InitToken(())
+            }
+        }
+
+        unsafe fn arch_mul_sum<F: Field>(h: &[u128], x: &[u128]) -> u128 {
+            mul_sum_with::<F, soft::Soft>(h, x)
+        }
+    }
+}
+
+pub(super) use clmul_intrinsics::InitToken;
+
+/// Detect support of carry-less multiplication instructions.
+pub(super) fn init() -> InitToken {
+    clmul_intrinsics::init()
+}
+
+/// Sum of `h[i] * x[i]` over all `i` using the fastest available
+/// implementation.
+#[inline]
+pub(super) fn mul_sum<F: Field>(token: InitToken, h: &[u128], x: &[u128]) -> u128 {
+    if token.get() {
+        // SAFETY: support of the required target features was checked above
+        unsafe { arch_mul_sum::<F>(h, x) }
+    } else {
+        mul_sum_with::<F, soft::Soft>(h, x)
+    }
+}
@@ -0,0 +1,30 @@
+#![allow(unsafe_code)]
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::{Clmul, Field, mul_sum_with};
+
+struct Pclmul;
+
+impl Clmul for Pclmul {
+    #[inline(always)]
+    fn clmul(a: u64, b: u64) -> u128 {
+        // SAFETY: only called from `mul_sum`, which enables the features
+        unsafe {
+            let a = _mm_set_epi64x(0, a as i64);
+            let b = _mm_set_epi64x(0, b as i64);
+            let p = _mm_clmulepi64_si128(a, b, 0x00);
+            let mut out = [0u64; 2];
+            _mm_storeu_si128(out.as_mut_ptr().cast(), p);
+            (out[0] as u128) | ((out[1] as u128) << 64)
+        }
+    }
+}
+
+#[target_feature(enable = "pclmulqdq", enable = "sse2")]
+pub(super) unsafe fn mul_sum<F: Field>(h: &[u128], x: &[u128]) -> u128 {
+    mul_sum_with::<F, Pclmul>(h, x)
+}
@@ -0,0 +1,322 @@
+//! [MGM] (Multilinear Galois Mode) authenticated encryption.
+//!
+//! MGM is specified in [RFC 9058] and used by the Russian TLS 1.3 cipher
+//! suites defined in [RFC 9367]. [`Mgm`] works with any block cipher with
+//! 64-bit or 128-bit blocks, e.g. [`KuznyechikEnc`] or `magma::Magma`, and
+//! only uses the encryption direction of the cipher.
+//!
+//! The nonce has the same size as the block, but its most significant bit
+//! must be zero: MGM uses that bit to derive separate initial counters for
+//! encryption and authentication. Nonces with the bit set are rejected with
+//! an error. The tag always has the full block size.
+//!
+//! Multiplication in GF(2<sup>128</sup>) and GF(2<sup>64</sup>) is
+//! constant-time. It uses the PCLMULQDQ instruction on x86 and PMULL on
+//! AArch64 when the CPU supports them, with a portable fallback otherwise.
+//!
+//! # Example
+//! ```
+//! use kuznyechik::mgm::{
+//!     KuznyechikMgm,
+//!     aead::{AeadInOut, KeyInit},
+//! };
+//!
+//! let cipher = KuznyechikMgm::new(&[0x42; 32].into());
+//! // The most significant bit of the nonce must be zero
+//! let nonce = [0x01; 16].into();
+//!
+//! let mut buf = *b"plaintext message";
+//! let tag = cipher
+//!     .encrypt_inout_detached(&nonce, b"header", (&mut buf[..]).into())
+//!     .unwrap();
+//! cipher
+//!     .decrypt_inout_detached(&nonce, b"header", (&mut buf[..]).into(), &tag)
+//!     .unwrap();
+//! assert_eq!(&buf, b"plaintext message");
+//! ```
+//!
+//! [MGM]: https://eprint.iacr.org/2019/123
+//! [RFC 9058]: https://www.rfc-editor.org/rfc/rfc9058
+//! [RFC 9367]: https://www.rfc-editor.org/rfc/rfc9367
+
+pub use aead;
+
+use aead::{AeadCore, AeadInOut, Error, Nonce, Tag, TagPosition, inout::InOutBuf};
+use cipher::{
+    Block, BlockCipherEncrypt, BlockSizeUser,
+    array::ArraySize,
+    common::{InnerInit, InnerUser},
+    consts::{U8, U16},
+    typenum::Unsigned,
+};
+use core::fmt;
+use subtle::ConstantTimeEq;
+
+use crate::KuznyechikEnc;
+
+mod gf;
+
+/// MGM with the Kuznyechik block cipher.
+pub type KuznyechikMgm = Mgm<KuznyechikEnc>;
+
+/// Number of blocks processed with a single call to the block cipher.
+const BATCH: usize = 8;
+
+/// Block sizes supported by [`Mgm`].
+///
+/// This trait is sealed and implemented for [`U8`] and [`U16`].
+pub trait MgmBlockSize: ArraySize + sealed::Sealed {}
+
+impl MgmBlockSize for U8 {}
+impl MgmBlockSize for U16 {}
+
+mod sealed {
+    use super::gf::{Field, Gf64, Gf128};
+
+    pub trait Sealed {
+        type Field: Field;
+    }
+
+    impl Sealed for super::U8 {
+        type Field = Gf64;
+    }
+
+    impl Sealed for super::U16 {
+        type Field = Gf128;
+    }
+}
+
+/// MGM authenticated encryption with the block cipher `C`.
+#[derive(Clone)]
+pub struct Mgm<C> {
+    cipher: C,
+    token: gf::InitToken,
+}
+
+impl<C: BlockSizeUser> InnerUser for Mgm<C> {
+    type Inner = C;
+}
+
+impl<C> InnerInit for Mgm<C>
+where
+    C: BlockCipherEncrypt,
+    C::BlockSize: MgmBlockSize,
+{
+    fn inner_init(cipher: C) -> Self {
+        Self {
+            cipher,
+            token: gf::init(),
+        }
+    }
+}
+
+impl<C> AeadCore for Mgm<C>
+where
+    C: BlockCipherEncrypt,
+    C::BlockSize: MgmBlockSize,
+{
+    type NonceSize = C::BlockSize;
+    type TagSize = C::BlockSize;
+    const TAG_POSITION: TagPosition = TagPosition::Postfix;
+}
+
+impl<C> AeadInOut for Mgm<C>
+where
+    C: BlockCipherEncrypt,
+    C::BlockSize: MgmBlockSize,
+{
+    fn encrypt_inout_detached(
+        &self,
+        nonce: &Nonce<Self>,
+        associated_data: &[u8],
+        mut buffer: InOutBuf<'_, '_, u8>,
+    ) -> Result<Tag<Self>, Error> {
+        let (y, z) = self.init_counters(nonce, associated_data.len(), buffer.len())?;
+        self.apply_keystream(y, buffer.reborrow());
+        Ok(self.compute_tag(z, associated_data, buffer.get_out()))
+    }
+
+    fn decrypt_inout_detached(
+        &self,
+        nonce: &Nonce<Self>,
+        associated_data: &[u8],
+        buffer: InOutBuf<'_, '_, u8>,
+        tag: &Tag<Self>,
+    ) -> Result<(), Error> {
+        let (y, z) = self.init_counters(nonce, associated_data.len(), buffer.len())?;
+        // The tag covers the ciphertext, so it is checked before decryption
+        let expected = self.compute_tag(z, associated_data, buffer.get_in());
+        if expected.ct_eq(tag).into() {
+            self.apply_keystream(y, buffer);
+            Ok(())
+        } else {
+            Err(Error)
+        }
+    }
+}
+
+impl<C> Mgm<C>
+where
+    C: BlockCipherEncrypt,
+    C::BlockSize: MgmBlockSize,
+{
+    const N: usize = C::BlockSize::USIZE;
+    const HALF_BITS: usize = 4 * Self::N;
+
+    /// Check the input lengths and compute the initial encryption counter
+    /// `Y_1 = E(0 || nonce)` and authentication counter `Z_1 = E(1 || nonce)`.
+    fn init_counters(
+        &self,
+        nonce: &Nonce<Self>,
+        ad_len: usize,
+        msg_len: usize,
+    ) -> Result<(u128, u128), Error> {
+        // The combined bit length of the associated data and the message must
+        // be non-zero and fit into a half block
+        let bits = 8 * (ad_len as u128 + msg_len as u128);
+        if nonce[0] & 0x80 != 0 || bits == 0 || bits >> Self::HALF_BITS != 0 {
+            return Err(Error);
+        }
+
+        let mut blocks = [nonce.clone(), nonce.clone()];
+        blocks[1][0] |= 0x80;
+        self.cipher.encrypt_blocks(&mut blocks);
+        Ok((to_elem(&blocks[0]), to_elem(&blocks[1])))
+    }
+
+    /// Increment the right half of a counter.
+    #[inline]
+    fn incr_r(x: u128) -> u128 {
+        let mask = (1u128 << Self::HALF_BITS) - 1;
+        (x & !mask) | (x.wrapping_add(1) & mask)
+    }
+
+    /// Increment the left half of a counter.
+    #[inline]
+    fn incr_l(x: u128) -> u128 {
+        let mask = (1u128 << Self::HALF_BITS) - 1;
+        let left = ((x >> Self::HALF_BITS).wrapping_add(1) & mask) << Self::HALF_BITS;
+        (x & mask) | left
+    }
+
+    /// XOR the buffer with the keystream `E(Y_1) || E(Y_2) || ...`.
+    fn apply_keystream(&self, mut y: u128, mut buffer: InOutBuf<'_, '_, u8>) {
+        let n = Self::N;
+        let mut blocks: [Block<C>; BATCH] = Default::default();
+        let mut keystream = [0u8; BATCH * 16];
+
+        while !buffer.is_empty() {
+            let len = buffer.len().min(BATCH * n);
+            let (mut chunk, rest) = buffer.split_at(len);
+            let blocks = &mut blocks[..len.div_ceil(n)];
+            for block in blocks.iter_mut() {
+                from_elem(y, block);
+                y = Self::incr_r(y);
+            }
+            self.cipher.encrypt_blocks(blocks);
+            for (ks, block) in keystream.chunks_exact_mut(n).zip(blocks.iter()) {
+                ks.copy_from_slice(block);
+            }
+            chunk.xor_in2out(&keystream[..len]);
+            buffer = rest;
+        }
+    }
+
+    /// Compute the tag of the associated data and the ciphertext.
+    fn compute_tag(&self, z: u128, associated_data: &[u8], ciphertext: &[u8]) -> Tag<Self> {
+        let mut hasher = Hasher {
+            mgm: self,
+            z,
+            xs: [0; BATCH],
+            len: 0,
+            sum: 0,
+        };
+        hasher.update(associated_data);
+        hasher.update(ciphertext);
+        let ad_bits = 8 * associated_data.len() as u128;
+        let ct_bits = 8 * ciphertext.len() as u128;
+        hasher.push((ad_bits << Self::HALF_BITS) | ct_bits);
+        hasher.flush();
+
+        let mut tag = Tag::<Self>::default();
+        from_elem(hasher.sum, &mut tag);
+        self.cipher.encrypt_block(&mut tag);
+        tag
+    }
+}
+
+/// Accumulator of the sum of `H_i * X_i`, where `H_i = E(Z_i)`.
+struct Hasher<'a, C> {
+    mgm: &'a Mgm<C>,
+    z: u128,
+    xs: [u128; BATCH],
+    len: usize,
+    sum: u128,
+}
+
+impl<C> Hasher<'_, C>
+where
+    C: BlockCipherEncrypt,
+    C::BlockSize: MgmBlockSize,
+{
+    /// Absorb `data` padded with zeros to a multiple of the block size.
+    fn update(&mut self, data: &[u8]) {
+        let n = Mgm::<C>::N;
+        for chunk in data.chunks(n) {
+            let mut block = [0u8; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.push(to_elem(&block[..n]));
+        }
+    }
+
+    fn push(&mut self, x: u128) {
+        self.xs[self.len] = x;
+        self.len += 1;
+        if self.len == BATCH {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        let mut blocks: [Block<C>; BATCH] = Default::default();
+        let blocks = &mut blocks[..self.len];
+        for block in blocks.iter_mut() {
+            from_elem(self.z, block);
+            self.z = Mgm::<C>::incr_l(self.z);
+        }
+        self.mgm.cipher.encrypt_blocks(blocks);
+
+        let mut hs = [0u128; BATCH];
+        for (h, block) in hs.iter_mut().zip(blocks.iter()) {
+            *h = to_elem(block);
+        }
+        let xs = &self.xs[..self.len];
+        self.sum ^= gf::mul_sum::<<C::BlockSize as sealed::Sealed>::Field>(
+            self.mgm.token,
+            &hs[..self.len],
+            xs,
+        );
+        self.len = 0;
+    }
+}
+
+/// Interpret a block of up to 16 bytes as a big-endian integer.
+#[inline]
+fn to_elem(block: &[u8]) -> u128 {
+    let mut buf = [0u8; 16];
+    buf[16 - block.len()..].copy_from_slice(block);
+    u128::from_be_bytes(buf)
+}
+
+/// Write the low bits of `x` into `block` as a big-endian integer.
+#[inline]
+fn from_elem(x: u128, block: &mut [u8]) {
+    let len = block.len();
+    block.copy_from_slice(&x.to_be_bytes()[16 - len..]);
+}
+
+impl<C: fmt::Debug> fmt::Debug for Mgm<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mgm").field("cipher", &self.cipher).finish()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{BlockSize, Key};
+use crate::{Backend, BlockSize, Key};
 use cipher::{
     BlockCipherDecClosure, BlockCipherDecrypt, BlockCipherEncClosure, BlockCipherEncrypt,
 };
@@ -38,6 +38,28 @@ impl From<EncKeys> for DecKeys {
     }
 }
 
+pub(crate) fn backend() -> Backend {
+    Backend::Neon
+}
+
+impl EncDecKeys {
+    pub fn backend(&self) -> Backend {
+        backend()
+    }
+}
+
+impl EncKeys {
+    pub fn backend(&self) -> Backend {
+        backend()
+    }
+}
+
+impl DecKeys {
+    pub fn backend(&self) -> Backend {
+        backend()
+    }
+}
+
 impl BlockCipherEncrypt for crate::Kuznyechik {
     fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = BlockSize>) {
         f.call(&EncBackend(&self.keys.enc));
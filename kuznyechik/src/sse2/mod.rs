@@ -1,13 +1,11 @@
 //! SSE2-based implementation based on <https://github.com/aprelev/lg15>
 
-use crate::{BlockSize, Key};
-use cipher::{
-    BlockCipherDecClosure, BlockCipherDecrypt, BlockCipherEncClosure, BlockCipherEncrypt,
-};
+use crate::Key;
 
 mod backends;
 
-use backends::{DecBackend, EncBackend, RoundKeys, expand_enc_keys, inv_enc_keys};
+pub(crate) use backends::{DecBackend, EncBackend};
+use backends::{RoundKeys, expand_enc_keys, inv_enc_keys};
 
 #[derive(Clone)]
 pub(crate) struct EncDecKeys {
@@ -40,26 +38,31 @@ impl From<EncKeys> for DecKeys {
     }
 }
 
-impl BlockCipherEncrypt for crate::Kuznyechik {
-    fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = BlockSize>) {
-        f.call(&EncBackend(&self.keys.enc));
+impl EncDecKeys {
+    pub(crate) fn enc_backend(&self) -> EncBackend<'_> {
+        EncBackend(&self.enc)
     }
-}
 
-impl BlockCipherDecrypt for crate::Kuznyechik {
-    fn decrypt_with_backend(&self, f: impl BlockCipherDecClosure<BlockSize = BlockSize>) {
-        f.call(&DecBackend(&self.keys.dec));
+    pub(crate) fn dec_backend(&self) -> DecBackend<'_> {
+        DecBackend(&self.dec)
     }
 }
 
-impl BlockCipherEncrypt for crate::KuznyechikEnc {
-    fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = BlockSize>) {
-        f.call(&EncBackend(&self.keys.0));
+impl EncKeys {
+    pub(crate) fn enc_backend(&self) -> EncBackend<'_> {
+        EncBackend(&self.0)
+    }
+
+    /// Return the round keys as bytes.
+    pub(crate) fn to_bytes(&self) -> [[u8; 16]; 10] {
+        // SAFETY: `__m128i` and `[u8; 16]` have the same size and any bit
+        // pattern is valid for both
+        self.0.map(|k| unsafe { core::mem::transmute(k) })
     }
 }
 
-impl BlockCipherDecrypt for crate::KuznyechikDec {
-    fn decrypt_with_backend(&self, f: impl BlockCipherDecClosure<BlockSize = BlockSize>) {
-        f.call(&DecBackend(&self.keys.0));
+impl DecKeys {
+    pub(crate) fn dec_backend(&self) -> DecBackend<'_> {
+        DecBackend(&self.0)
     }
 }
@@ -0,0 +1,246 @@
+//! Test vectors from RFC 8645, Appendix A, and the OMAC examples of
+//! GOST R 34.13-2015
+#![cfg(feature = "acpkm")]
+
+use cipher::StreamCipher;
+use hex_literal::hex;
+use kuznyechik::acpkm::{
+    AcpkmCipher, CtrAcpkm, Error, KuznyechikCtrAcpkm, KuznyechikOmac, KuznyechikOmacAcpkm, Omac,
+    OmacAcpkm,
+};
+use magma::Magma;
+
+const KEY: [u8; 32] = hex!(
+    "8899AABBCCDDEEFF0011223344556677"
+    "FEDCBA98765432100123456789ABCDEF"
+);
+const PT: [u8; 112] = hex!(
+    "1122334455667700FFEEDDCCBBAA9988"
+    "00112233445566778899AABBCCEEFF0A"
+    "112233445566778899AABBCCEEFF0A00"
+    "2233445566778899AABBCCEEFF0A0011"
+    "33445566778899AABBCCEEFF0A001122"
+    "445566778899AABBCCEEFF0A00112233"
+    "5566778899AABBCCEEFF0A0011223344"
+);
+
+const KUZNYECHIK_ICN: [u8; 8] = hex!("1234567890ABCEF0");
+const KUZNYECHIK_CT: [u8; 112] = hex!(
+    "F195D8BEC10ED1DBD57B5FA240BDA1B8"
+    "85EEE733F6A13E5DF33CE4B33C45DEE4"
+    "4BCEEB8F646F4C55001706275E85E800"
+    "587C4DF568D094393E4834AFD0805046"
+    "CF30F57686AEECE11CFC6C316B8A896E"
+    "DFFD07EC813636460C4F3B743423163E"
+    "6409A9C282FAC8D469D221E7FBD6DE5D"
+);
+
+const MAGMA_ICN: [u8; 4] = hex!("12345678");
+const MAGMA_CT: [u8; 112] = hex!(
+    "2AB81DEEEB1E4CAB68E104C4BD6B94EA"
+    "C72C67AF6C2E5B6B0EAFB61770F1B32E"
+    "A1AE71149EED1382ABD467180672EC6F"
+    "84A2F15B3FCA72C15559FBD38C4C7C5D"
+    "A90D5ADBBD3D22F92B2283B686439FB4"
+    "796FA8A3FE3B7EC39E48C896F90E1097"
+    "A9351073A37A742C0569C8D445FAEAC5"
+);
+
+fn kuznyechik_ctr() -> KuznyechikCtrAcpkm {
+    KuznyechikCtrAcpkm::new(&KEY.into(), &KUZNYECHIK_ICN.into(), 32).unwrap()
+}
+
+fn magma_ctr() -> CtrAcpkm<Magma> {
+    CtrAcpkm::<Magma>::new(&KEY.into(), &MAGMA_ICN.into(), 16).unwrap()
+}
+
+fn check_ctr<C: StreamCipher>(new: impl Fn() -> C, pt: &[u8], ct: &[u8]) {
+    let mut buf = pt.to_vec();
+    new().apply_keystream(&mut buf);
+    assert_eq!(buf, ct);
+    new().apply_keystream(&mut buf);
+    assert_eq!(buf, pt);
+
+    // Processing the data in pieces gives the same result
+    for step in [1, 3, 7, 8, 15, 16, 17, 33, 70] {
+        let mut cipher = new();
+        let mut buf = pt.to_vec();
+        for chunk in buf.chunks_mut(step) {
+            cipher.apply_keystream(chunk);
+        }
+        assert_eq!(buf, ct, "step: {step}");
+    }
+}
+
+#[test]
+fn kuznyechik_ctr_acpkm() {
+    check_ctr(kuznyechik_ctr, &PT, &KUZNYECHIK_CT);
+}
+
+#[test]
+fn magma_ctr_acpkm() {
+    check_ctr(magma_ctr, &PT, &MAGMA_CT);
+}
+
+#[test]
+fn ctr_acpkm_write_keystream() {
+    let mut ks = [0u8; 112];
+    kuznyechik_ctr().write_keystream(&mut ks);
+    for ((k, p), c) in ks.iter().zip(PT).zip(KUZNYECHIK_CT) {
+        assert_eq!(k ^ p, c);
+    }
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn ctr_acpkm_counter_limit() {
+    // 2^32 blocks of keystream are available with a 64-bit block
+    let limit = 8usize << 32;
+    let mut cipher = magma_ctr();
+    assert!(cipher.check_remaining(limit).is_ok());
+    assert!(cipher.check_remaining(limit + 1).is_err());
+
+    cipher.apply_keystream(&mut [0u8; 5]);
+    assert!(cipher.check_remaining(limit - 5).is_ok());
+    assert!(cipher.check_remaining(limit - 4).is_err());
+}
+
+#[test]
+fn invalid_section_size() {
+    let key = KEY.into();
+    for size in [0, 8, 31] {
+        let res = KuznyechikCtrAcpkm::new(&key, &KUZNYECHIK_ICN.into(), size);
+        assert_eq!(res.unwrap_err(), Error::InvalidSectionSize);
+    }
+    assert_eq!(
+        CtrAcpkm::<Magma>::new(&key, &MAGMA_ICN.into(), 12).unwrap_err(),
+        Error::InvalidSectionSize,
+    );
+    assert_eq!(
+        KuznyechikOmacAcpkm::new(&key, 32, 40).unwrap_err(),
+        Error::InvalidSectionSize,
+    );
+    assert_eq!(
+        OmacAcpkm::<Magma>::new(&key, 4, 80).unwrap_err(),
+        Error::InvalidSectionSize,
+    );
+}
+
+/// Interface shared by [`Omac`] and [`OmacAcpkm`].
+trait Mac: Clone {
+    fn update(&mut self, data: &[u8]);
+    fn finalize(self) -> Vec<u8>;
+    fn verify(self, tag: &[u8]) -> Result<(), Error>;
+}
+
+macro_rules! impl_mac {
+    ($mac:ident) => {
+        impl<C: AcpkmCipher + Clone> Mac for $mac<C> {
+            fn update(&mut self, data: &[u8]) {
+                $mac::update(self, data)
+            }
+
+            fn finalize(self) -> Vec<u8> {
+                $mac::finalize(self).to_vec()
+            }
+
+            fn verify(self, tag: &[u8]) -> Result<(), Error> {
+                $mac::verify(self, tag)
+            }
+        }
+    };
+}
+
+impl_mac!(Omac);
+impl_mac!(OmacAcpkm);
+
+/// Check the MAC of `msg`, of which `tag` may be a truncated prefix.
+fn check_omac<M: Mac>(new: impl Fn() -> M, msg: &[u8], tag: &[u8]) {
+    let mut mac = new();
+    mac.update(msg);
+    assert_eq!(&mac.finalize()[..tag.len()], tag);
+
+    for step in [1, 3, 8, 16, 17] {
+        let mut mac = new();
+        for chunk in msg.chunks(step) {
+            mac.update(chunk);
+        }
+        assert_eq!(&mac.clone().finalize()[..tag.len()], tag, "step: {step}");
+        mac.verify(tag).unwrap();
+    }
+
+    // Truncated tags
+    for len in [4, tag.len().div_ceil(2).max(4)] {
+        let mut mac = new();
+        mac.update(msg);
+        mac.verify(&tag[..len]).unwrap();
+    }
+    for len in [0, 3] {
+        let mut mac = new();
+        mac.update(msg);
+        assert_eq!(mac.verify(&tag[..len]), Err(Error::Verification));
+    }
+
+    let mut bad_tag = tag.to_vec();
+    bad_tag[0] ^= 1;
+    let mut mac = new();
+    mac.update(msg);
+    assert_eq!(mac.verify(&bad_tag), Err(Error::Verification));
+
+    let mut bad_msg = msg.to_vec();
+    *bad_msg.last_mut().unwrap() ^= 1;
+    let mut mac = new();
+    mac.update(&bad_msg);
+    assert_eq!(mac.verify(tag), Err(Error::Verification));
+}
+
+#[test]
+fn kuznyechik_omac_acpkm() {
+    let new = || KuznyechikOmacAcpkm::new(&KEY.into(), 32, 96).unwrap();
+    check_omac(new, &PT[..24], &hex!("B5367F47B62B995EEB2A648C5843145E"));
+    check_omac(new, &PT[..80], &hex!("FBB8DCEE45BEA67C35F58C5700898E5D"));
+}
+
+#[test]
+fn magma_omac_acpkm() {
+    let new = || OmacAcpkm::<Magma>::new(&KEY.into(), 16, 80).unwrap();
+    check_omac(new, &PT[..12], &hex!("A0540E3730ACBCF3"));
+    check_omac(new, &PT[..40], &hex!("34008DAD5496BB8E"));
+}
+
+#[test]
+fn kuznyechik_omac() {
+    // GOST R 34.13-2015, A.1.6, with the tag truncated to 64 bits
+    let new = || KuznyechikOmac::new(&KEY.into());
+    check_omac(new, &PT[..64], &hex!("336F4D296059FBE3"));
+}
+
+#[test]
+fn magma_omac() {
+    // GOST R 34.13-2015, A.2.6, with the tag truncated to 32 bits
+    let key = hex!(
+        "FFEEDDCCBBAA99887766554433221100"
+        "F0F1F2F3F4F5F6F7F8F9FAFBFCFDFEFF"
+    );
+    let pt = hex!(
+        "92DEF06B3C130A59DB54C704F8189D20"
+        "4A98FB2E67A8024C8912409B17B57E41"
+    );
+    let new = || Omac::<Magma>::new(&key.into());
+    check_omac(new, &pt, &hex!("154E7210"));
+}
+
+#[test]
+fn omac_acpkm_empty_message() {
+    // An empty message is a single padded block
+    let mac = KuznyechikOmacAcpkm::new(&KEY.into(), 32, 96).unwrap();
+    let tag = mac.finalize();
+
+    let mut mac = KuznyechikOmacAcpkm::new(&KEY.into(), 32, 96).unwrap();
+    mac.update(&[]);
+    assert_eq!(mac.finalize(), tag);
+
+    let mut mac = KuznyechikOmacAcpkm::new(&KEY.into(), 32, 96).unwrap();
+    mac.update(&[0x80]);
+    assert_ne!(mac.finalize(), tag);
+}
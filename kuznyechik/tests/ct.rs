@@ -0,0 +1,168 @@
+//! Timing tests for the constant-time backend in the style of dudect, see
+//! "Dude, is my code constant time?" by O. Reparaz, J. Balasch and
+//! I. Verbauwhede.
+//!
+//! Execution times are measured for two classes of inputs: the all-zero input
+//! and random inputs, with the class of every measurement chosen at random. The
+//! distributions of the execution times are compared with the Welch's t-test
+//! after cropping the measurements at several percentiles.
+//!
+//! The results depend on the load of the machine, so the timing tests are
+//! ignored by default. Run them with optimizations enabled:
+//!
+//! ```sh
+//! RUSTFLAGS='--cfg kuznyechik_backend="ct"' cargo test --release --test ct -- --ignored
+//! ```
+#![cfg(kuznyechik_backend = "ct")]
+
+use cipher::{BlockCipherDecrypt, BlockCipherEncrypt, KeyInit};
+use kuznyechik::{Backend, Kuznyechik};
+use std::{hint::black_box, time::Instant};
+
+const MEASUREMENTS: usize = 20_000;
+
+/// Value of the t-statistic above which the timings are considered to
+/// depend on the input class.
+const THRESHOLD: f64 = 10.0;
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(8) {
+            let v = self.next().to_le_bytes();
+            chunk.copy_from_slice(&v[..chunk.len()]);
+        }
+    }
+}
+
+/// Online mean and variance of a sample.
+#[derive(Default)]
+struct Stats {
+    n: f64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Stats {
+    fn push(&mut self, x: f64) {
+        self.n += 1.0;
+        let delta = x - self.mean;
+        self.mean += delta / self.n;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    fn var(&self) -> f64 {
+        self.m2 / (self.n - 1.0)
+    }
+}
+
+fn welch_t(a: &Stats, b: &Stats) -> f64 {
+    (a.mean - b.mean) / (a.var() / a.n + b.var() / b.n).sqrt()
+}
+
+/// Measure `f` on the all-zero input and random inputs and return the largest
+/// absolute value of the t-statistic.
+fn max_t<const N: usize>(mut f: impl FnMut(&[u8; N])) -> f64 {
+    let mut rng = XorShift(0x0123_4567_89AB_CDEF);
+    let (inputs, classes): (Vec<[u8; N]>, Vec<bool>) = (0..MEASUREMENTS)
+        .map(|_| {
+            let class = rng.next() & 1 == 1;
+            let mut input = [0u8; N];
+            if class {
+                rng.fill(&mut input);
+            }
+            (input, class)
+        })
+        .unzip();
+
+    // Warm up caches and branch predictors
+    for input in &inputs[..MEASUREMENTS / 10] {
+        f(input);
+    }
+
+    let times: Vec<f64> = inputs
+        .iter()
+        .map(|input| {
+            let start = Instant::now();
+            f(black_box(input));
+            start.elapsed().as_nanos() as f64
+        })
+        .collect();
+
+    let mut sorted = times.clone();
+    sorted.sort_by(f64::total_cmp);
+
+    let mut res = 0f64;
+    for pct in [100, 99, 95, 90, 80, 50] {
+        let limit = sorted[(sorted.len() - 1) * pct / 100];
+        let mut stats = [Stats::default(), Stats::default()];
+        for (&t, &class) in times.iter().zip(&classes) {
+            if t <= limit {
+                stats[usize::from(class)].push(t);
+            }
+        }
+        res = res.max(welch_t(&stats[0], &stats[1]).abs());
+    }
+    res
+}
+
+#[test]
+fn backend() {
+    assert_eq!(kuznyechik::backend(), Backend::Ct);
+}
+
+/// Check that the test detects an implementation with input-dependent timing.
+#[test]
+#[ignore = "timing measurement, run with `--release -- --ignored`"]
+fn detects_leak() {
+    let t = max_t(|input: &[u8; 64]| {
+        // Comparison with the all-zero input exits early for random inputs
+        for &b in input {
+            if black_box(b) != 0 {
+                break;
+            }
+        }
+    });
+    assert!(t > THRESHOLD, "t = {t}");
+}
+
+#[test]
+#[ignore = "timing measurement, run with `--release -- --ignored`"]
+fn encrypt() {
+    let cipher = Kuznyechik::new(&[0x42; 32].into());
+    let t = max_t(|input: &[u8; 16]| {
+        let mut block = (*input).into();
+        cipher.encrypt_block(&mut block);
+        black_box(block);
+    });
+    assert!(t < THRESHOLD, "t = {t}");
+}
+
+#[test]
+#[ignore = "timing measurement, run with `--release -- --ignored`"]
+fn decrypt() {
+    let cipher = Kuznyechik::new(&[0x42; 32].into());
+    let t = max_t(|input: &[u8; 16]| {
+        let mut block = (*input).into();
+        cipher.decrypt_block(&mut block);
+        black_box(block);
+    });
+    assert!(t < THRESHOLD, "t = {t}");
+}
+
+#[test]
+#[ignore = "timing measurement, run with `--release -- --ignored`"]
+fn key_expansion() {
+    let t = max_t(|input: &[u8; 32]| {
+        black_box(Kuznyechik::new(&(*input).into()));
+    });
+    assert!(t < THRESHOLD, "t = {t}");
+}
@@ -0,0 +1,172 @@
+//! Test vectors from RFC 9058, Appendix A
+#![cfg(feature = "mgm")]
+
+use cipher::KeyInit;
+use hex_literal::hex;
+use kuznyechik::mgm::{
+    KuznyechikMgm, Mgm,
+    aead::{AeadInOut, Nonce, Tag},
+};
+use magma::Magma;
+
+const KUZNYECHIK_KEY: [u8; 32] = hex!(
+    "8899AABBCCDDEEFF0011223344556677"
+    "FEDCBA98765432100123456789ABCDEF"
+);
+const KUZNYECHIK_NONCE: [u8; 16] = hex!("1122334455667700FFEEDDCCBBAA9988");
+const KUZNYECHIK_AD: [u8; 41] = hex!(
+    "02020202020202020101010101010101"
+    "04040404040404040303030303030303"
+    "EA0505050505050505"
+);
+const KUZNYECHIK_PT: [u8; 67] = hex!(
+    "1122334455667700FFEEDDCCBBAA9988"
+    "00112233445566778899AABBCCEEFF0A"
+    "112233445566778899AABBCCEEFF0A00"
+    "2233445566778899AABBCCEEFF0A0011"
+    "AABBCC"
+);
+const KUZNYECHIK_CT: [u8; 67] = hex!(
+    "A9757B8147956E9055B8A33DE89F42FC"
+    "8075D2212BF9FD5BD3F7069AADC16B39"
+    "497AB15915A6BA85936B5D0EA9F6851C"
+    "C60C14D4D3F883D0AB94420695C76DEB"
+    "2C7552"
+);
+const KUZNYECHIK_TAG: [u8; 16] = hex!("CF5D656F40C34F5C46E8BB0E29FCDB4C");
+
+const MAGMA_KEY: [u8; 32] = hex!(
+    "FFEEDDCCBBAA99887766554433221100"
+    "F0F1F2F3F4F5F6F7F8F9FAFBFCFDFEFF"
+);
+const MAGMA_NONCE: [u8; 8] = hex!("12DEF06B3C130A59");
+const MAGMA_AD: [u8; 41] = hex!(
+    "01010101010101010202020202020202"
+    "03030303030303030404040404040404"
+    "0505050505050505EA"
+);
+const MAGMA_PT: [u8; 67] = hex!(
+    "FFEEDDCCBBAA99881122334455667700"
+    "8899AABBCCEEFF0A0011223344556677"
+    "99AABBCCEEFF0A001122334455667788"
+    "AABBCCEEFF0A00112233445566778899"
+    "AABBCC"
+);
+const MAGMA_CT: [u8; 67] = hex!(
+    "C795066C5F9EA03B85113342459185AE"
+    "1F2E00D6BF2B785D940470B8BB9C8E7D"
+    "9A5DD3731F7DDC70EC27CB0ACE6FA576"
+    "70F65C646ABB75D547AA37C3BCB5C34E"
+    "03BB9C"
+);
+const MAGMA_TAG: [u8; 8] = hex!("A7928069AA10FD10");
+
+fn check<A: AeadInOut + KeyInit>(
+    key: &[u8],
+    nonce: &[u8],
+    ad: &[u8],
+    pt: &[u8],
+    ct: &[u8],
+    tag: &[u8],
+) {
+    let cipher = A::new_from_slice(key).unwrap();
+    let nonce = Nonce::<A>::try_from(nonce).unwrap();
+
+    let mut buf = pt.to_vec();
+    let res = cipher
+        .encrypt_inout_detached(&nonce, ad, buf.as_mut_slice().into())
+        .unwrap();
+    assert_eq!(buf, ct);
+    assert_eq!(res[..], *tag);
+
+    let tag = Tag::<A>::try_from(tag).unwrap();
+    cipher
+        .decrypt_inout_detached(&nonce, ad, buf.as_mut_slice().into(), &tag)
+        .unwrap();
+    assert_eq!(buf, pt);
+
+    // Attached tag
+    let mut buf = pt.to_vec();
+    cipher.encrypt_in_place(&nonce, ad, &mut buf).unwrap();
+    assert_eq!(buf[..ct.len()], *ct);
+    assert_eq!(buf[ct.len()..], tag[..]);
+    cipher.decrypt_in_place(&nonce, ad, &mut buf).unwrap();
+    assert_eq!(buf, pt);
+
+    // Every prefix of the associated data and the message
+    for ad_len in [0, 1, 16, 17, ad.len()] {
+        for pt_len in [0, 1, 8, 15, 16, 33, pt.len()] {
+            if ad_len + pt_len == 0 {
+                continue;
+            }
+            let mut buf = pt[..pt_len].to_vec();
+            let tag = cipher
+                .encrypt_inout_detached(&nonce, &ad[..ad_len], buf.as_mut_slice().into())
+                .unwrap();
+            assert_eq!(buf, ct[..pt_len]);
+            cipher
+                .decrypt_inout_detached(&nonce, &ad[..ad_len], buf.as_mut_slice().into(), &tag)
+                .unwrap();
+            assert_eq!(buf, pt[..pt_len]);
+        }
+    }
+
+    // Any modification must be detected without decrypting the ciphertext
+    for i in 0..ct.len() + tag.len() {
+        let mut buf = ct.to_vec();
+        let mut tag = tag.clone();
+        if i < ct.len() {
+            buf[i] ^= 0x40;
+        } else {
+            tag[i - ct.len()] ^= 0x01;
+        }
+        let res = cipher.decrypt_inout_detached(&nonce, ad, buf.as_mut_slice().into(), &tag);
+        assert!(res.is_err());
+        if i >= ct.len() {
+            assert_eq!(buf, ct);
+        }
+    }
+    let mut buf = ct.to_vec();
+    let res = cipher.decrypt_inout_detached(&nonce, &ad[1..], buf.as_mut_slice().into(), &tag);
+    assert!(res.is_err());
+}
+
+#[test]
+fn kuznyechik_mgm() {
+    check::<KuznyechikMgm>(
+        &KUZNYECHIK_KEY,
+        &KUZNYECHIK_NONCE,
+        &KUZNYECHIK_AD,
+        &KUZNYECHIK_PT,
+        &KUZNYECHIK_CT,
+        &KUZNYECHIK_TAG,
+    );
+}
+
+#[test]
+fn magma_mgm() {
+    check::<Mgm<Magma>>(
+        &MAGMA_KEY,
+        &MAGMA_NONCE,
+        &MAGMA_AD,
+        &MAGMA_PT,
+        &MAGMA_CT,
+        &MAGMA_TAG,
+    );
+}
+
+#[test]
+fn invalid_inputs() {
+    let cipher = KuznyechikMgm::new(&KUZNYECHIK_KEY.into());
+    let mut buf = [0u8; 16];
+
+    // Most significant bit of the nonce must be zero
+    let nonce = Nonce::<KuznyechikMgm>::from([0x80; 16]);
+    let res = cipher.encrypt_inout_detached(&nonce, b"", (&mut buf[..]).into());
+    assert!(res.is_err());
+
+    // At least one of the associated data and the message must be non-empty
+    let nonce = Nonce::<KuznyechikMgm>::from(KUZNYECHIK_NONCE);
+    let res = cipher.encrypt_inout_detached(&nonce, b"", (&mut buf[..0]).into());
+    assert!(res.is_err());
+}
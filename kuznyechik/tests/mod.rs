@@ -174,3 +174,16 @@ fn kuznyechik_chain() {
     }
     assert_eq!(blocks, INIT_BLOCKS);
 }
+
+#[test]
+fn backend() {
+    let key = Array([0x42; 32]);
+    let backend = kuznyechik::backend();
+    assert_eq!(Kuznyechik::new(&key).backend(), backend);
+    assert_eq!(KuznyechikEnc::new(&key).backend(), backend);
+    assert_eq!(KuznyechikDec::new(&key).backend(), backend);
+
+    let enc = KuznyechikEnc::new(&key);
+    assert_eq!(Kuznyechik::from(&enc).backend(), backend);
+    assert_eq!(KuznyechikDec::from(enc).backend(), backend);
+}
@@ -2,7 +2,18 @@
 extern crate test;
 
 use cipher::{block_decryptor_bench, block_encryptor_bench};
-use magma::Magma;
+use magma::{Gost89CryptoProA, Magma};
 
 block_encryptor_bench!(Key: Magma, magma_encrypt_block, magma_encrypt_blocks);
 block_decryptor_bench!(Key: Magma, magma_decrypt_block, magma_decrypt_blocks);
+
+block_encryptor_bench!(
+    Key: Gost89CryptoProA,
+    gost89_cryptopro_a_encrypt_block,
+    gost89_cryptopro_a_encrypt_blocks,
+);
+block_decryptor_bench!(
+    Key: Gost89CryptoProA,
+    gost89_cryptopro_a_decrypt_block,
+    gost89_cryptopro_a_decrypt_blocks,
+);
@@ -0,0 +1,172 @@
+//! Constant-time bitsliced software backend.
+//!
+//! The state of 32 blocks is stored as bit planes: word `i` of a block half
+//! holds bit `i` of that half of every block. The key addition is computed
+//! with a ripple-carry adder, the S-box with its algebraic normal form and
+//! the rotation by renaming the planes, so neither memory accesses nor
+//! branches depend on the key, the data or the S-box of [`Gost89Dyn`].
+//!
+//! [`Gost89Dyn`]: crate::Gost89Dyn
+
+use crate::{DEC_ORDER, ENC_ORDER, MAC_ORDER, sboxes::Anf};
+use cipher::{
+    Block, BlockCipherDecBackend, BlockCipherEncBackend, BlockSizeUser, InOut, ParBlocks,
+    ParBlocksSizeUser, consts,
+};
+
+/// Bit planes of a block half of 32 blocks.
+type Planes = [u32; 32];
+
+/// Transpose the 32x32 bit matrix with rows stored in `m`.
+#[inline(always)]
+fn transpose(m: &mut Planes) {
+    let mut j = 16;
+    let mut mask = 0x0000_FFFFu32;
+    while j != 0 {
+        let mut k = 0;
+        while k < 32 {
+            let t = ((m[k] >> j) ^ m[k + j]) & mask;
+            m[k] ^= t << j;
+            m[k + j] ^= t;
+            k = (k + j + 1) & !j;
+        }
+        j >>= 1;
+        mask ^= mask << j;
+    }
+}
+
+/// Compute the function `g` of the block halves `x` with the key word `k`.
+#[inline(always)]
+fn g(anf: &Anf, x: &Planes, k: u32) -> Planes {
+    // Addition modulo 2^32
+    let mut sum = [0u32; 32];
+    let mut carry = 0;
+    for i in 0..32 {
+        let kb = 0u32.wrapping_sub((k >> i) & 1);
+        let t = x[i] ^ kb;
+        sum[i] = t ^ carry;
+        carry = (x[i] & kb) | (carry & t);
+    }
+
+    let mut res = [0u32; 32];
+    for p in 0..8 {
+        let mut mons = [!0u32; 16];
+        for m in 1..16usize {
+            let i = m.trailing_zeros() as usize;
+            mons[m] = mons[m & (m - 1)] & sum[4 * p + i];
+        }
+        for c in 0..4 {
+            // Every monomial is masked by its coefficient, so neither the
+            // data nor the S-box of `Gost89Dyn` affect the executed code
+            let coeffs = u32::from(anf[p][c]);
+            let mut v = 0;
+            for (m, mon) in mons.iter().enumerate() {
+                v ^= mon & 0u32.wrapping_sub((coeffs >> m) & 1);
+            }
+            // Rotation left by 11 bits
+            res[(4 * p + c + 11) % 32] = v;
+        }
+    }
+    res
+}
+
+/// Apply the rounds with the key words in `order` to the block halves `a`
+/// and `b`.
+#[inline(always)]
+fn rounds(sbox: &Anf, key: &[u32; 8], order: &[usize], a: &mut Planes, b: &mut Planes) {
+    for &i in order {
+        let t = g(sbox, b, key[i]);
+        for (a, t) in a.iter_mut().zip(t) {
+            *a ^= t;
+        }
+        core::mem::swap(a, b);
+    }
+}
+
+fn crypt(sbox: &Anf, key: &[u32; 8], order: &[usize; 32], blocks: &[[u8; 8]; 32]) -> [[u8; 8]; 32] {
+    let mut a = [0u32; 32];
+    let mut b = [0u32; 32];
+    for j in 0..32 {
+        a[j] = u32::from_be_bytes(blocks[j][..4].try_into().unwrap());
+        b[j] = u32::from_be_bytes(blocks[j][4..].try_into().unwrap());
+    }
+    transpose(&mut a);
+    transpose(&mut b);
+    rounds(sbox, key, order, &mut a, &mut b);
+    transpose(&mut a);
+    transpose(&mut b);
+    let mut res = [[0; 8]; 32];
+    for j in 0..32 {
+        res[j][..4].copy_from_slice(&b[j].to_be_bytes());
+        res[j][4..].copy_from_slice(&a[j].to_be_bytes());
+    }
+    res
+}
+
+pub(crate) fn mac_block(sbox: &Anf, key: &[u32; 8], block: &mut [u8; 8]) {
+    let mut a = [0u32; 32];
+    let mut b = [0u32; 32];
+    a[0] = u32::from_be_bytes(block[..4].try_into().unwrap());
+    b[0] = u32::from_be_bytes(block[4..].try_into().unwrap());
+    transpose(&mut a);
+    transpose(&mut b);
+    rounds(sbox, key, &MAC_ORDER, &mut a, &mut b);
+    transpose(&mut a);
+    transpose(&mut b);
+    block[..4].copy_from_slice(&a[0].to_be_bytes());
+    block[4..].copy_from_slice(&b[0].to_be_bytes());
+}
+
+fn crypt_block(sbox: &Anf, key: &[u32; 8], order: &[usize; 32], block: &[u8; 8]) -> [u8; 8] {
+    let mut blocks = [[0; 8]; 32];
+    blocks[0] = *block;
+    crypt(sbox, key, order, &blocks)[0]
+}
+
+pub(crate) struct EncBackend<'a>(pub(crate) &'a [u32; 8], pub(crate) &'a Anf);
+
+impl BlockSizeUser for EncBackend<'_> {
+    type BlockSize = consts::U8;
+}
+
+impl ParBlocksSizeUser for EncBackend<'_> {
+    type ParBlocksSize = consts::U32;
+}
+
+impl BlockCipherEncBackend for EncBackend<'_> {
+    #[inline]
+    fn encrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
+        block.get_out().0 = crypt_block(self.1, self.0, &ENC_ORDER, &block.get_in().0);
+    }
+
+    #[inline]
+    fn encrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let input = blocks.get_in().0.map(|b| b.0);
+        let res = crypt(self.1, self.0, &ENC_ORDER, &input);
+        blocks.get_out().0 = res.map(Into::into);
+    }
+}
+
+pub(crate) struct DecBackend<'a>(pub(crate) &'a [u32; 8], pub(crate) &'a Anf);
+
+impl BlockSizeUser for DecBackend<'_> {
+    type BlockSize = consts::U8;
+}
+
+impl ParBlocksSizeUser for DecBackend<'_> {
+    type ParBlocksSize = consts::U32;
+}
+
+impl BlockCipherDecBackend for DecBackend<'_> {
+    #[inline]
+    fn decrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
+        block.get_out().0 = crypt_block(self.1, self.0, &DEC_ORDER, &block.get_in().0);
+    }
+
+    #[inline]
+    fn decrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let input = blocks.get_in().0.map(|b| b.0);
+        let res = crypt(self.1, self.0, &DEC_ORDER, &input);
+        blocks.get_out().0 = res.map(Into::into);
+    }
+}
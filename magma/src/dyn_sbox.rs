@@ -0,0 +1,177 @@
+//! GOST 28147-89 with S-boxes defined at runtime.
+
+use crate::{
+    Sbox, backend, key_words,
+    sboxes::{ExpandedSbox, SmallSbox, expand},
+};
+use cipher::{
+    AlgorithmName, Block, BlockCipherDecClosure, BlockCipherDecrypt, BlockCipherEncClosure,
+    BlockCipherEncrypt, BlockSizeUser, Key, KeySizeUser,
+    consts::{U8, U32},
+};
+use core::fmt;
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// S-box of GOST 28147-89 defined at runtime.
+///
+/// GOST 28147-89 does not fix the S-box, and some implementations use their
+/// own S-boxes ("substitution blocks"). Row `i` substitutes the nibble `i` of
+/// the input of the round function, counting from the least significant one,
+/// as in [`Sbox::SBOX`]. Every row must be a permutation of the numbers from
+/// 0 to 15.
+#[derive(Clone)]
+pub struct DynSbox {
+    sbox: SmallSbox,
+    expanded: ExpandedSbox,
+}
+
+impl DynSbox {
+    /// Create a new S-box from its rows.
+    ///
+    /// Returns [`InvalidSbox`] if a row is not a permutation of the numbers
+    /// from 0 to 15.
+    pub fn new(sbox: &[[u8; 16]; 8]) -> Result<Self, InvalidSbox> {
+        for row in sbox {
+            let mut seen = 0u16;
+            for &v in row {
+                if v >= 16 {
+                    return Err(InvalidSbox);
+                }
+                seen |= 1 << v;
+            }
+            if seen != u16::MAX {
+                return Err(InvalidSbox);
+            }
+        }
+        Ok(Self {
+            sbox: *sbox,
+            expanded: expand(sbox),
+        })
+    }
+
+    /// Create a new S-box equal to the compile-time S-box `S`.
+    pub fn from_sbox<S: Sbox>() -> Self {
+        Self {
+            sbox: S::SBOX,
+            expanded: expand(&S::SBOX),
+        }
+    }
+
+    /// Rows of the S-box.
+    pub fn rows(&self) -> &[[u8; 16]; 8] {
+        &self.sbox
+    }
+}
+
+impl PartialEq for DynSbox {
+    fn eq(&self, other: &Self) -> bool {
+        self.sbox == other.sbox
+    }
+}
+
+impl Eq for DynSbox {}
+
+impl fmt::Debug for DynSbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DynSbox").field(&self.sbox).finish()
+    }
+}
+
+/// Block cipher defined in GOST 28147-89 with an S-box defined at runtime.
+///
+/// It is equivalent to [`Gost89`][crate::Gost89] with the same S-box. The
+/// expanded S-box is borrowed, so instances with the same S-box share it.
+pub struct Gost89Dyn<'a> {
+    pub(crate) key: [u32; 8],
+    pub(crate) sbox: &'a DynSbox,
+}
+
+impl<'a> Gost89Dyn<'a> {
+    /// Create a new instance from a key and an S-box.
+    pub fn new(key: &Key<Self>, sbox: &'a DynSbox) -> Self {
+        Self {
+            key: key_words(key),
+            sbox,
+        }
+    }
+
+    /// S-box used by the cipher.
+    pub fn sbox(&self) -> &'a DynSbox {
+        self.sbox
+    }
+
+    /// Apply the 16-round transformation of the GOST 28147-89 MAC
+    /// (imitovstavka) to `block`, see [`Gost89::mac_block`][crate::Gost89::mac_block].
+    pub fn mac_block(&self, block: &mut Block<Self>) {
+        backend::mac_block(&self.sbox.expanded, &self.key, &mut block.0);
+    }
+}
+
+impl KeySizeUser for Gost89Dyn<'_> {
+    type KeySize = U32;
+}
+
+impl BlockSizeUser for Gost89Dyn<'_> {
+    type BlockSize = U8;
+}
+
+impl BlockCipherEncrypt for Gost89Dyn<'_> {
+    #[inline]
+    fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = Self::BlockSize>) {
+        f.call(&backend::EncBackend(&self.key, &self.sbox.expanded))
+    }
+}
+
+impl BlockCipherDecrypt for Gost89Dyn<'_> {
+    #[inline]
+    fn decrypt_with_backend(&self, f: impl BlockCipherDecClosure<BlockSize = Self::BlockSize>) {
+        f.call(&backend::DecBackend(&self.key, &self.sbox.expanded))
+    }
+}
+
+impl Clone for Gost89Dyn<'_> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key,
+            sbox: self.sbox,
+        }
+    }
+}
+
+impl fmt::Debug for Gost89Dyn<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Gost89Dyn { ... }")
+    }
+}
+
+impl AlgorithmName for Gost89Dyn<'_> {
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Gost89Dyn")
+    }
+}
+
+impl Drop for Gost89Dyn<'_> {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        self.key.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Gost89Dyn<'_> {}
+
+/// Error returned by [`DynSbox::new`] for S-boxes with a row which is not a
+/// permutation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidSbox;
+
+impl fmt::Display for InvalidSbox {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("S-box row is not a permutation")
+    }
+}
+
+impl core::error::Error for InvalidSbox {}
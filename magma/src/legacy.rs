@@ -0,0 +1,87 @@
+//! Block cipher operations in the byte order of GOST 28147-89.
+//!
+//! Implementations of GOST 28147-89 based algorithms (RFC 4357, RFC 5830)
+//! load the key and the data as little-endian 32-bit words, with the first
+//! word of a block entering the round function first. GOST R 34.12-2015, and
+//! so [`Gost89`], uses big-endian words and the reverse order of the block
+//! halves. The key words are therefore byte-swapped, and blocks are reversed
+//! before and after applying [`Gost89`].
+
+use crate::{DynSbox, Gost89, Gost89Dyn, Magma, Sbox, modes::Gost89Cipher};
+use cipher::{Array, Key, consts::U8};
+use core::marker::PhantomData;
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::Zeroize;
+
+/// Block in the byte order of GOST 28147-89.
+pub(crate) type Block = Array<u8, U8>;
+
+/// Constant of the CryptoPro key meshing algorithm, RFC 4357 section 2.3.2.
+const MESHING_KEY: [u8; 32] = [
+    0x69, 0x00, 0x72, 0x22, 0x64, 0xC9, 0x04, 0x23, 0x8D, 0x3A, 0xDB, 0x96, 0x46, 0xE9, 0x2A, 0xC4,
+    0x18, 0xFE, 0xAC, 0x94, 0x00, 0xED, 0x07, 0x12, 0xC0, 0x86, 0xDC, 0xC2, 0xEF, 0x4C, 0xA9, 0x2B,
+];
+
+/// Number of blocks (1 KiB) processed with a key before CryptoPro key
+/// meshing.
+pub(crate) const MESHING_BLOCKS: u64 = 128;
+
+/// Load a key in the byte order of GOST 28147-89 as 32-bit words.
+fn key_words(key: &Key<Magma>) -> [u32; 8] {
+    let mut words = [0u32; 8];
+    for (w, chunk) in words.iter_mut().zip(key.chunks_exact(4)) {
+        *w = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    words
+}
+
+/// Initialize the cipher with a key in the byte order of GOST 28147-89.
+pub(crate) fn new_cipher<S: Sbox>(key: &Key<Gost89<S>>) -> Gost89<S> {
+    Gost89 {
+        key: key_words(key),
+        _p: PhantomData,
+    }
+}
+
+/// Initialize the cipher with a key in the byte order of GOST 28147-89 and
+/// an S-box defined at runtime.
+pub(crate) fn new_cipher_dyn<'a>(key: &Key<Magma>, sbox: &'a DynSbox) -> Gost89Dyn<'a> {
+    Gost89Dyn {
+        key: key_words(key),
+        sbox,
+    }
+}
+
+pub(crate) fn encrypt<C: Gost89Cipher>(cipher: &C, block: &mut Block) {
+    block.reverse();
+    cipher.encrypt_block(block);
+    block.reverse();
+}
+
+pub(crate) fn decrypt<C: Gost89Cipher>(cipher: &C, block: &mut Block) {
+    block.reverse();
+    cipher.decrypt_block(block);
+    block.reverse();
+}
+
+#[cfg(feature = "mac")]
+pub(crate) fn mac_block<C: Gost89Cipher>(cipher: &C, block: &mut Block) {
+    block.reverse();
+    cipher.mac_block(block);
+    block.reverse();
+}
+
+/// Replace the key using the CryptoPro key meshing algorithm, RFC 4357
+/// section 2.3.2.
+pub(crate) fn mesh_key<C: Gost89Cipher>(cipher: &mut C) {
+    let mut key = Key::<Magma>::from(MESHING_KEY);
+    for chunk in key.chunks_exact_mut(8) {
+        let block: &mut Block = chunk.try_into().unwrap();
+        decrypt(cipher, block);
+    }
+    cipher.set_key_words(key_words(&key));
+
+    #[cfg(feature = "zeroize")]
+    key.zeroize();
+}
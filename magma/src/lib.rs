@@ -33,6 +33,24 @@
 //! assert_eq!(&plaintext, block.as_slice());
 //! ```
 //!
+//! # Configuration Flags
+//!
+//! You can modify crate using the `magma_backend` configuration flag.
+//! It accepts the following values
+//!
+//! - `ct`: use constant-time bitsliced software backend without lookup tables
+//!   indexed by secret data. It processes 32 blocks in parallel and is
+//!   significantly slower than the default backend, especially for single
+//!   blocks.
+//!
+//! The flag can be enabled using `RUSTFLAGS` environment variable
+//! (e.g. `RUSTFLAGS='--cfg magma_backend="ct"'`) or by modifying
+//! `.cargo/config`.
+//!
+//! By default the crate uses the software backend with lookup tables, which
+//! combine the S-box with the rotation of the round function, and processes
+//! 4 blocks in parallel.
+//!
 //! [Magma]: https://en.wikipedia.org/wiki/GOST_(block_cipher)
 //! [GOST R 34.12-2015]: https://tc26.ru/standard/gost/GOST_R_3412-2015.pdf
 #![no_std]
@@ -57,12 +75,31 @@ use core::{fmt, marker::PhantomData};
 #[cfg(feature = "zeroize")]
 use cipher::zeroize::{Zeroize, ZeroizeOnDrop};
 
+#[cfg(magma_backend = "ct")]
+mod ct;
+mod dyn_sbox;
+mod legacy;
+#[cfg(feature = "mac")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mac")))]
+pub mod mac;
+pub mod modes;
 mod sboxes;
+#[cfg(not(magma_backend = "ct"))]
+mod soft;
+#[cfg(feature = "wrap")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wrap")))]
+pub mod wrap;
 
-pub use sboxes::Sbox;
+pub use dyn_sbox::{DynSbox, Gost89Dyn, InvalidSbox};
+pub use sboxes::{CryptoProA, CryptoProB, CryptoProC, CryptoProD, Sbox, Tc26, TestSbox};
 
 use sboxes::SboxExt;
 
+#[cfg(magma_backend = "ct")]
+use ct as backend;
+#[cfg(not(magma_backend = "ct"))]
+use soft as backend;
+
 /// Block cipher defined in GOST 28147-89 generic over S-box
 pub struct Gost89<S: Sbox> {
     key: [u32; 8],
@@ -76,17 +113,25 @@ impl<S: Sbox> KeySizeUser for Gost89<S> {
 impl<S: Sbox> KeyInit for Gost89<S> {
     #[inline]
     fn new(key: &Key<Self>) -> Self {
-        let mut key_u32 = [0u32; 8];
-        key.chunks_exact(4)
-            .zip(key_u32.iter_mut())
-            .for_each(|(chunk, v)| *v = to_u32(chunk));
         Self {
-            key: key_u32,
+            key: key_words(key),
             _p: PhantomData,
         }
     }
 }
 
+impl<S: Sbox> Gost89<S> {
+    /// Apply the 16-round transformation of the GOST 28147-89 MAC
+    /// (imitovstavka) to `block`.
+    ///
+    /// The transformation consists of the first 16 rounds of encryption
+    /// without the final swap of the block halves. The complete MAC is
+    /// implemented in the `mac` module.
+    pub fn mac_block(&self, block: &mut Block<Self>) {
+        backend::mac_block(S::EXPANDED, &self.key, &mut block.0);
+    }
+}
+
 impl<S: Sbox> BlockSizeUser for Gost89<S> {
     type BlockSize = U8;
 }
@@ -97,55 +142,29 @@ impl<S: Sbox> ParBlocksSizeUser for Gost89<S> {
 
 impl<S: Sbox> BlockCipherEncBackend for Gost89<S> {
     #[inline]
-    fn encrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
-        let b = block.get_in();
-        let mut v = (to_u32(&b[0..4]), to_u32(&b[4..8]));
-        for _ in 0..3 {
-            for i in 0..8 {
-                v = (v.1, v.0 ^ S::g(v.1, self.key[i]));
-            }
-        }
-        for i in (0..8).rev() {
-            v = (v.1, v.0 ^ S::g(v.1, self.key[i]));
-        }
-        let block = block.get_out();
-        block[0..4].copy_from_slice(&v.1.to_be_bytes());
-        block[4..8].copy_from_slice(&v.0.to_be_bytes());
+    fn encrypt_block(&self, block: InOut<'_, '_, Block<Self>>) {
+        backend::EncBackend(&self.key, S::EXPANDED).encrypt_block(block)
     }
 }
 
 impl<S: Sbox> BlockCipherEncrypt for Gost89<S> {
     #[inline]
     fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = Self::BlockSize>) {
-        f.call(self)
+        f.call(&backend::EncBackend(&self.key, S::EXPANDED))
     }
 }
 
 impl<S: Sbox> BlockCipherDecBackend for Gost89<S> {
     #[inline]
-    fn decrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
-        let b = block.get_in();
-        let mut v = (to_u32(&b[0..4]), to_u32(&b[4..8]));
-
-        for i in 0..8 {
-            v = (v.1, v.0 ^ S::g(v.1, self.key[i]));
-        }
-
-        for _ in 0..3 {
-            for i in (0..8).rev() {
-                v = (v.1, v.0 ^ S::g(v.1, self.key[i]));
-            }
-        }
-        let block = block.get_out();
-        block[0..4].copy_from_slice(&v.1.to_be_bytes());
-        block[4..8].copy_from_slice(&v.0.to_be_bytes());
+    fn decrypt_block(&self, block: InOut<'_, '_, Block<Self>>) {
+        backend::DecBackend(&self.key, S::EXPANDED).decrypt_block(block)
     }
 }
 
 impl<S: Sbox> BlockCipherDecrypt for Gost89<S> {
     #[inline]
     fn decrypt_with_backend(&self, f: impl BlockCipherDecClosure<BlockSize = Self::BlockSize>) {
-        f.call(self)
+        f.call(&backend::DecBackend(&self.key, S::EXPANDED))
     }
 }
 
@@ -193,19 +212,37 @@ impl<S: Sbox> Drop for Gost89<S> {
 impl<S: Sbox> ZeroizeOnDrop for Gost89<S> {}
 
 /// Block cipher defined in GOST R 34.12-2015 (Magma)
-pub type Magma = Gost89<sboxes::Tc26>;
+pub type Magma = Gost89<Tc26>;
 /// Block cipher defined in GOST 28147-89 with test S-box
-pub type Gost89Test = Gost89<sboxes::TestSbox>;
+pub type Gost89Test = Gost89<TestSbox>;
 /// Block cipher defined in GOST 28147-89 with CryptoPro S-box version A
-pub type Gost89CryptoProA = Gost89<sboxes::CryptoProA>;
+pub type Gost89CryptoProA = Gost89<CryptoProA>;
 /// Block cipher defined in GOST 28147-89 with CryptoPro S-box version B
-pub type Gost89CryptoProB = Gost89<sboxes::CryptoProB>;
+pub type Gost89CryptoProB = Gost89<CryptoProB>;
 /// Block cipher defined in GOST 28147-89 with CryptoPro S-box version C
-pub type Gost89CryptoProC = Gost89<sboxes::CryptoProC>;
+pub type Gost89CryptoProC = Gost89<CryptoProC>;
 /// Block cipher defined in GOST 28147-89 with CryptoPro S-box version D
-pub type Gost89CryptoProD = Gost89<sboxes::CryptoProD>;
+pub type Gost89CryptoProD = Gost89<CryptoProD>;
 
+/// Load the key as big-endian 32-bit words.
 #[inline(always)]
-fn to_u32(chunk: &[u8]) -> u32 {
-    u32::from_be_bytes(chunk.try_into().unwrap())
+fn key_words(key: &Key<Magma>) -> [u32; 8] {
+    let mut words = [0u32; 8];
+    key.chunks_exact(4)
+        .zip(words.iter_mut())
+        .for_each(|(chunk, v)| *v = u32::from_be_bytes(chunk.try_into().unwrap()));
+    words
 }
+
+/// Order of the key words in the rounds of encryption.
+const ENC_ORDER: [usize; 32] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 0, 1, 2, 3, 4, 5, 6, 7, 0, 1, 2, 3, 4, 5, 6, 7, 7, 6, 5, 4, 3, 2, 1, 0,
+];
+
+/// Order of the key words in the rounds of decryption.
+const DEC_ORDER: [usize; 32] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 7, 6, 5, 4, 3, 2, 1, 0, 7, 6, 5, 4, 3, 2, 1, 0, 7, 6, 5, 4, 3, 2, 1, 0,
+];
+
+/// Order of the key words in the rounds of the MAC transformation.
+const MAC_ORDER: [usize; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 0, 1, 2, 3, 4, 5, 6, 7];
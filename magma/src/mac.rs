@@ -0,0 +1,168 @@
+//! GOST 28147-89 message authentication code (imitovstavka).
+//!
+//! The message is split into 64-bit blocks, the last one padded with zeros,
+//! and the MAC state, initialized with the IV, is updated for every block by
+//! XORing the block into it and applying [`Gost89::mac_block`]. A message of
+//! a single block is processed as is, and an empty message as a single zero
+//! block. With [`KeyMeshing::CryptoPro`] the key is transformed after every
+//! 1 KiB of the message, as in CryptoPro implementations. Unlike in the
+//! encryption modes, the MAC state is not encrypted with the new key.
+//!
+//! The MAC uses the byte order of GOST 28147-89 implementations, see the
+//! [`modes`][crate::modes] module. The full MAC state is returned by
+//! [`Gost89Mac::finalize`]; protocols usually truncate it to its first 4
+//! bytes.
+//!
+//! Like the modes, the MAC is generic over the [`Gost89Cipher`], so it can
+//! be used with an S-box defined at runtime through [`Gost89Mac::new_with_sbox`].
+//!
+//! # Example
+//! ```
+//! use magma::{Gost89CryptoProA, mac::Gost89Mac, modes::KeyMeshing};
+//!
+//! let key = [0x42; 32].into();
+//! let mut mac = Gost89Mac::<Gost89CryptoProA>::new(&key, KeyMeshing::CryptoPro);
+//! mac.update(b"message");
+//! let tag = mac.clone().finalize();
+//! mac.verify(&tag[..4]).unwrap();
+//! ```
+
+use crate::{
+    DynSbox, Gost89, Gost89Dyn, Sbox,
+    legacy::{self, Block},
+    modes::{Gost89Cipher, Iv, KeyMeshing, MeshingCipher},
+};
+use cipher::Key;
+use core::fmt;
+use subtle::ConstantTimeEq;
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::Zeroize;
+
+/// Minimum length of a truncated tag accepted by [`Gost89Mac::verify`].
+const MIN_TAG_LEN: usize = 4;
+
+/// GOST 28147-89 MAC generic over the cipher.
+#[derive(Clone)]
+pub struct Gost89Mac<C: Gost89Cipher> {
+    cipher: MeshingCipher<C>,
+    state: Block,
+    /// Incomplete block of the message and its length.
+    buf: Block,
+    pos: usize,
+}
+
+impl<S: Sbox> Gost89Mac<Gost89<S>> {
+    /// Create a new instance from a key and a key meshing algorithm with the
+    /// all-zero IV.
+    pub fn new(key: &Key<Gost89<S>>, meshing: KeyMeshing) -> Self {
+        Self::new_with_iv(key, &Iv::default(), meshing)
+    }
+
+    /// Create a new instance from a key, an IV and a key meshing algorithm.
+    pub fn new_with_iv(key: &Key<Gost89<S>>, iv: &Iv, meshing: KeyMeshing) -> Self {
+        Self::from_cipher(legacy::new_cipher(key), iv, meshing)
+    }
+}
+
+impl<'a> Gost89Mac<Gost89Dyn<'a>> {
+    /// Create a new instance from a key, an S-box, an IV and a key meshing
+    /// algorithm.
+    pub fn new_with_sbox(
+        key: &Key<Gost89Dyn<'a>>,
+        sbox: &'a DynSbox,
+        iv: &Iv,
+        meshing: KeyMeshing,
+    ) -> Self {
+        Self::from_cipher(legacy::new_cipher_dyn(key, sbox), iv, meshing)
+    }
+}
+
+impl<C: Gost89Cipher> Gost89Mac<C> {
+    fn from_cipher(cipher: C, iv: &Iv, meshing: KeyMeshing) -> Self {
+        Self {
+            cipher: MeshingCipher::new(cipher, meshing),
+            state: *iv,
+            buf: Default::default(),
+            pos: 0,
+        }
+    }
+
+    fn process_buf(&mut self) {
+        self.cipher.next_mac_block();
+        for (s, b) in self.state.iter_mut().zip(self.buf.iter()) {
+            *s ^= b;
+        }
+        legacy::mac_block(self.cipher.cipher(), &mut self.state);
+        self.pos = 0;
+    }
+
+    /// Update the MAC state with `data`.
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let len = data.len().min(8 - self.pos);
+            let (head, rest) = data.split_at(len);
+            self.buf[self.pos..][..len].copy_from_slice(head);
+            self.pos += len;
+            if self.pos == 8 {
+                self.process_buf();
+            }
+            data = rest;
+        }
+    }
+
+    /// Compute the MAC of the data processed so far.
+    pub fn finalize(mut self) -> Block {
+        if self.pos != 0 || self.cipher.blocks() == 0 {
+            self.buf[self.pos..].fill(0);
+            self.process_buf();
+        }
+        self.state
+    }
+
+    /// Verify a possibly truncated `tag` of the data processed so far in
+    /// constant time.
+    ///
+    /// Tags shorter than 4 bytes or longer than 8 bytes are rejected with
+    /// [`Error`].
+    pub fn verify(self, tag: &[u8]) -> Result<(), Error> {
+        if !(MIN_TAG_LEN..=8).contains(&tag.len()) {
+            return Err(Error);
+        }
+        let expected = self.finalize();
+        if expected[..tag.len()].ct_eq(tag).into() {
+            Ok(())
+        } else {
+            Err(Error)
+        }
+    }
+}
+
+impl<C: Gost89Cipher> fmt::Debug for Gost89Mac<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Gost89Mac { ... }")
+    }
+}
+
+impl<C: Gost89Cipher> Drop for Gost89Mac<C> {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        {
+            self.state.zeroize();
+            self.buf.zeroize();
+        }
+    }
+}
+
+/// MAC verification error.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Error;
+
+impl fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("MAC verification error")
+    }
+}
+
+impl core::error::Error for Error {}
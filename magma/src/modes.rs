@@ -0,0 +1,416 @@
+//! GOST 28147-89 encryption modes with key meshing.
+//!
+//! - [`Gost89Cnt`]: the counter mode ("gamming") of GOST 28147-89, in which
+//!   the two halves of the encrypted IV are incremented by the constants
+//!   `C2 = 0x01010101` modulo 2<sup>32</sup> and `C1 = 0x01010104` modulo
+//!   2<sup>32</sup> - 1. It implements [`StreamCipher`].
+//! - [`Gost89CfbEnc`] and [`Gost89CfbDec`]: the cipher feedback mode
+//!   ("gamming with feedback").
+//!
+//! The modes are generic over the [`Gost89Cipher`]: [`Gost89`] with an S-box
+//! fixed at compile time, created with `new`, or [`Gost89Dyn`] with an S-box
+//! defined at runtime, created with `new_with_sbox`.
+//!
+//! With [`KeyMeshing::CryptoPro`] the key and the current IV are transformed
+//! after every 1 KiB of data with the CryptoPro key meshing algorithm
+//! defined in [RFC 4357] section 2.3.2, as required by the CryptoPro
+//! parameter sets.
+//!
+//! The modes use the byte order of GOST 28147-89 implementations: key, IV
+//! and data are processed as little-endian 32-bit words, which differs from
+//! the GOST R 34.12-2015 byte order used by [`Gost89`]. Data can be processed
+//! in pieces of any size.
+//!
+//! # Example
+//! ```
+//! use magma::{
+//!     Gost89CryptoProA,
+//!     cipher::StreamCipher,
+//!     modes::{Gost89CfbDec, Gost89CfbEnc, Gost89Cnt, KeyMeshing},
+//! };
+//!
+//! let key = [0x42; 32].into();
+//! let iv = [0x24; 8].into();
+//! let plaintext = [0x11; 3000];
+//!
+//! let mut buf = plaintext;
+//! Gost89Cnt::<Gost89CryptoProA>::new(&key, &iv, KeyMeshing::CryptoPro).apply_keystream(&mut buf);
+//! Gost89Cnt::<Gost89CryptoProA>::new(&key, &iv, KeyMeshing::CryptoPro).apply_keystream(&mut buf);
+//! assert_eq!(buf, plaintext);
+//!
+//! Gost89CfbEnc::<Gost89CryptoProA>::new(&key, &iv, KeyMeshing::CryptoPro).encrypt(&mut buf);
+//! Gost89CfbDec::<Gost89CryptoProA>::new(&key, &iv, KeyMeshing::CryptoPro).decrypt(&mut buf);
+//! assert_eq!(buf, plaintext);
+//! ```
+//!
+//! [RFC 4357]: https://www.rfc-editor.org/rfc/rfc4357
+
+use crate::{
+    DynSbox, Gost89, Gost89Dyn, Sbox,
+    legacy::{self, Block, MESHING_BLOCKS},
+};
+use cipher::{
+    BlockCipherDecrypt, BlockCipherEncrypt, InOutBuf, Key, KeySizeUser, StreamCipher,
+    StreamCipherError,
+    consts::{U8, U32},
+};
+use core::fmt;
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::Zeroize;
+
+/// Initialization vector of the GOST 28147-89 modes.
+pub type Iv = Block;
+
+/// Key meshing algorithm applied by the GOST 28147-89 modes and MAC.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum KeyMeshing {
+    /// No key meshing.
+    #[default]
+    None,
+    /// CryptoPro key meshing after every 1 KiB of data, RFC 4357
+    /// section 2.3.2.
+    CryptoPro,
+}
+
+/// GOST 28147-89 block ciphers supported by the modes and the MAC.
+///
+/// This trait is sealed and implemented for [`Gost89`] and [`Gost89Dyn`].
+pub trait Gost89Cipher:
+    BlockCipherEncrypt<BlockSize = U8>
+    + BlockCipherDecrypt
+    + KeySizeUser<KeySize = U32>
+    + Clone
+    + sealed::Sealed
+{
+}
+
+impl<S: Sbox> Gost89Cipher for Gost89<S> {}
+
+impl Gost89Cipher for Gost89Dyn<'_> {}
+
+pub(crate) mod sealed {
+    use crate::{Gost89, Gost89Dyn, Sbox, legacy::Block};
+
+    pub trait Sealed {
+        /// Replace the key words, keeping the S-box.
+        fn set_key_words(&mut self, key: [u32; 8]);
+
+        /// Apply the 16-round transformation of the MAC.
+        fn mac_block(&self, block: &mut Block);
+    }
+
+    impl<S: Sbox> Sealed for Gost89<S> {
+        fn set_key_words(&mut self, key: [u32; 8]) {
+            self.key = key;
+        }
+
+        fn mac_block(&self, block: &mut Block) {
+            Gost89::mac_block(self, block);
+        }
+    }
+
+    impl Sealed for Gost89Dyn<'_> {
+        fn set_key_words(&mut self, key: [u32; 8]) {
+            self.key = key;
+        }
+
+        fn mac_block(&self, block: &mut Block) {
+            Gost89Dyn::mac_block(self, block);
+        }
+    }
+}
+
+/// Cipher with the optional key meshing, which counts the processed blocks.
+#[derive(Clone)]
+pub(crate) struct MeshingCipher<C: Gost89Cipher> {
+    cipher: C,
+    meshing: KeyMeshing,
+    blocks: u64,
+}
+
+impl<C: Gost89Cipher> MeshingCipher<C> {
+    pub(crate) fn new(cipher: C, meshing: KeyMeshing) -> Self {
+        Self {
+            cipher,
+            meshing,
+            blocks: 0,
+        }
+    }
+
+    pub(crate) fn cipher(&self) -> &C {
+        &self.cipher
+    }
+
+    /// Number of blocks processed so far.
+    #[cfg(feature = "mac")]
+    pub(crate) fn blocks(&self) -> u64 {
+        self.blocks
+    }
+
+    /// Account for the next block of data and return whether the key has to
+    /// be meshed before it, which is at the start of every 1 KiB of data
+    /// after the first.
+    fn next(&mut self) -> bool {
+        let mesh = self.meshing == KeyMeshing::CryptoPro
+            && self.blocks != 0
+            && self.blocks % MESHING_BLOCKS == 0;
+        self.blocks += 1;
+        mesh
+    }
+
+    /// Account for the next block of data, meshing the key and encrypting the
+    /// state `iv` with the new key if required.
+    pub(crate) fn next_block(&mut self, iv: &mut Block) {
+        if self.next() {
+            legacy::mesh_key(&mut self.cipher);
+            legacy::encrypt(&self.cipher, iv);
+        }
+    }
+
+    /// Account for the next block of a MAC, meshing only the key if
+    /// required: CryptoPro implementations leave the MAC state unchanged.
+    #[cfg(feature = "mac")]
+    pub(crate) fn next_mac_block(&mut self) {
+        if self.next() {
+            legacy::mesh_key(&mut self.cipher);
+        }
+    }
+}
+
+/// GOST 28147-89 counter mode.
+#[derive(Clone)]
+pub struct Gost89Cnt<C: Gost89Cipher> {
+    cipher: MeshingCipher<C>,
+    /// Counter state `N3 || N4`.
+    ctr: Block,
+    /// Keystream block and the position of its first unused byte.
+    buf: Block,
+    pos: usize,
+}
+
+impl<S: Sbox> Gost89Cnt<Gost89<S>> {
+    /// Create a new instance from a key, an IV and a key meshing algorithm.
+    pub fn new(key: &Key<Gost89<S>>, iv: &Iv, meshing: KeyMeshing) -> Self {
+        Self::from_cipher(legacy::new_cipher(key), iv, meshing)
+    }
+}
+
+impl<'a> Gost89Cnt<Gost89Dyn<'a>> {
+    /// Create a new instance from a key, an S-box, an IV and a key meshing
+    /// algorithm.
+    pub fn new_with_sbox(
+        key: &Key<Gost89Dyn<'a>>,
+        sbox: &'a DynSbox,
+        iv: &Iv,
+        meshing: KeyMeshing,
+    ) -> Self {
+        Self::from_cipher(legacy::new_cipher_dyn(key, sbox), iv, meshing)
+    }
+}
+
+impl<C: Gost89Cipher> Gost89Cnt<C> {
+    const C1: u32 = 0x0101_0104;
+    const C2: u32 = 0x0101_0101;
+
+    fn from_cipher(cipher: C, iv: &Iv, meshing: KeyMeshing) -> Self {
+        let cipher = MeshingCipher::new(cipher, meshing);
+        let mut ctr = *iv;
+        legacy::encrypt(cipher.cipher(), &mut ctr);
+        Self {
+            cipher,
+            ctr,
+            buf: Default::default(),
+            pos: 8,
+        }
+    }
+
+    /// Generate the next block of the keystream.
+    fn next_block(&mut self) {
+        self.cipher.next_block(&mut self.ctr);
+
+        let (n3, n4) = self.ctr.split_at_mut(4);
+        let v3 = u32::from_le_bytes(n3.try_into().unwrap()).wrapping_add(Self::C2);
+        // Addition modulo 2^32 - 1
+        let (v4, carry) = u32::from_le_bytes(n4.try_into().unwrap()).overflowing_add(Self::C1);
+        let v4 = v4 + u32::from(carry);
+        n3.copy_from_slice(&v3.to_le_bytes());
+        n4.copy_from_slice(&v4.to_le_bytes());
+
+        self.buf = self.ctr;
+        legacy::encrypt(self.cipher.cipher(), &mut self.buf);
+        self.pos = 0;
+    }
+}
+
+impl<C: Gost89Cipher> StreamCipher for Gost89Cnt<C> {
+    fn check_remaining(&self, _data_len: usize) -> Result<(), StreamCipherError> {
+        Ok(())
+    }
+
+    fn unchecked_apply_keystream_inout(&mut self, mut buf: InOutBuf<'_, '_, u8>) {
+        while !buf.is_empty() {
+            if self.pos == 8 {
+                self.next_block();
+            }
+            let len = buf.len().min(8 - self.pos);
+            let (mut head, rest) = buf.split_at(len);
+            head.xor_in2out(&self.buf[self.pos..][..len]);
+            self.pos += len;
+            buf = rest;
+        }
+    }
+
+    fn unchecked_write_keystream(&mut self, buf: &mut [u8]) {
+        buf.fill(0);
+        self.unchecked_apply_keystream(buf);
+    }
+}
+
+impl<C: Gost89Cipher> fmt::Debug for Gost89Cnt<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Gost89Cnt { ... }")
+    }
+}
+
+impl<C: Gost89Cipher> Drop for Gost89Cnt<C> {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        {
+            self.ctr.zeroize();
+            self.buf.zeroize();
+        }
+    }
+}
+
+/// State shared by the CFB encryptor and decryptor.
+#[derive(Clone)]
+struct Cfb<C: Gost89Cipher> {
+    cipher: MeshingCipher<C>,
+    /// Ciphertext block being fed back, keystream block generated from the
+    /// previous one, and the position of the first unused keystream byte.
+    feedback: Block,
+    buf: Block,
+    pos: usize,
+}
+
+impl<C: Gost89Cipher> Cfb<C> {
+    fn new(cipher: C, iv: &Iv, meshing: KeyMeshing) -> Self {
+        Self {
+            cipher: MeshingCipher::new(cipher, meshing),
+            feedback: *iv,
+            buf: Default::default(),
+            pos: 8,
+        }
+    }
+
+    fn process(&mut self, mut data: &mut [u8], decrypt: bool) {
+        while !data.is_empty() {
+            if self.pos == 8 {
+                self.cipher.next_block(&mut self.feedback);
+                self.buf = self.feedback;
+                legacy::encrypt(self.cipher.cipher(), &mut self.buf);
+                self.pos = 0;
+            }
+            let len = data.len().min(8 - self.pos);
+            let (head, rest) = data.split_at_mut(len);
+            let gamma = &self.buf[self.pos..][..len];
+            let feedback = &mut self.feedback[self.pos..][..len];
+            for ((b, g), f) in head.iter_mut().zip(gamma).zip(feedback) {
+                if decrypt {
+                    *f = *b;
+                    *b ^= g;
+                } else {
+                    *b ^= g;
+                    *f = *b;
+                }
+            }
+            self.pos += len;
+            data = rest;
+        }
+    }
+}
+
+impl<C: Gost89Cipher> Drop for Cfb<C> {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        {
+            self.feedback.zeroize();
+            self.buf.zeroize();
+        }
+    }
+}
+
+/// GOST 28147-89 cipher feedback mode encryptor.
+#[derive(Clone)]
+pub struct Gost89CfbEnc<C: Gost89Cipher>(Cfb<C>);
+
+impl<S: Sbox> Gost89CfbEnc<Gost89<S>> {
+    /// Create a new instance from a key, an IV and a key meshing algorithm.
+    pub fn new(key: &Key<Gost89<S>>, iv: &Iv, meshing: KeyMeshing) -> Self {
+        Self(Cfb::new(legacy::new_cipher(key), iv, meshing))
+    }
+}
+
+impl<'a> Gost89CfbEnc<Gost89Dyn<'a>> {
+    /// Create a new instance from a key, an S-box, an IV and a key meshing
+    /// algorithm.
+    pub fn new_with_sbox(
+        key: &Key<Gost89Dyn<'a>>,
+        sbox: &'a DynSbox,
+        iv: &Iv,
+        meshing: KeyMeshing,
+    ) -> Self {
+        Self(Cfb::new(legacy::new_cipher_dyn(key, sbox), iv, meshing))
+    }
+}
+
+impl<C: Gost89Cipher> Gost89CfbEnc<C> {
+    /// Encrypt `data` in place.
+    pub fn encrypt(&mut self, data: &mut [u8]) {
+        self.0.process(data, false);
+    }
+}
+
+impl<C: Gost89Cipher> fmt::Debug for Gost89CfbEnc<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Gost89CfbEnc { ... }")
+    }
+}
+
+/// GOST 28147-89 cipher feedback mode decryptor.
+#[derive(Clone)]
+pub struct Gost89CfbDec<C: Gost89Cipher>(Cfb<C>);
+
+impl<S: Sbox> Gost89CfbDec<Gost89<S>> {
+    /// Create a new instance from a key, an IV and a key meshing algorithm.
+    pub fn new(key: &Key<Gost89<S>>, iv: &Iv, meshing: KeyMeshing) -> Self {
+        Self(Cfb::new(legacy::new_cipher(key), iv, meshing))
+    }
+}
+
+impl<'a> Gost89CfbDec<Gost89Dyn<'a>> {
+    /// Create a new instance from a key, an S-box, an IV and a key meshing
+    /// algorithm.
+    pub fn new_with_sbox(
+        key: &Key<Gost89Dyn<'a>>,
+        sbox: &'a DynSbox,
+        iv: &Iv,
+        meshing: KeyMeshing,
+    ) -> Self {
+        Self(Cfb::new(legacy::new_cipher_dyn(key, sbox), iv, meshing))
+    }
+}
+
+impl<C: Gost89Cipher> Gost89CfbDec<C> {
+    /// Decrypt `data` in place.
+    pub fn decrypt(&mut self, data: &mut [u8]) {
+        self.0.process(data, true);
+    }
+}
+
+impl<C: Gost89Cipher> fmt::Debug for Gost89CfbDec<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Gost89CfbDec { ... }")
+    }
+}
@@ -1,8 +1,22 @@
-//! Expanded S-boxes generated using `gen_exp_sbox` function
+//! S-boxes and the lookup tables or algebraic normal forms generated from them
 
+#[cfg(not(magma_backend = "ct"))]
 type ExpSbox = [[u8; 256]; 4];
-type SmallSbox = [[u8; 16]; 8];
+pub(crate) type SmallSbox = [[u8; 16]; 8];
 
+/// Lookup tables of the function `g` without the key addition: entry `b` of
+/// table `i` is the S-box applied to byte `i` of the input, shifted into place
+/// and rotated left by 11 bits.
+#[cfg(not(magma_backend = "ct"))]
+pub(crate) type Tables = [[u32; 256]; 4];
+
+/// Algebraic normal form of the S-box: bit `m` of `anf[p][c]` is the
+/// coefficient of the monomial `prod_{i in m} x_i` of the input nibble `p` in
+/// output bit `c`.
+#[cfg(magma_backend = "ct")]
+pub(crate) type Anf = [[u16; 4]; 8];
+
+#[cfg(not(magma_backend = "ct"))]
 const fn gen_exp_sbox(sbox: &SmallSbox) -> ExpSbox {
     let mut out = [[0u8; 256]; 4];
     let mut i = 0;
@@ -23,6 +37,74 @@ const fn gen_exp_sbox(sbox: &SmallSbox) -> ExpSbox {
     out
 }
 
+#[cfg(not(magma_backend = "ct"))]
+const fn gen_tables(exp_sbox: &ExpSbox) -> Tables {
+    let mut out = [[0u32; 256]; 4];
+    let mut i = 0;
+    while i < 4 {
+        let mut b = 0;
+        while b < 256 {
+            out[i][b] = ((exp_sbox[i][b] as u32) << (8 * i)).rotate_left(11);
+            b += 1;
+        }
+        i += 1;
+    }
+    out
+}
+
+#[cfg(magma_backend = "ct")]
+const fn gen_anf(sbox: &SmallSbox) -> Anf {
+    let mut out = [[0u16; 4]; 8];
+    let mut p = 0;
+    while p < 8 {
+        // Moebius transform of the truth tables of all output bits
+        let mut t = sbox[p];
+        let mut i = 0;
+        while i < 4 {
+            let mut x = 0;
+            while x < 16 {
+                if x & (1 << i) != 0 {
+                    t[x] ^= t[x ^ (1 << i)];
+                }
+                x += 1;
+            }
+            i += 1;
+        }
+        let mut m = 0;
+        while m < 16 {
+            let mut c = 0;
+            while c < 4 {
+                if t[m] & (1 << c) != 0 {
+                    out[p][c] |= 1 << m;
+                }
+                c += 1;
+            }
+            m += 1;
+        }
+        p += 1;
+    }
+    out
+}
+
+/// S-box expanded for the backend in use.
+#[cfg(not(magma_backend = "ct"))]
+pub(crate) type ExpandedSbox = Tables;
+/// S-box expanded for the backend in use.
+#[cfg(magma_backend = "ct")]
+pub(crate) type ExpandedSbox = Anf;
+
+/// Expand the S-box for the backend in use.
+pub(crate) const fn expand(sbox: &SmallSbox) -> ExpandedSbox {
+    #[cfg(not(magma_backend = "ct"))]
+    {
+        gen_tables(&gen_exp_sbox(sbox))
+    }
+    #[cfg(magma_backend = "ct")]
+    {
+        gen_anf(sbox)
+    }
+}
+
 /// Trait for GOST 28147-89 cipher S-boxes
 pub trait Sbox {
     /// S-Box name
@@ -32,30 +114,14 @@ pub trait Sbox {
 }
 
 /// Extension of the `Sbox` trait which provides expanded S-Box
-/// and helper methods
 pub(crate) trait SboxExt: Sbox {
     /// Expanded S-box
-    const EXP_SBOX: ExpSbox = gen_exp_sbox(&Self::SBOX);
-
-    /// Apply S-box and return result
-    fn apply_sbox(a: u32) -> u32 {
-        let mut v = 0;
-        for i in 0..4 {
-            let shift = 8 * i;
-            let k = ((a & (0xffu32 << shift)) >> shift) as usize;
-            v += (Self::EXP_SBOX[i][k] as u32) << shift;
-        }
-        v
-    }
-
-    /// Function `g` based on the S-box
-    fn g(a: u32, k: u32) -> u32 {
-        Self::apply_sbox(a.wrapping_add(k)).rotate_left(11)
-    }
+    const EXPANDED: &'static ExpandedSbox = &expand(&Self::SBOX);
 }
 
 impl<T: Sbox> SboxExt for T {}
 
+/// S-box defined in GOST R 34.12-2015 (id-tc26-gost-28147-param-Z)
 pub enum Tc26 {}
 
 impl Sbox for Tc26 {
@@ -72,6 +138,7 @@ impl Sbox for Tc26 {
     ];
 }
 
+/// Test S-box from GOST R 34.11-94 (id-GostR3411-94-TestParamSet)
 pub enum TestSbox {}
 
 impl Sbox for TestSbox {
@@ -88,6 +155,7 @@ impl Sbox for TestSbox {
     ];
 }
 
+/// CryptoPro S-box version A (id-Gost28147-89-CryptoPro-A-ParamSet)
 pub enum CryptoProA {}
 
 impl Sbox for CryptoProA {
@@ -104,6 +172,7 @@ impl Sbox for CryptoProA {
     ];
 }
 
+/// CryptoPro S-box version B (id-Gost28147-89-CryptoPro-B-ParamSet)
 pub enum CryptoProB {}
 
 impl Sbox for CryptoProB {
@@ -120,6 +189,7 @@ impl Sbox for CryptoProB {
     ];
 }
 
+/// CryptoPro S-box version C (id-Gost28147-89-CryptoPro-C-ParamSet)
 pub enum CryptoProC {}
 
 impl Sbox for CryptoProC {
@@ -136,6 +206,7 @@ impl Sbox for CryptoProC {
     ];
 }
 
+/// CryptoPro S-box version D (id-Gost28147-89-CryptoPro-D-ParamSet)
 pub enum CryptoProD {}
 
 impl Sbox for CryptoProD {
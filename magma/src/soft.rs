@@ -0,0 +1,120 @@
+//! Software backend with lookup tables of the function `g`.
+//!
+//! Every round is computed with four lookups into tables which combine the
+//! S-box and the rotation. Parallel blocks are processed with interleaved
+//! rounds to hide the latency of the lookups.
+
+use crate::{DEC_ORDER, ENC_ORDER, MAC_ORDER, sboxes::Tables};
+use cipher::{
+    Block, BlockCipherDecBackend, BlockCipherEncBackend, BlockSizeUser, InOut, ParBlocks,
+    ParBlocksSizeUser, consts,
+};
+
+/// Number of blocks processed in parallel.
+const PAR_BLOCKS: usize = 4;
+
+#[inline(always)]
+fn g(t: &Tables, x: u32) -> u32 {
+    let [b0, b1, b2, b3] = x.to_le_bytes();
+    t[0][usize::from(b0)] ^ t[1][usize::from(b1)] ^ t[2][usize::from(b2)] ^ t[3][usize::from(b3)]
+}
+
+/// Apply the rounds with the key words in `order` to the block halves `a`
+/// and `b` of `N` blocks.
+#[inline(always)]
+fn rounds<const N: usize>(
+    sbox: &Tables,
+    key: &[u32; 8],
+    order: &[usize],
+    a: &mut [u32; N],
+    b: &mut [u32; N],
+) {
+    for &i in order {
+        let k = key[i];
+        for j in 0..N {
+            let v = a[j] ^ g(sbox, b[j].wrapping_add(k));
+            a[j] = b[j];
+            b[j] = v;
+        }
+    }
+}
+
+#[inline(always)]
+fn crypt<const N: usize>(
+    sbox: &Tables,
+    key: &[u32; 8],
+    order: &[usize; 32],
+    blocks: &[[u8; 8]; N],
+) -> [[u8; 8]; N] {
+    let mut a = [0; N];
+    let mut b = [0; N];
+    for j in 0..N {
+        a[j] = u32::from_be_bytes(blocks[j][..4].try_into().unwrap());
+        b[j] = u32::from_be_bytes(blocks[j][4..].try_into().unwrap());
+    }
+    rounds::<N>(sbox, key, order, &mut a, &mut b);
+    let mut res = [[0; 8]; N];
+    for j in 0..N {
+        res[j][..4].copy_from_slice(&b[j].to_be_bytes());
+        res[j][4..].copy_from_slice(&a[j].to_be_bytes());
+    }
+    res
+}
+
+pub(crate) fn mac_block(sbox: &Tables, key: &[u32; 8], block: &mut [u8; 8]) {
+    let mut a = [u32::from_be_bytes(block[..4].try_into().unwrap())];
+    let mut b = [u32::from_be_bytes(block[4..].try_into().unwrap())];
+    rounds::<1>(sbox, key, &MAC_ORDER, &mut a, &mut b);
+    block[..4].copy_from_slice(&a[0].to_be_bytes());
+    block[4..].copy_from_slice(&b[0].to_be_bytes());
+}
+
+pub(crate) struct EncBackend<'a>(pub(crate) &'a [u32; 8], pub(crate) &'a Tables);
+
+impl BlockSizeUser for EncBackend<'_> {
+    type BlockSize = consts::U8;
+}
+
+impl ParBlocksSizeUser for EncBackend<'_> {
+    type ParBlocksSize = consts::U4;
+}
+
+impl BlockCipherEncBackend for EncBackend<'_> {
+    #[inline]
+    fn encrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
+        let [res] = crypt::<1>(self.1, self.0, &ENC_ORDER, &[block.get_in().0]);
+        block.get_out().0 = res;
+    }
+
+    #[inline]
+    fn encrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let input: [[u8; 8]; PAR_BLOCKS] = blocks.get_in().0.map(|b| b.0);
+        let res = crypt::<PAR_BLOCKS>(self.1, self.0, &ENC_ORDER, &input);
+        blocks.get_out().0 = res.map(Into::into);
+    }
+}
+
+pub(crate) struct DecBackend<'a>(pub(crate) &'a [u32; 8], pub(crate) &'a Tables);
+
+impl BlockSizeUser for DecBackend<'_> {
+    type BlockSize = consts::U8;
+}
+
+impl ParBlocksSizeUser for DecBackend<'_> {
+    type ParBlocksSize = consts::U4;
+}
+
+impl BlockCipherDecBackend for DecBackend<'_> {
+    #[inline]
+    fn decrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
+        let [res] = crypt::<1>(self.1, self.0, &DEC_ORDER, &[block.get_in().0]);
+        block.get_out().0 = res;
+    }
+
+    #[inline]
+    fn decrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let input: [[u8; 8]; PAR_BLOCKS] = blocks.get_in().0.map(|b| b.0);
+        let res = crypt::<PAR_BLOCKS>(self.1, self.0, &DEC_ORDER, &input);
+        blocks.get_out().0 = res.map(Into::into);
+    }
+}
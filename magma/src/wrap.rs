@@ -0,0 +1,301 @@
+//! Key wrap algorithms of GOST 28147-89 and GOST R 34.12-2015.
+//!
+//! - [`wrap_gost`] and [`unwrap_gost`]: the GOST 28147-89 key wrap defined
+//!   in [RFC 4357] section 6.1. The content encryption key (CEK) is
+//!   encrypted with the key encryption key (KEK) in ECB mode and
+//!   authenticated with the first 4 bytes of the GOST 28147-89 MAC computed
+//!   with the user keying material (UKM) as the IV.
+//! - [`wrap_cryptopro`] and [`unwrap_cryptopro`]: the CryptoPro key wrap
+//!   defined in RFC 4357 section 6.3, which applies the GOST 28147-89 key
+//!   wrap with the KEK diversified by the UKM with [`diversify_kek`].
+//! - [`kexp15`] and [`kimp15`]: the KExp15 and KImp15 algorithms defined in
+//!   R 1323565.1.017-2018, which encrypt the key and its OMAC (CMAC) in CTR
+//!   mode of GOST R 34.13-2015. They work with any [`AcpkmCipher`], i.e. a
+//!   block cipher with 64-bit or 128-bit blocks and 256-bit keys, e.g.
+//!   [`Magma`][crate::Magma] or `kuznyechik::Kuznyechik`. The OMAC is the one
+//!   of [`kuznyechik::acpkm::Omac`].
+//!
+//! The GOST 28147-89 algorithms use its byte order, see the
+//! [`modes`][crate::modes] module, while KExp15 and KImp15 use the byte order
+//! of GOST R 34.12-2015. Wrapped keys are authenticated before being
+//! returned, with the MAC compared in constant time.
+//!
+//! # Example
+//! ```
+//! use magma::{
+//!     CryptoProA, Magma,
+//!     wrap::{kexp15, kimp15, unwrap_cryptopro, wrap_cryptopro},
+//! };
+//!
+//! let kek = [0x42; 32].into();
+//! let ukm = [0x24; 8].into();
+//! let cek = [0x11; 32].into();
+//!
+//! let wrapped = wrap_cryptopro::<CryptoProA>(&kek, &ukm, &cek);
+//! assert_eq!(unwrap_cryptopro::<CryptoProA>(&kek, &wrapped), Ok(cek));
+//!
+//! let (k_enc, k_mac) = ([0x01; 32].into(), [0x02; 32].into());
+//! let iv = [0x03; 4].into();
+//! let mut exported = [0u8; 40];
+//! kexp15::<Magma>(&k_enc, &k_mac, &iv, &cek, &mut exported).unwrap();
+//! let mut imported = [0u8; 32];
+//! kimp15::<Magma>(&k_enc, &k_mac, &iv, &exported, &mut imported).unwrap();
+//! assert_eq!(imported, cek[..]);
+//! ```
+//!
+//! [RFC 4357]: https://www.rfc-editor.org/rfc/rfc4357
+
+use crate::{
+    Gost89, Sbox,
+    legacy::{self, Block as LegacyBlock},
+    mac::Gost89Mac,
+    modes::{Gost89CfbEnc, KeyMeshing},
+};
+use cipher::{
+    Array, Block, Key,
+    consts::{U8, U44},
+    typenum::Unsigned,
+};
+use core::fmt;
+use kuznyechik::acpkm::{Icn, Omac};
+use subtle::ConstantTimeEq;
+
+pub use kuznyechik::acpkm::AcpkmCipher;
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::Zeroize;
+
+/// User keying material of the GOST 28147-89 key wraps.
+pub type Ukm = Array<u8, U8>;
+
+/// Key wrapped with [`wrap_gost`] or [`wrap_cryptopro`]: `UKM || CEK_ENC ||
+/// CEK_MAC`.
+pub type WrappedKey = Array<u8, U44>;
+
+/// IV of [`kexp15`] and [`kimp15`], which has half the block size.
+pub type Kexp15Iv<C> = Icn<C>;
+
+/// Length of the truncated MAC of the GOST 28147-89 key wrap.
+const CEK_MAC_LEN: usize = 4;
+
+/// Wrap `cek` with the GOST 28147-89 key wrap, RFC 4357 section 6.1.
+pub fn wrap_gost<S: Sbox>(kek: &Key<Gost89<S>>, ukm: &Ukm, cek: &Key<Gost89<S>>) -> WrappedKey {
+    let cipher = legacy::new_cipher::<S>(kek);
+    let mut res = WrappedKey::default();
+    let (res_ukm, rest) = res.split_at_mut(8);
+    let (cek_enc, cek_mac) = rest.split_at_mut(32);
+
+    res_ukm.copy_from_slice(ukm);
+    cek_enc.copy_from_slice(cek);
+    for chunk in cek_enc.chunks_exact_mut(8) {
+        let block: &mut LegacyBlock = chunk.try_into().unwrap();
+        legacy::encrypt(&cipher, block);
+    }
+    cek_mac.copy_from_slice(&cek_mac_of::<S>(kek, ukm, cek)[..CEK_MAC_LEN]);
+    res
+}
+
+/// Unwrap a key wrapped with [`wrap_gost`], RFC 4357 section 6.2.
+pub fn unwrap_gost<S: Sbox>(
+    kek: &Key<Gost89<S>>,
+    wrapped: &WrappedKey,
+) -> Result<Key<Gost89<S>>, Error> {
+    let (ukm, rest) = wrapped.split_at(8);
+    let (cek_enc, cek_mac) = rest.split_at(32);
+    let ukm: &Ukm = ukm.try_into().unwrap();
+
+    let cipher = legacy::new_cipher::<S>(kek);
+    let mut cek = Key::<Gost89<S>>::try_from(cek_enc).unwrap();
+    for chunk in cek.chunks_exact_mut(8) {
+        let block: &mut LegacyBlock = chunk.try_into().unwrap();
+        legacy::decrypt(&cipher, block);
+    }
+
+    let expected = cek_mac_of::<S>(kek, ukm, &cek);
+    if expected[..CEK_MAC_LEN].ct_eq(cek_mac).into() {
+        Ok(cek)
+    } else {
+        #[cfg(feature = "zeroize")]
+        cek.zeroize();
+        Err(Error::Verification)
+    }
+}
+
+/// Wrap `cek` with the CryptoPro key wrap, RFC 4357 section 6.3.
+pub fn wrap_cryptopro<S: Sbox>(
+    kek: &Key<Gost89<S>>,
+    ukm: &Ukm,
+    cek: &Key<Gost89<S>>,
+) -> WrappedKey {
+    #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+    let mut kek = diversify_kek::<S>(kek, ukm);
+    let res = wrap_gost::<S>(&kek, ukm, cek);
+    #[cfg(feature = "zeroize")]
+    kek.zeroize();
+    res
+}
+
+/// Unwrap a key wrapped with [`wrap_cryptopro`], RFC 4357 section 6.4.
+pub fn unwrap_cryptopro<S: Sbox>(
+    kek: &Key<Gost89<S>>,
+    wrapped: &WrappedKey,
+) -> Result<Key<Gost89<S>>, Error> {
+    let ukm: &Ukm = wrapped[..8].try_into().unwrap();
+    #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+    let mut kek = diversify_kek::<S>(kek, ukm);
+    let res = unwrap_gost::<S>(&kek, wrapped);
+    #[cfg(feature = "zeroize")]
+    kek.zeroize();
+    res
+}
+
+/// Diversify `kek` with `ukm` using the CryptoPro KEK diversification
+/// algorithm, RFC 4357 section 6.5.
+///
+/// Every byte of the UKM selects the 32-bit words of the key summed into the
+/// two halves of the IV, with which the key is encrypted with itself in CFB
+/// mode.
+pub fn diversify_kek<S: Sbox>(kek: &Key<Gost89<S>>, ukm: &Ukm) -> Key<Gost89<S>> {
+    let mut key = *kek;
+    for &a in ukm.iter() {
+        let (mut s1, mut s2) = (0u32, 0u32);
+        for (j, chunk) in key.chunks_exact(4).enumerate() {
+            let k = u32::from_le_bytes(chunk.try_into().unwrap());
+            // Select the sum without branching on the secret-dependent UKM
+            let mask = 0u32.wrapping_sub(u32::from((a >> j) & 1));
+            s1 = s1.wrapping_add(k & mask);
+            s2 = s2.wrapping_add(k & !mask);
+        }
+        let mut iv = LegacyBlock::default();
+        iv[..4].copy_from_slice(&s1.to_le_bytes());
+        iv[4..].copy_from_slice(&s2.to_le_bytes());
+
+        let mut cfb = Gost89CfbEnc::<Gost89<S>>::new(&key, &iv, KeyMeshing::None);
+        cfb.encrypt(&mut key);
+    }
+    key
+}
+
+fn cek_mac_of<S: Sbox>(kek: &Key<Gost89<S>>, ukm: &Ukm, cek: &Key<Gost89<S>>) -> LegacyBlock {
+    let mut mac = Gost89Mac::<Gost89<S>>::new_with_iv(kek, ukm, KeyMeshing::None);
+    mac.update(cek);
+    mac.finalize()
+}
+
+/// Export `key` with the KExp15 algorithm, R 1323565.1.017-2018.
+///
+/// Writes `CTR(k_enc, iv, key || OMAC(k_mac, iv || key))` into `out`, whose
+/// length must be the length of `key` plus the block size of `C`.
+pub fn kexp15<C: AcpkmCipher>(
+    k_enc: &Key<C>,
+    k_mac: &Key<C>,
+    iv: &Kexp15Iv<C>,
+    key: &[u8],
+    out: &mut [u8],
+) -> Result<(), Error> {
+    if out.len() != key.len() + C::BlockSize::USIZE {
+        return Err(Error::InvalidLength);
+    }
+    let (out_key, out_mac) = out.split_at_mut(key.len());
+    out_key.copy_from_slice(key);
+    out_mac.copy_from_slice(&omac::<C>(k_mac, iv, key));
+    ctr(&C::new(k_enc), iv, [out_key, out_mac]);
+    Ok(())
+}
+
+/// Import a key exported with [`kexp15`], R 1323565.1.017-2018.
+///
+/// Writes the key into `out`, whose length must be the length of `exported`
+/// minus the block size of `C`. On MAC mismatch `out` is filled with zeros.
+pub fn kimp15<C: AcpkmCipher>(
+    k_enc: &Key<C>,
+    k_mac: &Key<C>,
+    iv: &Kexp15Iv<C>,
+    exported: &[u8],
+    out: &mut [u8],
+) -> Result<(), Error> {
+    if exported.len() != out.len() + C::BlockSize::USIZE {
+        return Err(Error::InvalidLength);
+    }
+    let (exp_key, exp_mac) = exported.split_at(out.len());
+    let mut tag = Block::<C>::try_from(exp_mac).unwrap();
+
+    out.copy_from_slice(exp_key);
+    ctr(&C::new(k_enc), iv, [out, &mut tag]);
+
+    let expected = omac::<C>(k_mac, iv, out);
+    let res = if expected.ct_eq(&tag).into() {
+        Ok(())
+    } else {
+        out.fill(0);
+        Err(Error::Verification)
+    };
+    #[cfg(feature = "zeroize")]
+    tag.zeroize();
+    res
+}
+
+/// Apply the CTR keystream of GOST R 34.13-2015 to the concatenation of
+/// `bufs`.
+fn ctr<C: AcpkmCipher>(cipher: &C, iv: &Kexp15Iv<C>, bufs: [&mut [u8]; 2]) {
+    let n = C::BlockSize::USIZE;
+    let mut ctr = Block::<C>::default();
+    ctr[..n / 2].copy_from_slice(iv);
+    let mut ks = Block::<C>::default();
+    let mut pos = n;
+    for buf in bufs {
+        for b in buf.iter_mut() {
+            if pos == n {
+                ks = ctr.clone();
+                cipher.encrypt_block(&mut ks);
+                inc(&mut ctr);
+                pos = 0;
+            }
+            *b ^= ks[pos];
+            pos += 1;
+        }
+    }
+    #[cfg(feature = "zeroize")]
+    ks.zeroize();
+}
+
+/// Increment the counter block as a big-endian integer.
+#[inline]
+fn inc(block: &mut [u8]) {
+    for b in block.iter_mut().rev() {
+        let (v, carry) = b.overflowing_add(1);
+        *b = v;
+        if !carry {
+            break;
+        }
+    }
+}
+
+/// Compute OMAC of GOST R 34.13-2015 over `iv || data`.
+fn omac<C: AcpkmCipher>(k_mac: &Key<C>, iv: &[u8], data: &[u8]) -> Block<C> {
+    let mut mac = Omac::<C>::new(k_mac);
+    mac.update(iv);
+    mac.update(data);
+    mac.finalize()
+}
+
+/// Key wrap errors.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The length of the output buffer does not match the input.
+    InvalidLength,
+    /// The MAC of the wrapped key does not match.
+    Verification,
+}
+
+impl fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(match self {
+            Error::InvalidLength => "InvalidLength",
+            Error::Verification => "Verification",
+        })
+    }
+}
+
+impl core::error::Error for Error {}
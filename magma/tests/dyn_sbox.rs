@@ -0,0 +1,157 @@
+//! Tests for the cipher with S-boxes defined at runtime
+use cipher::{Array, BlockCipherDecrypt, BlockCipherEncrypt, KeyInit, StreamCipher};
+use hex_literal::hex;
+#[cfg(feature = "mac")]
+use magma::mac::Gost89Mac;
+use magma::{
+    CryptoProA, DynSbox, Gost89CryptoProA, Gost89Dyn, InvalidSbox, Sbox, Tc26,
+    modes::{Gost89CfbDec, Gost89CfbEnc, Gost89Cnt, KeyMeshing},
+};
+
+const KEY: [u8; 32] = hex!("FFEEDDCCBBAA99887766554433221100F0F1F2F3F4F5F6F7F8F9FAFBFCFDFEFF");
+
+/// Example from GOST R 34.12-2015 with the S-box loaded at runtime
+#[test]
+fn gost_r_34_12() {
+    let sbox = DynSbox::new(&Tc26::SBOX).unwrap();
+    assert_eq!(sbox, DynSbox::from_sbox::<Tc26>());
+
+    let cipher = Gost89Dyn::new(&KEY.into(), &sbox);
+    let mut block = hex!("FEDCBA9876543210").into();
+    cipher.encrypt_block(&mut block);
+    assert_eq!(block, hex!("4EE901E5C2D8CA3D"));
+    cipher.decrypt_block(&mut block);
+    assert_eq!(block, hex!("FEDCBA9876543210"));
+}
+
+#[test]
+fn matches_static_sbox() {
+    let sbox = DynSbox::new(&CryptoProA::SBOX).unwrap();
+    let cipher = Gost89Dyn::new(&KEY.into(), &sbox);
+    let reference = Gost89CryptoProA::new(&KEY.into());
+    let blocks: Vec<Array<u8, _>> = (0..50u64)
+        .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15).to_be_bytes().into())
+        .collect();
+
+    let mut buf = blocks.clone();
+    let mut expected = blocks.clone();
+    cipher.encrypt_blocks(&mut buf);
+    reference.encrypt_blocks(&mut expected);
+    assert_eq!(buf, expected);
+    cipher.decrypt_blocks(&mut buf);
+    assert_eq!(buf, blocks);
+
+    for block in &blocks {
+        let (mut a, mut b) = (*block, *block);
+        cipher.mac_block(&mut a);
+        reference.mac_block(&mut b);
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn invalid_sbox() {
+    let mut sbox = Tc26::SBOX;
+    sbox[3][5] = sbox[3][6];
+    assert_eq!(DynSbox::new(&sbox), Err(InvalidSbox));
+
+    let mut sbox = Tc26::SBOX;
+    sbox[7][0] = 16;
+    assert_eq!(DynSbox::new(&sbox), Err(InvalidSbox));
+}
+
+/// S-box which is not one of the built-in ones
+fn custom_sbox() -> DynSbox {
+    let mut rows = Tc26::SBOX;
+    rows.reverse();
+    DynSbox::new(&rows).unwrap()
+}
+
+/// Create the cipher used by the modes and the MAC, which load the key in the
+/// byte order of GOST 28147-89. Blocks have to be reversed as well.
+fn legacy_cipher<'a>(key: &[u8; 32], sbox: &'a DynSbox) -> Gost89Dyn<'a> {
+    let mut key = *key;
+    key.chunks_exact_mut(4).for_each(<[u8]>::reverse);
+    Gost89Dyn::new(&key.into(), sbox)
+}
+
+#[test]
+fn modes_match_static_sbox() {
+    let sbox = DynSbox::from_sbox::<CryptoProA>();
+    let iv = hex!("0102030405060708").into();
+    let pt: Vec<u8> = (0..3000u32).map(|i| (i * 7) as u8).collect();
+
+    for meshing in [KeyMeshing::None, KeyMeshing::CryptoPro] {
+        let mut buf = pt.clone();
+        let mut expected = pt.clone();
+        Gost89Cnt::new_with_sbox(&KEY.into(), &sbox, &iv, meshing).apply_keystream(&mut buf);
+        Gost89Cnt::<Gost89CryptoProA>::new(&KEY.into(), &iv, meshing)
+            .apply_keystream(&mut expected);
+        assert_eq!(buf, expected);
+
+        let mut buf = pt.clone();
+        let mut expected = pt.clone();
+        Gost89CfbEnc::new_with_sbox(&KEY.into(), &sbox, &iv, meshing).encrypt(&mut buf);
+        Gost89CfbEnc::<Gost89CryptoProA>::new(&KEY.into(), &iv, meshing).encrypt(&mut expected);
+        assert_eq!(buf, expected);
+        Gost89CfbDec::new_with_sbox(&KEY.into(), &sbox, &iv, meshing).decrypt(&mut buf);
+        assert_eq!(buf, pt);
+
+        #[cfg(feature = "mac")]
+        {
+            let mut mac = Gost89Mac::new_with_sbox(&KEY.into(), &sbox, &iv, meshing);
+            let mut expected =
+                Gost89Mac::<Gost89CryptoProA>::new_with_iv(&KEY.into(), &iv, meshing);
+            mac.update(&pt);
+            expected.update(&pt);
+            assert_eq!(mac.finalize(), expected.finalize());
+        }
+    }
+}
+
+#[test]
+fn modes_custom_sbox() {
+    let sbox = custom_sbox();
+    let cipher = legacy_cipher(&KEY, &sbox);
+    let iv = hex!("0102030405060708");
+    let pt: Vec<u8> = (0..3000u32).map(|i| (i * 7) as u8).collect();
+
+    // The first block of CFB is the plaintext XORed with the encrypted IV
+    let mut gamma = iv;
+    gamma.reverse();
+    cipher.encrypt_block((&mut gamma).into());
+    gamma.reverse();
+
+    let mut buf = pt.clone();
+    Gost89CfbEnc::new_with_sbox(&KEY.into(), &sbox, &iv.into(), KeyMeshing::CryptoPro)
+        .encrypt(&mut buf);
+    for i in 0..8 {
+        assert_eq!(buf[i], pt[i] ^ gamma[i]);
+    }
+    Gost89CfbDec::new_with_sbox(&KEY.into(), &sbox, &iv.into(), KeyMeshing::CryptoPro)
+        .decrypt(&mut buf);
+    assert_eq!(buf, pt);
+
+    let mut buf = pt.clone();
+    for _ in 0..2 {
+        Gost89Cnt::new_with_sbox(&KEY.into(), &sbox, &iv.into(), KeyMeshing::CryptoPro)
+            .apply_keystream(&mut buf);
+    }
+    assert_eq!(buf, pt);
+
+    // The MAC of two blocks applies the MAC transformation twice
+    #[cfg(feature = "mac")]
+    {
+        let mut expected = [0u8; 8];
+        for block in pt[..16].chunks_exact(8) {
+            expected.iter_mut().zip(block).for_each(|(s, b)| *s ^= b);
+            expected.reverse();
+            cipher.mac_block((&mut expected).into());
+            expected.reverse();
+        }
+        let mut mac =
+            Gost89Mac::new_with_sbox(&KEY.into(), &sbox, &Default::default(), KeyMeshing::None);
+        mac.update(&pt[..16]);
+        assert_eq!(mac.finalize(), expected);
+    }
+}
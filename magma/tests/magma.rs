@@ -0,0 +1,65 @@
+//! Tests for the block cipher
+use cipher::{Array, BlockCipherDecrypt, BlockCipherEncrypt, KeyInit};
+use hex_literal::hex;
+use magma::{Gost89CryptoProA, Magma};
+
+const KEY: [u8; 32] = hex!("FFEEDDCCBBAA99887766554433221100F0F1F2F3F4F5F6F7F8F9FAFBFCFDFEFF");
+
+/// Example from GOST R 34.12-2015
+#[test]
+fn gost_r_34_12() {
+    let cipher = Magma::new(&KEY.into());
+    let mut block = hex!("FEDCBA9876543210").into();
+    cipher.encrypt_block(&mut block);
+    assert_eq!(block, hex!("4EE901E5C2D8CA3D"));
+    cipher.decrypt_block(&mut block);
+    assert_eq!(block, hex!("FEDCBA9876543210"));
+}
+
+/// The cipher can be used directly as a single-block backend
+#[test]
+fn backend() {
+    let cipher = Magma::new(&KEY.into());
+    let mut block = Array(hex!("FEDCBA9876543210"));
+    cipher::BlockCipherEncBackend::encrypt_block(&cipher, (&mut block).into());
+    assert_eq!(block, hex!("4EE901E5C2D8CA3D"));
+    cipher::BlockCipherDecBackend::decrypt_block(&cipher, (&mut block).into());
+    assert_eq!(block, hex!("FEDCBA9876543210"));
+}
+
+/// Example of the ECB mode from GOST R 34.13-2015
+#[test]
+fn gost_r_34_13_ecb() {
+    let pt = hex!("92DEF06B3C130A59" "DB54C704F8189D20" "4A98FB2E67A8024C" "8912409B17B57E41");
+    let ct = hex!("2B073F0494F372A0" "DE70E715D3556E48" "11D8D9E9EACFBC1E" "7C68260996C67EFB");
+
+    let cipher = Magma::new(&KEY.into());
+    let mut blocks: [Array<u8, _>; 4] =
+        core::array::from_fn(|i| Array::try_from(&pt[8 * i..][..8]).unwrap());
+    cipher.encrypt_blocks(&mut blocks);
+    assert_eq!(blocks.concat(), ct);
+    cipher.decrypt_blocks(&mut blocks);
+    assert_eq!(blocks.concat(), pt);
+}
+
+/// Check that parallel processing of blocks matches processing of single
+/// blocks for all numbers of blocks up to a few parallel batches.
+#[test]
+fn par_blocks() {
+    let cipher = Gost89CryptoProA::new(&KEY.into());
+    let blocks: Vec<Array<u8, _>> = (0..100u64)
+        .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15).to_be_bytes().into())
+        .collect();
+
+    for n in 0..blocks.len() {
+        let mut buf = blocks[..n].to_vec();
+        cipher.encrypt_blocks(&mut buf);
+        for (b, c) in blocks.iter().zip(&buf) {
+            let mut block = *b;
+            cipher.encrypt_block(&mut block);
+            assert_eq!(&block, c);
+        }
+        cipher.decrypt_blocks(&mut buf);
+        assert_eq!(buf, blocks[..n]);
+    }
+}
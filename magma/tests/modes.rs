@@ -0,0 +1,213 @@
+//! Tests for the GOST 28147-89 modes with key meshing
+//!
+//! The example vectors of <http://cryptomanager.com/tv.html> fix the byte
+//! order of the cipher and the CFB mode. The counter mode and the key meshing
+//! are checked against direct implementations of GOST 28147-89 section 3 and
+//! RFC 4357 section 2.3.2 on top of the block cipher.
+use cipher::{BlockCipherDecrypt, BlockCipherEncrypt, KeyInit, StreamCipher};
+use hex_literal::hex;
+use magma::{
+    CryptoProA, Gost89, Gost89CryptoProA, Gost89Test, Sbox, TestSbox,
+    modes::{Gost89CfbDec, Gost89CfbEnc, Gost89Cnt, KeyMeshing},
+};
+
+const KEY: &[u8; 32] = b"This is message\xFF length\x0032 bytes";
+const IV: [u8; 8] = hex!("0102030405060708");
+
+/// Key of the cryptomanager.com examples, which use [`TestSbox`]
+const CM_KEY: [u8; 32] = hex!("75713134B60FEC45A607BB83AA3746AF4FF99DA6D1B53B5B1B402A1BAA030D1B");
+
+fn plaintext() -> Vec<u8> {
+    (0..2100).map(|i: u32| (i * 7 + 3) as u8).collect()
+}
+
+/// Block cipher in the byte order of GOST 28147-89: little-endian key words
+/// and block words in the reverse order of GOST R 34.12-2015.
+struct Legacy<S: Sbox>(Gost89<S>);
+
+impl<S: Sbox> Legacy<S> {
+    fn new(key: &[u8; 32]) -> Self {
+        let mut key = *key;
+        key.chunks_exact_mut(4).for_each(<[u8]>::reverse);
+        Self(Gost89::new(&key.into()))
+    }
+
+    fn encrypt(&self, mut block: [u8; 8]) -> [u8; 8] {
+        block.reverse();
+        let mut block = block.into();
+        self.0.encrypt_block(&mut block);
+        let mut block: [u8; 8] = block.into();
+        block.reverse();
+        block
+    }
+
+    fn decrypt(&self, mut block: [u8; 8]) -> [u8; 8] {
+        block.reverse();
+        let mut block = block.into();
+        self.0.decrypt_block(&mut block);
+        let mut block: [u8; 8] = block.into();
+        block.reverse();
+        block
+    }
+
+    /// CryptoPro key meshing of RFC 4357 section 2.3.2, which decrypts the
+    /// constant `C` with the current key and encrypts `iv` with the new key.
+    fn mesh(&self, iv: &mut [u8; 8]) -> Self {
+        const C: [u8; 32] = hex!(
+            "6900722264C904238D3ADB9646E92AC4"
+            "18FEAC9400ED0712C086DCC2EF4CA92B"
+        );
+        let mut key = [0; 32];
+        for (k, c) in key.chunks_exact_mut(8).zip(C.chunks_exact(8)) {
+            k.copy_from_slice(&self.decrypt(c.try_into().unwrap()));
+        }
+        let cipher = Self::new(&key);
+        *iv = cipher.encrypt(*iv);
+        cipher
+    }
+}
+
+/// Return the cipher for the block `i` of the data.
+fn next_cipher<S: Sbox>(
+    cipher: Legacy<S>,
+    i: usize,
+    meshing: KeyMeshing,
+    iv: &mut [u8; 8],
+) -> Legacy<S> {
+    if meshing == KeyMeshing::CryptoPro && i != 0 && i % 128 == 0 {
+        cipher.mesh(iv)
+    } else {
+        cipher
+    }
+}
+
+/// Counter mode of GOST 28147-89 section 3.1: the halves `N3` and `N4` of
+/// the encrypted IV are incremented by `C2` modulo 2^32 and by `C1` modulo
+/// 2^32 - 1, and the result is encrypted.
+fn cnt_ref<S: Sbox>(key: &[u8; 32], iv: [u8; 8], meshing: KeyMeshing, data: &mut [u8]) {
+    let mut cipher = Legacy::<S>::new(key);
+    let mut n = cipher.encrypt(iv);
+    for (i, chunk) in data.chunks_mut(8).enumerate() {
+        cipher = next_cipher(cipher, i, meshing, &mut n);
+        let n3 = u32::from_le_bytes(n[..4].try_into().unwrap()).wrapping_add(0x0101_0101);
+        let n4 = u32::from_le_bytes(n[4..].try_into().unwrap());
+        let n4 = ((u64::from(n4) + 0x0101_0104) % 0xFFFF_FFFF) as u32;
+        n[..4].copy_from_slice(&n3.to_le_bytes());
+        n[4..].copy_from_slice(&n4.to_le_bytes());
+        for (d, g) in chunk.iter_mut().zip(cipher.encrypt(n)) {
+            *d ^= g;
+        }
+    }
+}
+
+/// Cipher feedback mode of GOST 28147-89 section 3.2.
+fn cfb_ref<S: Sbox>(key: &[u8; 32], iv: [u8; 8], meshing: KeyMeshing, data: &mut [u8]) {
+    let mut cipher = Legacy::<S>::new(key);
+    let mut feedback = iv;
+    for (i, chunk) in data.chunks_mut(8).enumerate() {
+        cipher = next_cipher(cipher, i, meshing, &mut feedback);
+        for (d, g) in chunk.iter_mut().zip(cipher.encrypt(feedback)) {
+            *d ^= g;
+        }
+        feedback[..chunk.len()].copy_from_slice(chunk);
+    }
+}
+
+#[test]
+fn cryptomanager_vectors() {
+    let cipher = Legacy::<TestSbox>::new(&CM_KEY);
+    assert_eq!(
+        cipher.encrypt(hex!("1122334455667788")),
+        hex!("03251E14F9D28ACB")
+    );
+
+    let pt = hex!("112233445566778899AABBCCDDEEFF00");
+    let ct = hex!("6EE84586DD2BCA0CAD3616940E78BD42");
+    let mut buf = pt;
+    Gost89CfbEnc::<Gost89Test>::new(&CM_KEY.into(), &IV.into(), KeyMeshing::None).encrypt(&mut buf);
+    assert_eq!(buf, ct);
+    Gost89CfbDec::<Gost89Test>::new(&CM_KEY.into(), &IV.into(), KeyMeshing::None).decrypt(&mut buf);
+    assert_eq!(buf, pt);
+}
+
+fn cnt(meshing: KeyMeshing) -> Gost89Cnt<Gost89CryptoProA> {
+    Gost89Cnt::new(KEY.into(), &IV.into(), meshing)
+}
+
+fn cfb_enc(meshing: KeyMeshing) -> Gost89CfbEnc<Gost89CryptoProA> {
+    Gost89CfbEnc::new(KEY.into(), &IV.into(), meshing)
+}
+
+fn cfb_dec(meshing: KeyMeshing) -> Gost89CfbDec<Gost89CryptoProA> {
+    Gost89CfbDec::new(KEY.into(), &IV.into(), meshing)
+}
+
+#[test]
+fn cnt_mode() {
+    let pt = plaintext();
+    for meshing in [KeyMeshing::None, KeyMeshing::CryptoPro] {
+        let mut ct = pt.clone();
+        cnt_ref::<CryptoProA>(KEY, IV, meshing, &mut ct);
+
+        let mut buf = pt.clone();
+        cnt(meshing).apply_keystream(&mut buf);
+        assert_eq!(buf, ct);
+        cnt(meshing).apply_keystream(&mut buf);
+        assert_eq!(buf, pt);
+
+        // Processing the data in pieces gives the same result
+        for step in [1, 3, 8, 13, 1024, 1025] {
+            let mut cipher = cnt(meshing);
+            let mut buf = pt.clone();
+            for chunk in buf.chunks_mut(step) {
+                cipher.apply_keystream(chunk);
+            }
+            assert_eq!(buf, ct);
+        }
+    }
+
+    // Key meshing changes the data after the first 1 KiB only
+    let mut ct = pt.clone();
+    cnt(KeyMeshing::None).apply_keystream(&mut ct);
+    let mut ct_meshed = pt.clone();
+    cnt(KeyMeshing::CryptoPro).apply_keystream(&mut ct_meshed);
+    assert_eq!(ct[..1024], ct_meshed[..1024]);
+    assert_ne!(ct[1024..1032], ct_meshed[1024..1032]);
+
+    let mut ct = pt[..20].to_vec();
+    cnt_ref::<TestSbox>(&CM_KEY, IV, KeyMeshing::None, &mut ct);
+    let mut buf = pt[..20].to_vec();
+    Gost89Cnt::<Gost89Test>::new(&CM_KEY.into(), &IV.into(), KeyMeshing::None)
+        .apply_keystream(&mut buf);
+    assert_eq!(buf, ct);
+}
+
+#[test]
+fn cfb_mode() {
+    let pt = plaintext();
+    for meshing in [KeyMeshing::None, KeyMeshing::CryptoPro] {
+        let mut ct = pt.clone();
+        cfb_ref::<CryptoProA>(KEY, IV, meshing, &mut ct);
+
+        let mut buf = pt.clone();
+        cfb_enc(meshing).encrypt(&mut buf);
+        assert_eq!(buf, ct);
+        cfb_dec(meshing).decrypt(&mut buf);
+        assert_eq!(buf, pt);
+
+        // Processing the data in pieces gives the same result
+        for step in [1, 3, 8, 13, 1024, 1025] {
+            let mut enc = cfb_enc(meshing);
+            let mut dec = cfb_dec(meshing);
+            let mut buf = pt.clone();
+            for chunk in buf.chunks_mut(step) {
+                enc.encrypt(chunk);
+            }
+            assert_eq!(buf, ct);
+            for chunk in buf.chunks_mut(step) {
+                dec.decrypt(chunk);
+            }
+            assert_eq!(buf, pt);
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! Tests for the key wrap algorithms
+//!
+//! The GOST 28147-89 and CryptoPro key wraps are checked against direct
+//! implementations of RFC 4357 section 6 on top of the block cipher and the
+//! MAC, which are tested against published vectors in `tests/modes.rs` and
+//! `tests/mac.rs`. KExp15 and KImp15 use the examples of R 1323565.1.017.
+#![cfg(feature = "wrap")]
+
+use cipher::{BlockCipherEncrypt, KeyInit};
+use hex_literal::hex;
+use kuznyechik::Kuznyechik;
+use magma::{
+    CryptoProA, Gost89, Gost89CryptoProA, Magma,
+    mac::Gost89Mac,
+    modes::KeyMeshing,
+    wrap::{
+        Error, diversify_kek, kexp15, kimp15, unwrap_cryptopro, unwrap_gost, wrap_cryptopro,
+        wrap_gost,
+    },
+};
+
+const KEK: [u8; 32] = hex!("202122232425262728292A2B2C2D2E2F303132333435363738393A3B3C3D3E3F");
+const CEK: [u8; 32] = hex!("404142434445464748494A4B4C4D4E4F505152535455565758595A5B5C5D5E5F");
+const UKM: [u8; 8] = hex!("0123456789ABCDEF");
+
+/// Block encryption in the byte order of GOST 28147-89: little-endian key
+/// words and block words in the reverse order of GOST R 34.12-2015.
+fn encrypt_legacy(key: &[u8; 32], block: &[u8; 8]) -> [u8; 8] {
+    let mut key = *key;
+    key.chunks_exact_mut(4).for_each(<[u8]>::reverse);
+    let mut block = *block;
+    block.reverse();
+    let mut block = block.into();
+    Gost89::<CryptoProA>::new(&key.into()).encrypt_block(&mut block);
+    let mut block: [u8; 8] = block.into();
+    block.reverse();
+    block
+}
+
+/// GOST 28147-89 key wrap, RFC 4357 section 6.1: the CEK encrypted in ECB
+/// mode followed by the first 4 bytes of its MAC with the UKM as the IV.
+fn wrap_gost_ref(kek: &[u8; 32], ukm: &[u8; 8], cek: &[u8; 32]) -> Vec<u8> {
+    let mut res = ukm.to_vec();
+    for block in cek.chunks_exact(8) {
+        res.extend(encrypt_legacy(kek, block.try_into().unwrap()));
+    }
+    let mut mac =
+        Gost89Mac::<Gost89CryptoProA>::new_with_iv(kek.into(), ukm.into(), KeyMeshing::None);
+    mac.update(cek);
+    res.extend(&mac.finalize()[..4]);
+    res
+}
+
+/// CryptoPro KEK diversification, RFC 4357 section 6.5: for every byte of
+/// the UKM, the key words selected by its bits and the remaining ones are
+/// summed into the IV with which the key is encrypted with itself in CFB mode.
+fn diversify_kek_ref(kek: &[u8; 32], ukm: &[u8; 8]) -> [u8; 32] {
+    let mut key = *kek;
+    for &u in ukm {
+        let (mut s1, mut s2) = (0u32, 0u32);
+        for (j, word) in key.chunks_exact(4).enumerate() {
+            let k = u32::from_le_bytes(word.try_into().unwrap());
+            if (u >> j) & 1 == 1 {
+                s1 = s1.wrapping_add(k);
+            } else {
+                s2 = s2.wrapping_add(k);
+            }
+        }
+        let mut iv = [0; 8];
+        iv[..4].copy_from_slice(&s1.to_le_bytes());
+        iv[4..].copy_from_slice(&s2.to_le_bytes());
+
+        let kek = key;
+        for block in key.chunks_exact_mut(8) {
+            let gamma = encrypt_legacy(&kek, &iv);
+            block.iter_mut().zip(gamma).for_each(|(b, g)| *b ^= g);
+            iv.copy_from_slice(block);
+        }
+    }
+    key
+}
+
+#[test]
+fn gost_wrap() {
+    let wrapped = wrap_gost_ref(&KEK, &UKM, &CEK);
+    assert_eq!(
+        wrap_gost::<CryptoProA>(&KEK.into(), &UKM.into(), &CEK.into())[..],
+        wrapped[..]
+    );
+    assert_eq!(
+        unwrap_gost::<CryptoProA>(&KEK.into(), wrapped[..].try_into().unwrap()),
+        Ok(CEK.into())
+    );
+}
+
+#[test]
+fn cryptopro_wrap() {
+    let kek = diversify_kek_ref(&KEK, &UKM);
+    assert_eq!(diversify_kek::<CryptoProA>(&KEK.into(), &UKM.into()), kek);
+
+    // Every bit of the UKM selects a different key word in each round
+    for ukm in [[0x00; 8], [0xFF; 8], hex!("8040201008040201")] {
+        assert_eq!(
+            diversify_kek::<CryptoProA>(&KEK.into(), &ukm.into()),
+            diversify_kek_ref(&KEK, &ukm)
+        );
+    }
+
+    // RFC 4357 section 6.3: the GOST 28147-89 key wrap with the diversified KEK
+    let wrapped = wrap_gost_ref(&kek, &UKM, &CEK);
+    assert_eq!(
+        wrap_cryptopro::<CryptoProA>(&KEK.into(), &UKM.into(), &CEK.into())[..],
+        wrapped[..]
+    );
+    assert_eq!(
+        unwrap_cryptopro::<CryptoProA>(&KEK.into(), wrapped[..].try_into().unwrap()),
+        Ok(CEK.into())
+    );
+}
+
+#[test]
+fn unwrap_tampered() {
+    let wrapped = wrap_cryptopro::<CryptoProA>(&KEK.into(), &UKM.into(), &CEK.into());
+    for i in 0..wrapped.len() {
+        let mut tampered = wrapped;
+        tampered[i] ^= 1;
+        let res = unwrap_cryptopro::<CryptoProA>(&KEK.into(), &tampered);
+        assert_eq!(res, Err(Error::Verification));
+    }
+}
+
+const KEY: [u8; 32] = hex!("8899AABBCCDDEEFF0011223344556677FEDCBA98765432100123456789ABCDEF");
+const K_ENC: [u8; 32] = hex!("202122232425262728292A2B2C2D2E2F38393A3B3C3D3E3F3031323334353637");
+const K_MAC: [u8; 32] = hex!("08090A0B0C0D0E0F0001020304050607101112131415161718191A1B1C1D1E1F");
+
+/// Example from R 1323565.1.017-2018, appendix A.1
+#[test]
+fn kexp15_magma() {
+    let iv = hex!("67BED654");
+    let exported = hex!(
+        "CFD5A12D5B81B6E1E99C916D07900C6AC12703FB3ABDED55567BF3742C899C75"
+        "5DAFE7B42E3A8BD9"
+    );
+
+    let mut buf = [0u8; 40];
+    kexp15::<Magma>(&K_ENC.into(), &K_MAC.into(), &iv.into(), &KEY, &mut buf).unwrap();
+    assert_eq!(buf, exported);
+
+    let mut key = [0u8; 32];
+    kimp15::<Magma>(
+        &K_ENC.into(),
+        &K_MAC.into(),
+        &iv.into(),
+        &exported,
+        &mut key,
+    )
+    .unwrap();
+    assert_eq!(key, KEY);
+}
+
+/// Example from R 1323565.1.017-2018, appendix A.2
+#[test]
+fn kexp15_kuznyechik() {
+    let iv = hex!("0909472DD9F26BE8");
+    let exported = hex!(
+        "E36184E84E8D736FF36CC2E5AE065DC656B23C20F549B02FDFF88E1F3F30D8C2"
+        "9A53F3CA554DBAD80DE152B9A4625B32"
+    );
+
+    let mut buf = [0u8; 48];
+    kexp15::<Kuznyechik>(&K_ENC.into(), &K_MAC.into(), &iv.into(), &KEY, &mut buf).unwrap();
+    assert_eq!(buf, exported);
+
+    let mut key = [0u8; 32];
+    let res = kimp15::<Kuznyechik>(
+        &K_ENC.into(),
+        &K_MAC.into(),
+        &iv.into(),
+        &exported,
+        &mut key,
+    );
+    res.unwrap();
+    assert_eq!(key, KEY);
+}
+
+#[test]
+fn kexp15_unaligned() {
+    let iv = hex!("67BED654");
+    for len in 0..=32 {
+        let key = &KEY[..len];
+        let mut buf = [0u8; 40];
+        let buf = &mut buf[..key.len() + 8];
+        kexp15::<Magma>(&K_ENC.into(), &K_MAC.into(), &iv.into(), key, buf).unwrap();
+
+        let mut out = [0u8; 32];
+        let out = &mut out[..key.len()];
+        kimp15::<Magma>(&K_ENC.into(), &K_MAC.into(), &iv.into(), buf, out).unwrap();
+        assert_eq!(out, key);
+    }
+}
+
+#[test]
+fn kimp15_errors() {
+    let iv = hex!("67BED654");
+    let mut exported = [0u8; 40];
+    kexp15::<Magma>(
+        &K_ENC.into(),
+        &K_MAC.into(),
+        &iv.into(),
+        &KEY,
+        &mut exported,
+    )
+    .unwrap();
+
+    let mut short = [0u8; 39];
+    let res = kexp15::<Magma>(&K_ENC.into(), &K_MAC.into(), &iv.into(), &KEY, &mut short);
+    assert_eq!(res, Err(Error::InvalidLength));
+    let mut key = [0u8; 31];
+    let res = kimp15::<Magma>(
+        &K_ENC.into(),
+        &K_MAC.into(),
+        &iv.into(),
+        &exported,
+        &mut key,
+    );
+    assert_eq!(res, Err(Error::InvalidLength));
+
+    for i in 0..exported.len() {
+        let mut tampered = exported;
+        tampered[i] ^= 0x80;
+        let mut key = [0u8; 32];
+        let res = kimp15::<Magma>(
+            &K_ENC.into(),
+            &K_MAC.into(),
+            &iv.into(),
+            &tampered,
+            &mut key,
+        );
+        assert_eq!(res, Err(Error::Verification));
+        assert_eq!(key, [0; 32]);
+    }
+}
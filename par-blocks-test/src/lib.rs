@@ -0,0 +1,71 @@
+//! Shared tests of the multi-block backends of the block ciphers in this
+//! workspace.
+//!
+//! The crate is not published. Cipher crates use it as a path-only
+//! dev-dependency, which `cargo publish` strips from their manifests.
+
+use cipher::{
+    Block, BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherDecrypt, BlockCipherEncBackend,
+    BlockCipherEncClosure, BlockCipherEncrypt, BlockSizeUser,
+    array::{Array, ArraySize},
+    typenum::Unsigned,
+};
+use core::marker::PhantomData;
+
+/// Records the `ParBlocksSize` of the encryption and decryption backends.
+struct ParBlocks<'a, BS>(&'a mut [usize; 2], PhantomData<BS>);
+
+impl<BS: ArraySize> BlockSizeUser for ParBlocks<'_, BS> {
+    type BlockSize = BS;
+}
+
+impl<BS: ArraySize> BlockCipherEncClosure for ParBlocks<'_, BS> {
+    fn call<B: BlockCipherEncBackend<BlockSize = BS>>(self, _: &B) {
+        self.0[0] = B::ParBlocksSize::USIZE;
+    }
+}
+
+impl<BS: ArraySize> BlockCipherDecClosure for ParBlocks<'_, BS> {
+    fn call<B: BlockCipherDecBackend<BlockSize = BS>>(self, _: &B) {
+        self.0[1] = B::ParBlocksSize::USIZE;
+    }
+}
+
+/// Return the number of blocks processed at once by the encryption and the
+/// decryption backend of `cipher`.
+pub fn par_blocks<C: BlockCipherEncrypt + BlockCipherDecrypt>(cipher: &C) -> [usize; 2] {
+    let mut par = [0; 2];
+    cipher.encrypt_with_backend(ParBlocks(&mut par, PhantomData));
+    cipher.decrypt_with_backend(ParBlocks(&mut par, PhantomData));
+    par
+}
+
+/// Check that both backends of `cipher` process `par` blocks at once, and that
+/// `encrypt_blocks` and `decrypt_blocks` agree with single blocks for every
+/// length up to three batches. This covers inputs shorter than a batch and
+/// batches followed by a partial tail, and checks that the blocks after the
+/// processed prefix are left untouched.
+pub fn check_par_blocks<C: BlockCipherEncrypt + BlockCipherDecrypt>(cipher: &C, par: usize) {
+    assert_eq!(par_blocks(cipher), [par; 2], "blocks per batch");
+
+    let data: Vec<Block<C>> = (0..3 * par)
+        .map(|i| Array::from_fn(|j| (i * 31 + j * 5) as u8))
+        .collect();
+    for n in 1..=data.len() {
+        let mut expected = data.clone();
+        expected[..n]
+            .iter_mut()
+            .for_each(|b| cipher.encrypt_block(b));
+        let mut blocks = data.clone();
+        cipher.encrypt_blocks(&mut blocks[..n]);
+        assert_eq!(blocks, expected, "encryption of {n} blocks");
+
+        let mut expected = data.clone();
+        expected[..n]
+            .iter_mut()
+            .for_each(|b| cipher.decrypt_block(b));
+        let mut blocks = data.clone();
+        cipher.decrypt_blocks(&mut blocks[..n]);
+        assert_eq!(blocks, expected, "decryption of {n} blocks");
+    }
+}
@@ -0,0 +1,263 @@
+//! RC5 with parameters selected at runtime.
+
+use alloc::{boxed::Box, vec};
+use cipher::{Array, InvalidLength, typenum::Unsigned};
+use core::fmt;
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::primitives::{self, Word};
+
+/// Maximum number of rounds supported by RC5.
+const MAX_ROUNDS: usize = 255;
+/// Maximum key length in bytes supported by RC5.
+const MAX_KEY_LEN: usize = 255;
+
+/// RC5 block cipher instance with the word size, number of rounds and key
+/// length selected at runtime.
+///
+/// Supported parameters are the same as for [`RC5`][crate::RC5]: word size
+/// `w` in bits is one of 8, 16, 32, 64 or 128, number of rounds `r` is at
+/// most 255 and key length `b` in bytes is at most 255. Block size is
+/// `2 * w / 8` bytes.
+///
+/// The expanded key table is allocated on the heap with the size required by
+/// the selected word size and number of rounds. Prefer [`RC5`][crate::RC5]
+/// when the parameters are known at compile time, it needs no allocation.
+///
+/// ```
+/// use rc5::DynRc5;
+///
+/// let key = [0u8; 16];
+/// let cipher = DynRc5::new(32, 12, &key).unwrap();
+/// assert_eq!(cipher.block_size(), 8);
+///
+/// let mut block = *b"RC5 blok";
+/// cipher.encrypt_block(&mut block).unwrap();
+/// cipher.decrypt_block(&mut block).unwrap();
+/// assert_eq!(&block, b"RC5 blok");
+/// ```
+#[derive(Clone)]
+pub struct DynRc5 {
+    key_table: KeyTable,
+    rounds: u8,
+    key_len: u8,
+}
+
+/// Expanded key table for every supported word size.
+#[derive(Clone)]
+enum KeyTable {
+    W8(Box<[u8]>),
+    W16(Box<[u16]>),
+    W32(Box<[u32]>),
+    W64(Box<[u64]>),
+    W128(Box<[u128]>),
+}
+
+/// Call `$f` with the expanded key table.
+macro_rules! with_key_table {
+    ($self:ident, $f:ident $(, $arg:expr)*) => {{
+        match &$self.key_table {
+            KeyTable::W8(t) => $f(t $(, $arg)*),
+            KeyTable::W16(t) => $f(t $(, $arg)*),
+            KeyTable::W32(t) => $f(t $(, $arg)*),
+            KeyTable::W64(t) => $f(t $(, $arg)*),
+            KeyTable::W128(t) => $f(t $(, $arg)*),
+        }
+    }};
+}
+
+impl DynRc5 {
+    /// Create a new RC5-`w`/`r`/`b` instance, where `word_size` is `w` in
+    /// bits, `rounds` is `r` and `b` is the length of `key`.
+    ///
+    /// Returns [`InvalidParametersError`] if any of the parameters is out of
+    /// the supported range.
+    pub fn new(
+        word_size: usize,
+        rounds: usize,
+        key: &[u8],
+    ) -> Result<Self, InvalidParametersError> {
+        if rounds > MAX_ROUNDS || key.len() > MAX_KEY_LEN {
+            return Err(InvalidParametersError);
+        }
+        let table_len = 2 * (rounds + 1);
+        let key_table = match word_size {
+            8 => KeyTable::W8(expand_key(key, table_len)),
+            16 => KeyTable::W16(expand_key(key, table_len)),
+            32 => KeyTable::W32(expand_key(key, table_len)),
+            64 => KeyTable::W64(expand_key(key, table_len)),
+            128 => KeyTable::W128(expand_key(key, table_len)),
+            _ => return Err(InvalidParametersError),
+        };
+        Ok(Self {
+            key_table,
+            rounds: rounds as u8,
+            key_len: key.len() as u8,
+        })
+    }
+
+    /// Word size in bits.
+    pub fn word_size(&self) -> usize {
+        match self.key_table {
+            KeyTable::W8(_) => 8,
+            KeyTable::W16(_) => 16,
+            KeyTable::W32(_) => 32,
+            KeyTable::W64(_) => 64,
+            KeyTable::W128(_) => 128,
+        }
+    }
+
+    /// Number of rounds.
+    pub fn rounds(&self) -> usize {
+        self.rounds as usize
+    }
+
+    /// Key length in bytes.
+    pub fn key_len(&self) -> usize {
+        self.key_len as usize
+    }
+
+    /// Block size in bytes.
+    pub fn block_size(&self) -> usize {
+        self.word_size() / 4
+    }
+
+    /// Encrypt a single block in place.
+    ///
+    /// Returns [`InvalidLength`] if `block` is not [`block_size`][Self::block_size]
+    /// bytes long.
+    pub fn encrypt_block(&self, block: &mut [u8]) -> Result<(), InvalidLength> {
+        if block.len() != self.block_size() {
+            return Err(InvalidLength);
+        }
+        self.encrypt_blocks(block)
+    }
+
+    /// Decrypt a single block in place.
+    ///
+    /// Returns [`InvalidLength`] if `block` is not [`block_size`][Self::block_size]
+    /// bytes long.
+    pub fn decrypt_block(&self, block: &mut [u8]) -> Result<(), InvalidLength> {
+        if block.len() != self.block_size() {
+            return Err(InvalidLength);
+        }
+        self.decrypt_blocks(block)
+    }
+
+    /// Encrypt blocks in place in ECB mode.
+    ///
+    /// Returns [`InvalidLength`] if the length of `blocks` is zero or not a
+    /// multiple of [`block_size`][Self::block_size].
+    pub fn encrypt_blocks(&self, blocks: &mut [u8]) -> Result<(), InvalidLength> {
+        self.check_len(blocks)?;
+        with_key_table!(self, encrypt_blocks, blocks);
+        Ok(())
+    }
+
+    /// Decrypt blocks in place in ECB mode.
+    ///
+    /// Returns [`InvalidLength`] if the length of `blocks` is zero or not a
+    /// multiple of [`block_size`][Self::block_size].
+    pub fn decrypt_blocks(&self, blocks: &mut [u8]) -> Result<(), InvalidLength> {
+        self.check_len(blocks)?;
+        with_key_table!(self, decrypt_blocks, blocks);
+        Ok(())
+    }
+
+    fn check_len(&self, blocks: &[u8]) -> Result<(), InvalidLength> {
+        if blocks.is_empty() || blocks.len() % self.block_size() != 0 {
+            return Err(InvalidLength);
+        }
+        Ok(())
+    }
+}
+
+fn expand_key<W: Word>(key: &[u8], table_len: usize) -> Box<[W]> {
+    let word_len = W::Bytes::USIZE;
+    // RC5 uses a single zero word for empty keys
+    let key_words_len = key.len().div_ceil(word_len).max(1);
+
+    let mut key_as_words = vec![W::ZERO; key_words_len];
+    primitives::key_into_words(key, &mut key_as_words);
+
+    let mut key_table = vec![W::ZERO; table_len].into_boxed_slice();
+    primitives::initialize_expanded_key_table(&mut key_table);
+    primitives::mix_in(&mut key_table, &mut key_as_words);
+
+    #[cfg(feature = "zeroize")]
+    key_as_words[..].zeroize();
+
+    key_table
+}
+
+fn words_from_block<W: Word>(block: &[u8]) -> (W, W) {
+    let (a, b) = block.split_at(W::Bytes::USIZE);
+    let a = Array::try_from(a).expect("block has two words");
+    let b = Array::try_from(b).expect("block has two words");
+    (W::from_le_bytes(&a), W::from_le_bytes(&b))
+}
+
+fn block_from_words<W: Word>(a: W, b: W, block: &mut [u8]) {
+    let (a_out, b_out) = block.split_at_mut(W::Bytes::USIZE);
+    a_out.copy_from_slice(&a.to_le_bytes());
+    b_out.copy_from_slice(&b.to_le_bytes());
+}
+
+fn encrypt_blocks<W: Word>(key_table: &[W], blocks: &mut [u8]) {
+    for block in blocks.chunks_exact_mut(2 * W::Bytes::USIZE) {
+        let (a, b) = words_from_block(block);
+        let (a, b) = primitives::encrypt_words(a, b, key_table);
+        block_from_words(a, b, block);
+    }
+}
+
+fn decrypt_blocks<W: Word>(key_table: &[W], blocks: &mut [u8]) {
+    for block in blocks.chunks_exact_mut(2 * W::Bytes::USIZE) {
+        let (a, b) = words_from_block(block);
+        let (a, b) = primitives::decrypt_words(a, b, key_table);
+        block_from_words(a, b, block);
+    }
+}
+
+impl fmt::Debug for DynRc5 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "DynRc5 - {}/{}/{} {{ ... }}",
+            self.word_size(),
+            self.rounds,
+            self.key_len,
+        )
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for DynRc5 {}
+
+impl Drop for DynRc5 {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        match &mut self.key_table {
+            KeyTable::W8(t) => t[..].zeroize(),
+            KeyTable::W16(t) => t[..].zeroize(),
+            KeyTable::W32(t) => t[..].zeroize(),
+            KeyTable::W64(t) => t[..].zeroize(),
+            KeyTable::W128(t) => t[..].zeroize(),
+        }
+    }
+}
+
+/// Error returned by [`DynRc5::new`] for unsupported RC5 parameters.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct InvalidParametersError;
+
+impl fmt::Display for InvalidParametersError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("InvalidParameters")
+    }
+}
+
+impl core::error::Error for InvalidParametersError {}
@@ -18,6 +18,9 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![warn(missing_docs, rust_2018_idioms)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 use cipher::{
     AlgorithmName, Array, BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherDecrypt,
     BlockCipherEncBackend, BlockCipherEncClosure, BlockCipherEncrypt, BlockSizeUser, KeyInit,
@@ -28,7 +31,6 @@ use cipher::{
     typenum::{Diff, IsLess, Le, NonZero, Sum, Unsigned},
 };
 use core::{
-    cmp::max,
     fmt,
     marker::PhantomData,
     ops::{Add, Div, Mul, Sub},
@@ -37,7 +39,14 @@ use core::{
 #[cfg(feature = "zeroize")]
 use cipher::zeroize::{Zeroize, ZeroizeOnDrop};
 
+#[cfg(feature = "alloc")]
+mod dyn_rc5;
 mod primitives;
+pub mod rfc2040;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use dyn_rc5::{DynRc5, InvalidParametersError};
 
 use primitives::{
     Block, BlockSize, ExpandedKeyTable, ExpandedKeyTableSize, Key, KeyAsWords, KeyAsWordsSize, Word,
@@ -89,65 +98,17 @@ where
     KeyAsWordsSize<W, B>: ArraySize,
 {
     pub(crate) fn substitute_key(key: &Key<B>) -> ExpandedKeyTable<W, R> {
-        let key_as_words = Self::key_into_words(key);
-        let expanded_key_table = Self::initialize_expanded_key_table();
-
-        Self::mix_in(expanded_key_table, key_as_words)
-    }
-
-    fn key_into_words(key: &Key<B>) -> KeyAsWords<W, B> {
         // can be uninitialized
-        let mut key_as_words: Array<W, KeyAsWordsSize<W, B>> = Array::default();
-
-        for i in (0..B::USIZE).rev() {
-            key_as_words[i / W::Bytes::USIZE] =
-                key_as_words[i / W::Bytes::USIZE].rotate_left(W::EIGHT) + key[i].into();
-            // no need for wrapping addition since we are adding a byte sized uint onto an uint with its lsb byte zeroed
-        }
+        let mut key_as_words: KeyAsWords<W, B> = Array::default();
+        primitives::key_into_words(key, &mut key_as_words);
 
-        key_as_words
-    }
-
-    fn initialize_expanded_key_table() -> ExpandedKeyTable<W, R> {
         // must be zero initialized
-        let mut expanded_key_table: Array<W, ExpandedKeyTableSize<R>> = Array::from_fn(|_| W::ZERO);
-
-        expanded_key_table[0] = W::P;
-        for i in 1..expanded_key_table.len() {
-            expanded_key_table[i] = expanded_key_table[i - 1].wrapping_add(W::Q);
-        }
+        let mut expanded_key_table: ExpandedKeyTable<W, R> = Array::from_fn(|_| W::ZERO);
+        primitives::initialize_expanded_key_table(&mut expanded_key_table);
+        primitives::mix_in(&mut expanded_key_table, &mut key_as_words);
 
         expanded_key_table
     }
-
-    fn mix_in(
-        mut key_table: ExpandedKeyTable<W, R>,
-        mut key_as_words: KeyAsWords<W, B>,
-    ) -> ExpandedKeyTable<W, R> {
-        let (mut expanded_key_index, mut key_as_words_index) = (0, 0);
-        let (mut a, mut b) = (W::ZERO, W::ZERO);
-
-        for _ in 0..3 * max(key_as_words.len(), key_table.len()) {
-            key_table[expanded_key_index] = key_table[expanded_key_index]
-                .wrapping_add(a)
-                .wrapping_add(b)
-                .rotate_left(W::THREE);
-
-            a = key_table[expanded_key_index];
-
-            key_as_words[key_as_words_index] = key_as_words[key_as_words_index]
-                .wrapping_add(a)
-                .wrapping_add(b)
-                .rotate_left(a.wrapping_add(b));
-
-            b = key_as_words[key_as_words_index];
-
-            expanded_key_index = (expanded_key_index + 1) % key_table.len();
-            key_as_words_index = (key_as_words_index + 1) % key_as_words.len();
-        }
-
-        key_table
-    }
 }
 
 impl<W, R, B> RC5<W, R, B>
@@ -323,17 +284,8 @@ where
 {
     #[inline(always)]
     fn encrypt_block(&self, mut block: InOut<'_, '_, cipher::Block<Self>>) {
-        let (mut a, mut b) = Self::words_from_block(block.get_in());
-        let key = &self.key_table;
-
-        a = a.wrapping_add(key[0]);
-        b = b.wrapping_add(key[1]);
-
-        for i in 1..=R::USIZE {
-            a = a.bitxor(b).rotate_left(b).wrapping_add(key[2 * i]);
-            b = b.bitxor(a).rotate_left(a).wrapping_add(key[2 * i + 1]);
-        }
-
+        let (a, b) = Self::words_from_block(block.get_in());
+        let (a, b) = primitives::encrypt_words(a, b, &self.key_table);
         Self::block_from_words(a, b, block.get_out())
     }
 }
@@ -393,17 +345,8 @@ where
 {
     #[inline(always)]
     fn decrypt_block(&self, mut block: InOut<'_, '_, cipher::Block<Self>>) {
-        let (mut a, mut b) = Self::words_from_block(block.get_in());
-        let key = &self.key_table;
-
-        for i in (1..=R::USIZE).rev() {
-            b = b.wrapping_sub(key[2 * i + 1]).rotate_right(a).bitxor(a);
-            a = a.wrapping_sub(key[2 * i]).rotate_right(b).bitxor(b);
-        }
-
-        b = b.wrapping_sub(key[1]);
-        a = a.wrapping_sub(key[0]);
-
+        let (a, b) = Self::words_from_block(block.get_in());
+        let (a, b) = primitives::decrypt_words(a, b, &self.key_table);
         Self::block_from_words(a, b, block.get_out())
     }
 }
@@ -1,8 +1,11 @@
-use core::ops::{Add, BitXor, Mul};
+use core::{
+    cmp::max,
+    ops::{Add, BitXor, Mul},
+};
 
 use cipher::{
     array::{Array, ArraySize},
-    typenum::{Diff, Prod, Quot, Sum, U1, U2, U4, U8, U16},
+    typenum::{Diff, Prod, Quot, Sum, U1, U2, U4, U8, U16, Unsigned},
 };
 
 pub type BlockSize<W> = Prod<<W as Word>::Bytes, U2>;
@@ -42,6 +45,79 @@ where
     fn bitxor(self, other: Self) -> Self;
 }
 
+/// Load `key` into `key_as_words` as little-endian words.
+///
+/// `key_as_words` must be zero initialized and hold at least
+/// `ceil(key.len() / W::Bytes)` words.
+pub(crate) fn key_into_words<W: Word>(key: &[u8], key_as_words: &mut [W]) {
+    for i in (0..key.len()).rev() {
+        key_as_words[i / W::Bytes::USIZE] =
+            key_as_words[i / W::Bytes::USIZE].rotate_left(W::EIGHT) + key[i].into();
+        // no need for wrapping addition since we are adding a byte sized uint onto an uint with its lsb byte zeroed
+    }
+}
+
+/// Fill `key_table` with the magic constants sequence.
+pub(crate) fn initialize_expanded_key_table<W: Word>(key_table: &mut [W]) {
+    key_table[0] = W::P;
+    for i in 1..key_table.len() {
+        key_table[i] = key_table[i - 1].wrapping_add(W::Q);
+    }
+}
+
+/// Mix the secret key words into `key_table`.
+pub(crate) fn mix_in<W: Word>(key_table: &mut [W], key_as_words: &mut [W]) {
+    let (mut expanded_key_index, mut key_as_words_index) = (0, 0);
+    let (mut a, mut b) = (W::ZERO, W::ZERO);
+
+    for _ in 0..3 * max(key_as_words.len(), key_table.len()) {
+        key_table[expanded_key_index] = key_table[expanded_key_index]
+            .wrapping_add(a)
+            .wrapping_add(b)
+            .rotate_left(W::THREE);
+
+        a = key_table[expanded_key_index];
+
+        key_as_words[key_as_words_index] = key_as_words[key_as_words_index]
+            .wrapping_add(a)
+            .wrapping_add(b)
+            .rotate_left(a.wrapping_add(b));
+
+        b = key_as_words[key_as_words_index];
+
+        expanded_key_index = (expanded_key_index + 1) % key_table.len();
+        key_as_words_index = (key_as_words_index + 1) % key_as_words.len();
+    }
+}
+
+/// Encrypt the block `(a, b)` using `key` with `key.len() / 2 - 1` rounds.
+#[inline(always)]
+pub(crate) fn encrypt_words<W: Word>(mut a: W, mut b: W, key: &[W]) -> (W, W) {
+    a = a.wrapping_add(key[0]);
+    b = b.wrapping_add(key[1]);
+
+    for i in 1..key.len() / 2 {
+        a = a.bitxor(b).rotate_left(b).wrapping_add(key[2 * i]);
+        b = b.bitxor(a).rotate_left(a).wrapping_add(key[2 * i + 1]);
+    }
+
+    (a, b)
+}
+
+/// Decrypt the block `(a, b)` using `key` with `key.len() / 2 - 1` rounds.
+#[inline(always)]
+pub(crate) fn decrypt_words<W: Word>(mut a: W, mut b: W, key: &[W]) -> (W, W) {
+    for i in (1..key.len() / 2).rev() {
+        b = b.wrapping_sub(key[2 * i + 1]).rotate_right(a).bitxor(a);
+        a = a.wrapping_sub(key[2 * i]).rotate_right(b).bitxor(b);
+    }
+
+    b = b.wrapping_sub(key[1]);
+    a = a.wrapping_sub(key[0]);
+
+    (a, b)
+}
+
 mod private {
     #[cfg(feature = "zeroize")]
     pub trait Sealed: cipher::zeroize::DefaultIsZeroes {}
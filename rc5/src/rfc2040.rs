@@ -0,0 +1,365 @@
+//! Modes of operation defined in [RFC 2040].
+//!
+//! The RFC specifies three ways of using RC5 with messages longer than a
+//! single block:
+//!
+//! - [`Cbc`]: RC5-CBC, cipher block chaining over whole blocks without padding.
+//! - [`CbcPad`]: RC5-CBC-Pad, cipher block chaining with 1 to `bb` bytes of
+//!   padding, where `bb` is the block size in bytes.
+//! - [`Cts`]: RC5-CTS, cipher block chaining with ciphertext stealing, which
+//!   produces a ciphertext of exactly the same length as the plaintext.
+//!
+//! All modes are initialized with a key and an IV of the block size using
+//! [`KeyIvInit`][cipher::KeyIvInit], or with an already initialized cipher
+//! using [`InnerIvInit`]. The IV can be replaced without rekeying using the
+//! `set_iv` methods, which correspond to `RC5_CBC_SetIV` in the RFC.
+//!
+//! # Example
+//! ```
+//! use cipher::{KeyIvInit, consts::{U12, U16}};
+//! use rc5::{RC5, rfc2040::CbcPad};
+//!
+//! let key = [0x42; 16];
+//! let iv = [0x24; 8];
+//! let mode = CbcPad::<RC5<u32, U12, U16>>::new(&key.into(), &iv.into());
+//!
+//! let msg = b"hello world";
+//! let mut buf = [0u8; 16];
+//! buf[..msg.len()].copy_from_slice(msg);
+//! let ct_len = mode.encrypt_padded(&mut buf, msg.len()).unwrap().len();
+//! assert_eq!(ct_len, 16);
+//! assert_eq!(mode.decrypt_padded(&mut buf).unwrap(), msg);
+//! ```
+//!
+//! [RFC 2040]: https://www.rfc-editor.org/rfc/rfc2040
+
+use cipher::{
+    Block, BlockCipherDecrypt, BlockCipherEncrypt, BlockSizeUser, InnerIvInit, Iv, IvSizeUser,
+    IvState, common::InnerUser, typenum::Unsigned,
+};
+use core::fmt;
+use subtle::{ConstantTimeEq, ConstantTimeGreater, ConstantTimeLess};
+
+/// RC5-CBC mode.
+///
+/// Unlike the other modes this one keeps the chaining value between calls,
+/// so a long message can be processed in several parts. Use [`Cbc::set_iv`]
+/// to start a new message.
+#[derive(Clone)]
+pub struct Cbc<C: BlockSizeUser> {
+    cipher: C,
+    iv: Block<C>,
+}
+
+impl<C: BlockSizeUser> Cbc<C> {
+    /// Replace the IV and reset the chaining state.
+    #[inline]
+    pub fn set_iv(&mut self, iv: &Iv<Self>) {
+        self.iv = iv.clone();
+    }
+}
+
+impl<C: BlockCipherEncrypt> Cbc<C> {
+    /// Encrypt `blocks` in place.
+    #[inline]
+    pub fn encrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        cbc_encrypt(&self.cipher, &mut self.iv, blocks.iter_mut());
+    }
+}
+
+impl<C: BlockCipherDecrypt> Cbc<C> {
+    /// Decrypt `blocks` in place.
+    #[inline]
+    pub fn decrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        cbc_decrypt(&self.cipher, &mut self.iv, blocks.iter_mut());
+    }
+}
+
+/// RC5-CBC-Pad mode.
+///
+/// Every call processes a complete message starting from the configured IV.
+#[derive(Clone)]
+pub struct CbcPad<C: BlockSizeUser> {
+    cipher: C,
+    iv: Block<C>,
+}
+
+impl<C: BlockSizeUser> CbcPad<C> {
+    /// Replace the IV.
+    #[inline]
+    pub fn set_iv(&mut self, iv: &Iv<Self>) {
+        self.iv = iv.clone();
+    }
+}
+
+impl<C: BlockCipherEncrypt> CbcPad<C> {
+    /// Pad and encrypt the first `msg_len` bytes of `buf` in place.
+    ///
+    /// Returns the ciphertext, which is always between 1 and `bb` bytes
+    /// longer than the message. Returns [`InvalidLengthError`] if `buf`
+    /// is too small to hold the padded message.
+    pub fn encrypt_padded<'a>(
+        &self,
+        buf: &'a mut [u8],
+        msg_len: usize,
+    ) -> Result<&'a [u8], InvalidLengthError> {
+        let bs = C::BlockSize::USIZE;
+        let pad_len = bs - msg_len % bs;
+        let ct_len = msg_len + pad_len;
+        if ct_len > buf.len() {
+            return Err(InvalidLengthError);
+        }
+
+        let buf = &mut buf[..ct_len];
+        // `pad_len` is at most 255 since `RC5<W, R, B>` blocks are at most 32 bytes long
+        buf[msg_len..].fill(pad_len as u8);
+
+        let mut iv = self.iv.clone();
+        cbc_encrypt(&self.cipher, &mut iv, as_blocks::<C>(buf));
+        Ok(buf)
+    }
+}
+
+impl<C: BlockCipherDecrypt> CbcPad<C> {
+    /// Decrypt `buf` in place and remove the padding.
+    ///
+    /// Returns the decrypted message. Returns [`PaddingError`] if the length
+    /// of `buf` is not a non-zero multiple of the block size or if the padding
+    /// is malformed.
+    pub fn decrypt_padded<'a>(&self, buf: &'a mut [u8]) -> Result<&'a [u8], PaddingError> {
+        let bs = C::BlockSize::USIZE;
+        if buf.is_empty() || buf.len() % bs != 0 {
+            return Err(PaddingError);
+        }
+
+        let mut iv = self.iv.clone();
+        cbc_decrypt(&self.cipher, &mut iv, as_blocks::<C>(buf));
+
+        // The padding is checked in constant time over the whole last block,
+        // so the time taken does not depend on the padding length or on the
+        // position of a malformed byte
+        let last = &buf[buf.len() - bs..];
+        let pad_len = last[bs - 1];
+        let mut valid = !pad_len.ct_eq(&0) & !pad_len.ct_gt(&(bs as u8));
+        for (i, &b) in last.iter().rev().enumerate() {
+            let in_pad = (i as u8).ct_lt(&pad_len);
+            valid &= !in_pad | b.ct_eq(&pad_len);
+        }
+        if !bool::from(valid) {
+            return Err(PaddingError);
+        }
+        let (msg, _) = buf.split_at(buf.len() - pad_len as usize);
+        Ok(msg)
+    }
+}
+
+/// Leading whole blocks, second-to-last block and last block of a message.
+type CtsParts<'a> = (&'a mut [u8], &'a mut [u8], &'a mut [u8]);
+
+/// RC5-CTS mode.
+///
+/// Every call processes a complete message starting from the configured IV.
+/// Messages must be at least one block long. As specified in the RFC, the
+/// last two ciphertext blocks are always swapped, including when the message
+/// length is a multiple of the block size.
+#[derive(Clone)]
+pub struct Cts<C: BlockSizeUser> {
+    cipher: C,
+    iv: Block<C>,
+}
+
+impl<C: BlockSizeUser> Cts<C> {
+    /// Replace the IV.
+    #[inline]
+    pub fn set_iv(&mut self, iv: &Iv<Self>) {
+        self.iv = iv.clone();
+    }
+
+    /// Split `buf` into the whole blocks which are processed using plain CBC,
+    /// the second-to-last block and the (possibly partial) last block.
+    fn split(buf: &mut [u8]) -> Result<CtsParts<'_>, InvalidLengthError> {
+        let bs = C::BlockSize::USIZE;
+        if buf.len() < bs {
+            return Err(InvalidLengthError);
+        }
+        if buf.len() == bs {
+            return Ok((buf, &mut [], &mut []));
+        }
+
+        let last_len = match buf.len() % bs {
+            0 => bs,
+            n => n,
+        };
+        let (head, tail) = buf.split_at_mut(buf.len() - last_len - bs);
+        let (prev, last) = tail.split_at_mut(bs);
+        Ok((head, prev, last))
+    }
+}
+
+impl<C: BlockCipherEncrypt> Cts<C> {
+    /// Encrypt `buf` in place.
+    ///
+    /// Returns [`InvalidLengthError`] if `buf` is shorter than one block.
+    pub fn encrypt(&self, buf: &mut [u8]) -> Result<(), InvalidLengthError> {
+        let (head, prev, last) = Self::split(buf)?;
+
+        let mut iv = self.iv.clone();
+        cbc_encrypt(&self.cipher, &mut iv, as_blocks::<C>(head));
+        if last.is_empty() {
+            return Ok(());
+        }
+
+        // E[n-1] = Encrypt(P[n-1] ^ C[n-2])
+        let mut e = Block::<C>::try_from(&*prev).unwrap();
+        xor(&mut e, &iv);
+        self.cipher.encrypt_block(&mut e);
+
+        // C[n-1] = Encrypt(E[n-1] ^ (P[n] || 0...))
+        prev.copy_from_slice(&e);
+        xor(prev, last);
+        self.cipher
+            .encrypt_block(<&mut Block<C>>::try_from(prev).unwrap());
+
+        // C[n] = first `Ln` bytes of E[n-1]
+        let n = last.len();
+        last.copy_from_slice(&e[..n]);
+        Ok(())
+    }
+}
+
+impl<C: BlockCipherDecrypt> Cts<C> {
+    /// Decrypt `buf` in place.
+    ///
+    /// Returns [`InvalidLengthError`] if `buf` is shorter than one block.
+    pub fn decrypt(&self, buf: &mut [u8]) -> Result<(), InvalidLengthError> {
+        let (head, prev, last) = Self::split(buf)?;
+
+        let mut iv = self.iv.clone();
+        cbc_decrypt(&self.cipher, &mut iv, as_blocks::<C>(head));
+        if last.is_empty() {
+            return Ok(());
+        }
+
+        // X[n] = Decrypt(C[n-1]) ^ (C[n] || 0...), i.e. P[n] || tail of E[n-1]
+        let mut x = Block::<C>::try_from(&*prev).unwrap();
+        self.cipher.decrypt_block(&mut x);
+        xor(&mut x, last);
+
+        // E[n-1] = C[n] || tail of X[n]
+        let n = last.len();
+        prev[..n].copy_from_slice(last);
+        prev[n..].copy_from_slice(&x[n..]);
+        last.copy_from_slice(&x[..n]);
+
+        // P[n-1] = Decrypt(E[n-1]) ^ C[n-2]
+        let prev = <&mut Block<C>>::try_from(prev).unwrap();
+        self.cipher.decrypt_block(prev);
+        xor(prev, &iv);
+        Ok(())
+    }
+}
+
+macro_rules! impl_mode_traits {
+    ($($name:ident),*) => {$(
+        impl<C: BlockSizeUser> InnerUser for $name<C> {
+            type Inner = C;
+        }
+
+        impl<C: BlockSizeUser> IvSizeUser for $name<C> {
+            type IvSize = C::BlockSize;
+        }
+
+        impl<C: BlockSizeUser> InnerIvInit for $name<C> {
+            #[inline]
+            fn inner_iv_init(cipher: C, iv: &Iv<Self>) -> Self {
+                Self {
+                    cipher,
+                    iv: iv.clone(),
+                }
+            }
+        }
+
+        impl<C: BlockSizeUser> fmt::Debug for $name<C> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(concat!(stringify!($name), " { ... }"))
+            }
+        }
+    )*};
+}
+
+impl_mode_traits!(Cbc, CbcPad, Cts);
+
+impl<C: BlockSizeUser> IvState for Cbc<C> {
+    #[inline]
+    fn iv_state(&self) -> Iv<Self> {
+        self.iv.clone()
+    }
+}
+
+/// Error returned when the data passed to a mode has an invalid length.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct InvalidLengthError;
+
+impl fmt::Display for InvalidLengthError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("InvalidLength")
+    }
+}
+
+impl core::error::Error for InvalidLengthError {}
+
+/// Error returned when RC5-CBC-Pad decryption finds malformed padding.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct PaddingError;
+
+impl fmt::Display for PaddingError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("Padding")
+    }
+}
+
+impl core::error::Error for PaddingError {}
+
+fn cbc_encrypt<'a, C: BlockCipherEncrypt>(
+    cipher: &C,
+    iv: &mut Block<C>,
+    blocks: impl Iterator<Item = &'a mut Block<C>>,
+) {
+    for block in blocks {
+        xor(block, iv);
+        cipher.encrypt_block(block);
+        *iv = block.clone();
+    }
+}
+
+fn cbc_decrypt<'a, C: BlockCipherDecrypt>(
+    cipher: &C,
+    iv: &mut Block<C>,
+    blocks: impl Iterator<Item = &'a mut Block<C>>,
+) {
+    for block in blocks {
+        let next_iv = block.clone();
+        cipher.decrypt_block(block);
+        xor(block, iv);
+        *iv = next_iv;
+    }
+}
+
+/// Iterate over blocks of `buf`.
+///
+/// # Panics
+/// If length of `buf` is not a multiple of the block size.
+fn as_blocks<C: BlockSizeUser>(buf: &mut [u8]) -> impl Iterator<Item = &mut Block<C>> {
+    assert_eq!(buf.len() % C::BlockSize::USIZE, 0);
+    buf.chunks_exact_mut(C::BlockSize::USIZE)
+        .map(|block| block.try_into().unwrap())
+}
+
+/// XOR `src` into the beginning of `dst`.
+fn xor(dst: &mut [u8], src: &[u8]) {
+    for (a, b) in dst.iter_mut().zip(src) {
+        *a ^= b;
+    }
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "alloc")]
+
+use cipher::consts::*;
+use cipher::{Array, BlockCipherDecrypt, BlockCipherEncrypt, KeyInit};
+use hex_literal::hex;
+use rc5::{DynRc5, InvalidParametersError, RC5};
+
+macro_rules! impl_dyn_test {
+    ($name:ident, $word:ty, $w:expr, $rounds:ty, $key_size:ty) => {
+        #[test]
+        fn $name() {
+            let key: [u8; <$key_size>::USIZE] = core::array::from_fn(|i| (7 * i + 3) as u8);
+            let static_cipher =
+                <RC5<$word, $rounds, $key_size> as KeyInit>::new_from_slice(&key).unwrap();
+            let dyn_cipher = DynRc5::new($w, <$rounds>::USIZE, &key).unwrap();
+            assert_eq!(dyn_cipher.block_size(), 2 * $w / 8);
+
+            let mut blocks = [0u8; 4 * 2 * $w / 8];
+            for (i, b) in blocks.iter_mut().enumerate() {
+                *b = (i * 13) as u8;
+            }
+            let pt = blocks;
+
+            dyn_cipher.encrypt_blocks(&mut blocks).unwrap();
+            for (ct, pt) in blocks.chunks(2 * $w / 8).zip(pt.chunks(2 * $w / 8)) {
+                let mut block = Array::try_from(pt).unwrap();
+                static_cipher.encrypt_block(&mut block);
+                assert_eq!(ct, &block[..]);
+                static_cipher.decrypt_block(&mut block);
+                assert_eq!(pt, &block[..]);
+            }
+
+            dyn_cipher.decrypt_blocks(&mut blocks).unwrap();
+            assert_eq!(blocks, pt);
+        }
+    };
+}
+
+use cipher::typenum::Unsigned;
+
+impl_dyn_test!(match_static_8_12_4, u8, 8, U12, U4);
+impl_dyn_test!(match_static_16_16_8, u16, 16, U16, U8);
+impl_dyn_test!(match_static_32_0_16, u32, 32, U0, U16);
+impl_dyn_test!(match_static_32_20_255, u32, 32, U20, U255);
+impl_dyn_test!(match_static_64_24_24, u64, 64, U24, U24);
+impl_dyn_test!(match_static_128_255_1, u128, 128, U255, U1);
+impl_dyn_test!(match_static_8_255_255, u8, 8, U255, U255);
+
+#[test]
+fn rc5_32_12_16_vector() {
+    let key = hex!("000102030405060708090A0B0C0D0E0F");
+    let mut block = hex!("0001020304050607");
+
+    let cipher = DynRc5::new(32, 12, &key).unwrap();
+    cipher.encrypt_block(&mut block).unwrap();
+    assert_eq!(block, hex!("C8D3B3C486700CFA"));
+    cipher.decrypt_block(&mut block).unwrap();
+    assert_eq!(block, hex!("0001020304050607"));
+}
+
+#[test]
+fn empty_key() {
+    // An empty key expands to a single zero word
+    let mut block = hex!("0001020304050607");
+    let mut expected = block;
+    DynRc5::new(32, 12, &[])
+        .unwrap()
+        .encrypt_block(&mut block)
+        .unwrap();
+    DynRc5::new(32, 12, &[0; 4])
+        .unwrap()
+        .encrypt_block(&mut expected)
+        .unwrap();
+    assert_eq!(block, expected);
+}
+
+#[test]
+fn invalid_parameters() {
+    for w in [0, 4, 24, 256] {
+        assert_eq!(
+            DynRc5::new(w, 12, &[0; 16]).unwrap_err(),
+            InvalidParametersError
+        );
+    }
+    assert!(DynRc5::new(32, 256, &[0; 16]).is_err());
+    assert!(DynRc5::new(32, 12, &[0; 256]).is_err());
+}
+
+#[test]
+fn invalid_block_length() {
+    let cipher = DynRc5::new(64, 12, &[0; 16]).unwrap();
+    assert!(cipher.encrypt_block(&mut [0; 8]).is_err());
+    assert!(cipher.decrypt_block(&mut [0; 17]).is_err());
+    assert!(cipher.encrypt_blocks(&mut []).is_err());
+    assert!(cipher.encrypt_block(&mut [0; 32]).is_err());
+    assert!(cipher.encrypt_blocks(&mut [0; 32]).is_ok());
+}
+
+#[test]
+fn accessors_and_debug() {
+    let cipher = DynRc5::new(16, 20, &[1; 10]).unwrap();
+    assert_eq!(cipher.word_size(), 16);
+    assert_eq!(cipher.rounds(), 20);
+    assert_eq!(cipher.key_len(), 10);
+    assert_eq!(format!("{cipher:?}"), "DynRc5 - 16/20/10 { ... }");
+}
@@ -0,0 +1,381 @@
+//! Test vectors from section 9 of RFC 2040:
+//! https://www.rfc-editor.org/rfc/rfc2040#section-9
+use cipher::consts::*;
+use cipher::{Array, KeyIvInit};
+use hex_literal::hex;
+use rc5::RC5;
+use rc5::rfc2040::{Cbc, CbcPad, Cts, InvalidLengthError, PaddingError};
+
+macro_rules! cbc_test {
+    ($name:ident, $rounds:ty, $key_size:ty, $key:expr, $iv:expr, $pt:expr, $ct:expr) => {
+        #[test]
+        fn $name() {
+            let key = hex!($key);
+            let iv = hex!($iv);
+            let pt = hex!($pt);
+            let ct = hex!($ct);
+
+            let mut mode = Cbc::<RC5<u32, $rounds, $key_size>>::new_from_slices(&key, &iv).unwrap();
+            let mut blocks = [Array::from(pt)];
+            mode.encrypt_blocks(&mut blocks);
+            assert_eq!(blocks[0], ct);
+
+            mode.set_iv(&iv.into());
+            mode.decrypt_blocks(&mut blocks);
+            assert_eq!(blocks[0], pt);
+        }
+    };
+}
+
+cbc_test!(
+    cbc_1,
+    U0,
+    U1,
+    "00",
+    "0000000000000000",
+    "0000000000000000",
+    "7a7bba4d79111d1e"
+);
+cbc_test!(
+    cbc_2,
+    U0,
+    U1,
+    "00",
+    "0000000000000000",
+    "ffffffffffffffff",
+    "797bba4d78111d1e"
+);
+cbc_test!(
+    cbc_3,
+    U0,
+    U1,
+    "00",
+    "0000000000000001",
+    "0000000000000000",
+    "7a7bba4d79111d1f"
+);
+cbc_test!(
+    cbc_4,
+    U0,
+    U1,
+    "00",
+    "0000000000000000",
+    "0000000000000001",
+    "7a7bba4d79111d1f"
+);
+cbc_test!(
+    cbc_5,
+    U0,
+    U1,
+    "00",
+    "0102030405060708",
+    "1020304050607080",
+    "8b9ded91ce7794a6"
+);
+cbc_test!(
+    cbc_6,
+    U1,
+    U1,
+    "11",
+    "0000000000000000",
+    "0000000000000000",
+    "2f759fe7ad86a378"
+);
+cbc_test!(
+    cbc_7,
+    U2,
+    U1,
+    "00",
+    "0000000000000000",
+    "0000000000000000",
+    "dca2694bf40e0788"
+);
+cbc_test!(
+    cbc_8,
+    U2,
+    U4,
+    "00000000",
+    "0000000000000000",
+    "0000000000000000",
+    "dca2694bf40e0788"
+);
+cbc_test!(
+    cbc_9,
+    U8,
+    U1,
+    "00",
+    "0000000000000000",
+    "0000000000000000",
+    "dcfe098577eca5ff"
+);
+cbc_test!(
+    cbc_10,
+    U8,
+    U1,
+    "00",
+    "0102030405060708",
+    "1020304050607080",
+    "9646fb77638f9ca8"
+);
+cbc_test!(
+    cbc_11,
+    U12,
+    U1,
+    "00",
+    "0102030405060708",
+    "1020304050607080",
+    "b2b3209db6594da4"
+);
+cbc_test!(
+    cbc_12,
+    U16,
+    U1,
+    "00",
+    "0102030405060708",
+    "1020304050607080",
+    "545f7f32a5fc3836"
+);
+cbc_test!(
+    cbc_13,
+    U8,
+    U4,
+    "01020304",
+    "0000000000000000",
+    "ffffffffffffffff",
+    "8285e7c1b5bc7402"
+);
+cbc_test!(
+    cbc_14,
+    U12,
+    U4,
+    "01020304",
+    "0000000000000000",
+    "ffffffffffffffff",
+    "fc586f92f7080934"
+);
+cbc_test!(
+    cbc_15,
+    U16,
+    U4,
+    "01020304",
+    "0000000000000000",
+    "ffffffffffffffff",
+    "cf270ef9717ff7c4"
+);
+cbc_test!(
+    cbc_16,
+    U12,
+    U8,
+    "0102030405060708",
+    "0000000000000000",
+    "ffffffffffffffff",
+    "e493f1c1bb4d6e8c"
+);
+cbc_test!(
+    cbc_17,
+    U8,
+    U8,
+    "0102030405060708",
+    "0102030405060708",
+    "1020304050607080",
+    "5c4c041e0f217ac3"
+);
+cbc_test!(
+    cbc_18,
+    U12,
+    U8,
+    "0102030405060708",
+    "0102030405060708",
+    "1020304050607080",
+    "921f12485373b4f7"
+);
+cbc_test!(
+    cbc_19,
+    U16,
+    U8,
+    "0102030405060708",
+    "0102030405060708",
+    "1020304050607080",
+    "5ba0ca6bbe7f5fad"
+);
+cbc_test!(
+    cbc_20,
+    U8,
+    U16,
+    "01020304050607081020304050607080",
+    "0102030405060708",
+    "1020304050607080",
+    "c533771cd0110e63"
+);
+cbc_test!(
+    cbc_21,
+    U12,
+    U16,
+    "01020304050607081020304050607080",
+    "0102030405060708",
+    "1020304050607080",
+    "294ddb46b3278d60"
+);
+cbc_test!(
+    cbc_22,
+    U16,
+    U16,
+    "01020304050607081020304050607080",
+    "0102030405060708",
+    "1020304050607080",
+    "dad6bda9dfe8f7e8"
+);
+cbc_test!(
+    cbc_23,
+    U12,
+    U5,
+    "0102030405",
+    "0000000000000000",
+    "ffffffffffffffff",
+    "97e0787837ed317f"
+);
+cbc_test!(
+    cbc_24,
+    U8,
+    U5,
+    "0102030405",
+    "0000000000000000",
+    "ffffffffffffffff",
+    "7875dbf6738c6478"
+);
+cbc_test!(
+    cbc_25,
+    U8,
+    U5,
+    "0102030405",
+    "7875dbf6738c6478",
+    "0808080808080808",
+    "8f34c3c681c99695"
+);
+
+type Rc5_32_8_5 = RC5<u32, U8, U5>;
+
+#[test]
+fn cbc_pad() {
+    let key = hex!("0102030405");
+    let iv = hex!("0000000000000000");
+    let mode = CbcPad::<Rc5_32_8_5>::new_from_slices(&key, &iv).unwrap();
+
+    let pt = hex!("ffffffffffffffff");
+    let ct = hex!("7875dbf6738c6478 8f34c3c681c99695");
+    let mut buf = [0u8; 16];
+    buf[..pt.len()].copy_from_slice(&pt);
+    assert_eq!(mode.encrypt_padded(&mut buf, pt.len()).unwrap(), ct);
+    assert_eq!(mode.decrypt_padded(&mut buf).unwrap(), pt);
+
+    let pt = hex!("ffffffffffffffff 7875dbf6738c6478 11223344556677");
+    let ct = hex!("7875dbf6738c6478 7cb3f1df34f94811 7fd1a023a5bba217");
+    let mut buf = [0u8; 24];
+    buf[..pt.len()].copy_from_slice(&pt);
+    assert_eq!(mode.encrypt_padded(&mut buf, pt.len()).unwrap(), ct);
+    assert_eq!(mode.decrypt_padded(&mut buf).unwrap(), pt);
+
+    let mut buf = [0u8; 7];
+    assert_eq!(mode.encrypt_padded(&mut buf, 7), Err(InvalidLengthError));
+    let mut buf = [0u8; 12];
+    assert_eq!(mode.decrypt_padded(&mut buf), Err(PaddingError));
+}
+
+#[test]
+fn cbc_pad_invalid_padding() {
+    let key = hex!("0102030405");
+    let iv = hex!("0000000000000000");
+    let mode = CbcPad::<Rc5_32_8_5>::new_from_slices(&key, &iv).unwrap();
+
+    // decrypts to `ffffffffffffffff` which is not a valid padding
+    let mut buf = hex!("7875dbf6738c6478");
+    assert_eq!(mode.decrypt_padded(&mut buf), Err(PaddingError));
+
+    // Every malformed last block is rejected with the same error: padding
+    // lengths of zero or longer than the block, and a wrong byte at each
+    // position of padding of every valid length
+    let mut bad = vec![[0, 0, 0, 0, 0, 0, 0, 0], [9; 8], [0xff; 8]];
+    for pad_len in 2..=8 {
+        for pos in 8 - pad_len..7 {
+            let mut block = [pad_len as u8; 8];
+            block[pos] ^= 0x10;
+            bad.push(block);
+        }
+    }
+    for block in bad {
+        let mut blocks = [Array::from(block)];
+        Cbc::<Rc5_32_8_5>::new_from_slices(&key, &iv)
+            .unwrap()
+            .encrypt_blocks(&mut blocks);
+        let mut buf = blocks[0].0;
+        assert_eq!(mode.decrypt_padded(&mut buf), Err(PaddingError));
+    }
+}
+
+#[test]
+fn cts() {
+    let key = hex!("0102030405");
+    let iv = hex!("0000000000000000");
+    let mut cbc = Cbc::<Rc5_32_8_5>::new_from_slices(&key, &iv).unwrap();
+    let cts = Cts::<Rc5_32_8_5>::new_from_slices(&key, &iv).unwrap();
+
+    // CTS output is CBC output of the zero-padded message with the last two
+    // blocks swapped and the last one truncated to the length of the message
+    let pt = hex!("ffffffffffffffff 7875dbf6738c6478 11223344556677");
+    let mut blocks = [
+        Array::from(hex!("ffffffffffffffff")),
+        Array::from(hex!("7875dbf6738c6478")),
+        Array::from(hex!("1122334455667700")),
+    ];
+    cbc.encrypt_blocks(&mut blocks);
+
+    let mut buf = pt;
+    cts.encrypt(&mut buf).unwrap();
+    assert_eq!(buf[..8], blocks[0][..]);
+    assert_eq!(buf[8..16], blocks[2][..]);
+    assert_eq!(buf[16..], blocks[1][..7]);
+    cts.decrypt(&mut buf).unwrap();
+    assert_eq!(buf, pt);
+
+    for len in 8..40 {
+        let pt: [u8; 40] = core::array::from_fn(|i| i as u8);
+        let mut buf = pt;
+        cts.encrypt(&mut buf[..len]).unwrap();
+        assert_ne!(buf[..len], pt[..len]);
+        cts.decrypt(&mut buf[..len]).unwrap();
+        assert_eq!(buf, pt);
+    }
+
+    assert_eq!(cts.encrypt(&mut [0u8; 7]), Err(InvalidLengthError));
+    assert_eq!(cts.decrypt(&mut [0u8; 7]), Err(InvalidLengthError));
+}
+
+macro_rules! roundtrip_test {
+    ($name:ident, $word:ty) => {
+        #[test]
+        fn $name() {
+            type C = RC5<$word, U12, U16>;
+            let key = [0x42; 16];
+            let iv = Array::from_fn(|i| i as u8);
+
+            let mode = CbcPad::<C>::new(&key.into(), &iv);
+            let pt = b"The quick brown fox jumps over the lazy dog";
+            let mut buf = [0u8; 64];
+            buf[..pt.len()].copy_from_slice(pt);
+            let ct_len = mode.encrypt_padded(&mut buf, pt.len()).unwrap().len();
+            assert_eq!(mode.decrypt_padded(&mut buf[..ct_len]).unwrap(), pt);
+
+            let mode = Cts::<C>::new(&key.into(), &iv);
+            let mut buf = *pt;
+            mode.encrypt(&mut buf).unwrap();
+            mode.decrypt(&mut buf).unwrap();
+            assert_eq!(&buf, pt);
+        }
+    };
+}
+
+roundtrip_test!(roundtrip_8, u8);
+roundtrip_test!(roundtrip_16, u16);
+roundtrip_test!(roundtrip_32, u32);
+roundtrip_test!(roundtrip_64, u64);
+roundtrip_test!(roundtrip_128, u128);
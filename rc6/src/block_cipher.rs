@@ -1,14 +1,17 @@
 use core::ops::{Add, Div, Mul, Sub};
 
 use cipher::{
-    AlgorithmName, Block, BlockSizeUser, KeyInit, KeySizeUser, ParBlocksSizeUser,
+    AlgorithmName, Block, BlockSizeUser, KeyInit, KeySizeUser, ParBlocks, ParBlocksSizeUser,
     array::ArraySize,
     block::{
         BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherDecrypt, BlockCipherEncBackend,
         BlockCipherEncClosure, BlockCipherEncrypt,
     },
     inout::InOut,
-    typenum::{Diff, IsLess, Le, NonZero, Sum, U1, U2, U4, U8, U12, U16, U20, U24, U256, Unsigned},
+    typenum::{
+        Diff, IsLess, Le, NonZero, Sum, U1, U2, U4, U8, U12, U16, U20, U24, U28, U32, U256,
+        Unsigned,
+    },
 };
 
 use crate::core::{BlockSize, ExpandedKeyTableSize, KeyAsWordsSize, RC6, Word};
@@ -158,7 +161,7 @@ where
     Sum<R, U2>: Mul<U2>,
     ExpandedKeyTableSize<R>: ArraySize,
 {
-    type ParBlocksSize = U1;
+    type ParBlocksSize = W::ParBlocksSize;
 }
 
 impl<W, R, B> BlockCipherEncBackend for RC6EncryptBackend<'_, W, R, B>
@@ -190,6 +193,12 @@ where
         let backend = self.enc_dec;
         backend.encrypt(block)
     }
+
+    #[inline(always)]
+    fn encrypt_par_blocks(&self, blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let backend = self.enc_dec;
+        backend.encrypt_par(blocks)
+    }
 }
 
 impl<W, R, B> BlockCipherDecrypt for RC6<W, R, B>
@@ -271,7 +280,7 @@ where
     Sum<R, U2>: Mul<U2>,
     ExpandedKeyTableSize<R>: ArraySize,
 {
-    type ParBlocksSize = U1;
+    type ParBlocksSize = W::ParBlocksSize;
 }
 
 impl<W, R, B> BlockCipherDecBackend for RC6DecryptBackend<'_, W, R, B>
@@ -303,6 +312,12 @@ where
         let backend = self.enc_dec;
         backend.decrypt(block)
     }
+
+    #[inline(always)]
+    fn decrypt_par_blocks(&self, blocks: InOut<'_, '_, ParBlocks<Self>>) {
+        let backend = self.enc_dec;
+        backend.decrypt_par(blocks)
+    }
 }
 
 impl<W, R, B> AlgorithmName for RC6<W, R, B>
@@ -335,3 +350,4 @@ pub type RC6_8_12_4 = RC6<u8, U12, U4>;
 pub type RC6_16_16_8 = RC6<u16, U16, U8>;
 pub type RC6_32_20_16 = RC6<u32, U20, U16>;
 pub type RC6_64_24_24 = RC6<u64, U24, U24>;
+pub type RC6_128_28_32 = RC6<u128, U28, U32>;
@@ -11,7 +11,8 @@ use cipher::{
 };
 
 use super::{
-    Block, BlockSize, ExpandedKeyTable, ExpandedKeyTableSize, Key, KeyAsWords, KeyAsWordsSize, Word,
+    Block, BlockSize, ExpandedKeyTable, ExpandedKeyTableSize, Key, KeyAsWords, KeyAsWordsSize,
+    ParBlocks, Word,
 };
 
 pub struct RC6<W, R, B>
@@ -137,66 +138,36 @@ where
     ExpandedKeyTableSize<R>: ArraySize,
 {
     pub fn encrypt(&self, mut block: InOut<'_, '_, Block<W>>) {
-        let (mut a, mut b, mut c, mut d) = Self::words_from_block(block.get_in());
-        let key = &self.key_table;
-        let log_w = W::from((usize::BITS - 1 - (W::Bytes::USIZE * 8).leading_zeros()) as u8);
-
-        b = b.wrapping_add(key[0]);
-        d = d.wrapping_add(key[1]);
-
-        for i in 1..=R::USIZE {
-            let t = b
-                .wrapping_mul(b.wrapping_mul(W::from(2)).wrapping_add(W::from(1)))
-                .rotate_left(log_w);
-            let u = d
-                .wrapping_mul(d.wrapping_mul(W::from(2)).wrapping_add(W::from(1)))
-                .rotate_left(log_w);
-            a = a.bitxor(t).rotate_left(u).wrapping_add(key[2 * i]);
-            c = c.bitxor(u).rotate_left(t).wrapping_add(key[2 * i + 1]);
-            let tmp = a;
-            a = b;
-            b = c;
-            c = d;
-            d = tmp;
-        }
-
-        a = a.wrapping_add(key[2 * R::USIZE + 2]);
-        c = c.wrapping_add(key[2 * R::USIZE + 3]);
-
+        let words = Self::words_from_block(block.get_in());
+        let [a, b, c, d] = encrypt_words(words, &self.key_table);
         Self::block_from_words(a, b, c, d, block.get_out())
     }
 
     pub fn decrypt(&self, mut block: InOut<'_, '_, Block<W>>) {
-        let (mut a, mut b, mut c, mut d) = Self::words_from_block(block.get_in());
-        let key = &self.key_table;
-        let log_w = W::from((usize::BITS - 1 - (W::Bytes::USIZE * 8).leading_zeros()) as u8);
-
-        c = c.wrapping_sub(key[2 * R::USIZE + 3]);
-        a = a.wrapping_sub(key[2 * R::USIZE + 2]);
-
-        for i in (1..=R::USIZE).rev() {
-            let tmp = d;
-            d = c;
-            c = b;
-            b = a;
-            a = tmp;
-            let u = d
-                .wrapping_mul(d.wrapping_mul(W::from(2)).wrapping_add(W::from(1)))
-                .rotate_left(log_w);
-            let t = b
-                .wrapping_mul(b.wrapping_mul(W::from(2)).wrapping_add(W::from(1)))
-                .rotate_left(log_w);
-            c = c.wrapping_sub(key[2 * i + 1]).rotate_right(t).bitxor(u);
-            a = a.wrapping_sub(key[2 * i]).rotate_right(u).bitxor(t);
-        }
+        let words = Self::words_from_block(block.get_in());
+        let [a, b, c, d] = decrypt_words(words, &self.key_table);
+        Self::block_from_words(a, b, c, d, block.get_out())
+    }
 
-        d = d.wrapping_sub(key[1]);
-        b = b.wrapping_sub(key[0]);
+    pub fn encrypt_par(&self, mut blocks: InOut<'_, '_, ParBlocks<W>>) {
+        let mut words: Array<[W; 4], W::ParBlocksSize> =
+            Array::from_fn(|i| Self::words_from_block(&blocks.get_in()[i]));
+        W::encrypt_par(&self.key_table, &mut words);
+        for ([a, b, c, d], block) in words.into_iter().zip(blocks.get_out().iter_mut()) {
+            Self::block_from_words(a, b, c, d, block)
+        }
+    }
 
-        Self::block_from_words(a, b, c, d, block.get_out())
+    pub fn decrypt_par(&self, mut blocks: InOut<'_, '_, ParBlocks<W>>) {
+        let mut words: Array<[W; 4], W::ParBlocksSize> =
+            Array::from_fn(|i| Self::words_from_block(&blocks.get_in()[i]));
+        W::decrypt_par(&self.key_table, &mut words);
+        for ([a, b, c, d], block) in words.into_iter().zip(blocks.get_out().iter_mut()) {
+            Self::block_from_words(a, b, c, d, block)
+        }
     }
 
-    fn words_from_block(block: &Block<W>) -> (W, W, W, W) {
+    fn words_from_block(block: &Block<W>) -> [W; 4] {
         // Block size is 4 * word::BYTES so the unwrap is safe
         let a = W::from_le_bytes(block[..W::Bytes::USIZE].try_into().unwrap());
         let b = W::from_le_bytes(
@@ -215,7 +186,7 @@ where
                 .unwrap(),
         );
 
-        (a, b, c, d)
+        [a, b, c, d]
     }
 
     fn block_from_words(a: W, b: W, c: W, d: W, out_block: &mut Block<W>) {
@@ -229,3 +200,65 @@ where
         r_h.copy_from_slice(&d.to_le_bytes());
     }
 }
+
+/// Encrypt a single block of words using `key` with `key.len() / 2 - 2` rounds.
+#[inline(always)]
+pub(crate) fn encrypt_words<W: Word>([mut a, mut b, mut c, mut d]: [W; 4], key: &[W]) -> [W; 4] {
+    let rounds = key.len() / 2 - 2;
+    let log_w = W::from((usize::BITS - 1 - (W::Bytes::USIZE * 8).leading_zeros()) as u8);
+
+    b = b.wrapping_add(key[0]);
+    d = d.wrapping_add(key[1]);
+
+    for i in 1..=rounds {
+        let t = b
+            .wrapping_mul(b.wrapping_mul(W::from(2)).wrapping_add(W::from(1)))
+            .rotate_left(log_w);
+        let u = d
+            .wrapping_mul(d.wrapping_mul(W::from(2)).wrapping_add(W::from(1)))
+            .rotate_left(log_w);
+        a = a.bitxor(t).rotate_left(u).wrapping_add(key[2 * i]);
+        c = c.bitxor(u).rotate_left(t).wrapping_add(key[2 * i + 1]);
+        let tmp = a;
+        a = b;
+        b = c;
+        c = d;
+        d = tmp;
+    }
+
+    a = a.wrapping_add(key[2 * rounds + 2]);
+    c = c.wrapping_add(key[2 * rounds + 3]);
+
+    [a, b, c, d]
+}
+
+/// Decrypt a single block of words using `key` with `key.len() / 2 - 2` rounds.
+#[inline(always)]
+pub(crate) fn decrypt_words<W: Word>([mut a, mut b, mut c, mut d]: [W; 4], key: &[W]) -> [W; 4] {
+    let rounds = key.len() / 2 - 2;
+    let log_w = W::from((usize::BITS - 1 - (W::Bytes::USIZE * 8).leading_zeros()) as u8);
+
+    c = c.wrapping_sub(key[2 * rounds + 3]);
+    a = a.wrapping_sub(key[2 * rounds + 2]);
+
+    for i in (1..=rounds).rev() {
+        let tmp = d;
+        d = c;
+        c = b;
+        b = a;
+        a = tmp;
+        let u = d
+            .wrapping_mul(d.wrapping_mul(W::from(2)).wrapping_add(W::from(1)))
+            .rotate_left(log_w);
+        let t = b
+            .wrapping_mul(b.wrapping_mul(W::from(2)).wrapping_add(W::from(1)))
+            .rotate_left(log_w);
+        c = c.wrapping_sub(key[2 * i + 1]).rotate_right(t).bitxor(u);
+        a = a.wrapping_sub(key[2 * i]).rotate_right(u).bitxor(t);
+    }
+
+    d = d.wrapping_sub(key[1]);
+    b = b.wrapping_sub(key[0]);
+
+    [a, b, c, d]
+}
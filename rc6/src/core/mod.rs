@@ -2,6 +2,7 @@
 //! [RC6 paper]: https://www.grc.com/r&d/rc6.pdf
 
 mod backend;
+mod par;
 mod primitives;
 
 pub use backend::RC6;
@@ -0,0 +1,246 @@
+//! Multi-block RC6-32 backends.
+//!
+//! Blocks are processed in a word-sliced layout: lane `k` of vector `i` holds
+//! word `i` of block `k`, so every round operation works on all [`PAR`] blocks
+//! at once. The data-dependent rotations become per-lane variable rotations.
+#![allow(unsafe_code)]
+
+#[cfg(all(
+    target_arch = "aarch64",
+    target_feature = "neon",
+    not(rc6_backend = "soft")
+))]
+mod neon;
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    not(rc6_backend = "soft")
+))]
+mod x86;
+
+/// Number of blocks processed in parallel.
+pub(crate) const PAR: usize = 4;
+
+/// Vector of [`PAR`] 32-bit lanes.
+///
+/// The methods of SIMD implementations are compiled with the target features
+/// of their intrinsics, so callers must ensure that the CPU supports them.
+pub(crate) trait Lanes: Copy {
+    unsafe fn load(w: &[u32; PAR]) -> Self;
+    unsafe fn store(self, w: &mut [u32; PAR]);
+    unsafe fn splat(v: u32) -> Self;
+    unsafe fn add(self, rhs: Self) -> Self;
+    unsafe fn sub(self, rhs: Self) -> Self;
+    unsafe fn xor(self, rhs: Self) -> Self;
+    unsafe fn mul(self, rhs: Self) -> Self;
+    /// Rotate every lane left by `lg w = 5` bits.
+    unsafe fn rotl_lg_w(self) -> Self;
+    /// Rotate every lane left by the low 5 bits of the matching lane of `r`.
+    unsafe fn rotl(self, r: Self) -> Self;
+
+    /// Rotate every lane right by the low 5 bits of the matching lane of `r`.
+    #[inline(always)]
+    unsafe fn rotr(self, r: Self) -> Self {
+        unsafe { self.rotl(Self::splat(0).sub(r)) }
+    }
+}
+
+impl Lanes for [u32; PAR] {
+    #[inline(always)]
+    unsafe fn load(w: &[u32; PAR]) -> Self {
+        *w
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, w: &mut [u32; PAR]) {
+        *w = self;
+    }
+
+    #[inline(always)]
+    unsafe fn splat(v: u32) -> Self {
+        [v; PAR]
+    }
+
+    #[inline(always)]
+    unsafe fn add(self, rhs: Self) -> Self {
+        core::array::from_fn(|k| self[k].wrapping_add(rhs[k]))
+    }
+
+    #[inline(always)]
+    unsafe fn sub(self, rhs: Self) -> Self {
+        core::array::from_fn(|k| self[k].wrapping_sub(rhs[k]))
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        core::array::from_fn(|k| self[k] ^ rhs[k])
+    }
+
+    #[inline(always)]
+    unsafe fn mul(self, rhs: Self) -> Self {
+        core::array::from_fn(|k| self[k].wrapping_mul(rhs[k]))
+    }
+
+    #[inline(always)]
+    unsafe fn rotl_lg_w(self) -> Self {
+        self.map(|v| v.rotate_left(5))
+    }
+
+    #[inline(always)]
+    unsafe fn rotl(self, r: Self) -> Self {
+        core::array::from_fn(|k| self[k].rotate_left(r[k]))
+    }
+}
+
+/// Transpose `blocks` into the word-sliced layout.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+unsafe fn load<V: Lanes>(blocks: &[[u32; 4]; PAR]) -> [V; 4] {
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        let mut x = [V::splat(0); 4];
+        for (i, v) in x.iter_mut().enumerate() {
+            let mut w = [0u32; PAR];
+            for (w, block) in w.iter_mut().zip(blocks) {
+                *w = block[i];
+            }
+            *v = V::load(&w);
+        }
+        x
+    }
+}
+
+/// Transpose the word-sliced state `x` back into `blocks`.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+unsafe fn store<V: Lanes>(x: &[V; 4], blocks: &mut [[u32; 4]; PAR]) {
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        for (i, v) in x.iter().enumerate() {
+            let mut w = [0u32; PAR];
+            v.store(&mut w);
+            for (block, w) in blocks.iter_mut().zip(w) {
+                block[i] = w;
+            }
+        }
+    }
+}
+
+/// Compute `(x * (2x + 1)) <<< lg w` for every lane.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+unsafe fn f<V: Lanes>(x: V) -> V {
+    // SAFETY: guaranteed by the caller
+    unsafe { x.mul(x.add(x).add(V::splat(1))).rotl_lg_w() }
+}
+
+/// Encrypt [`PAR`] blocks using `key` with `key.len() / 2 - 2` rounds.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+pub(crate) unsafe fn encrypt<V: Lanes>(key: &[u32], blocks: &mut [[u32; 4]; PAR]) {
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        let rounds = key.len() / 2 - 2;
+        let [mut a, mut b, mut c, mut d] = load::<V>(blocks);
+
+        b = b.add(V::splat(key[0]));
+        d = d.add(V::splat(key[1]));
+
+        for i in 1..=rounds {
+            let t = f(b);
+            let u = f(d);
+            a = a.xor(t).rotl(u).add(V::splat(key[2 * i]));
+            c = c.xor(u).rotl(t).add(V::splat(key[2 * i + 1]));
+            (a, b, c, d) = (b, c, d, a);
+        }
+
+        a = a.add(V::splat(key[2 * rounds + 2]));
+        c = c.add(V::splat(key[2 * rounds + 3]));
+
+        store(&[a, b, c, d], blocks);
+    }
+}
+
+/// Decrypt [`PAR`] blocks using `key` with `key.len() / 2 - 2` rounds.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+pub(crate) unsafe fn decrypt<V: Lanes>(key: &[u32], blocks: &mut [[u32; 4]; PAR]) {
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        let rounds = key.len() / 2 - 2;
+        let [mut a, mut b, mut c, mut d] = load::<V>(blocks);
+
+        c = c.sub(V::splat(key[2 * rounds + 3]));
+        a = a.sub(V::splat(key[2 * rounds + 2]));
+
+        for i in (1..=rounds).rev() {
+            (a, b, c, d) = (d, a, b, c);
+            let u = f(d);
+            let t = f(b);
+            c = c.sub(V::splat(key[2 * i + 1])).rotr(t).xor(u);
+            a = a.sub(V::splat(key[2 * i])).rotr(u).xor(t);
+        }
+
+        d = d.sub(V::splat(key[1]));
+        b = b.sub(V::splat(key[0]));
+
+        store(&[a, b, c, d], blocks);
+    }
+}
+
+/// Encrypt [`PAR`] blocks using the fastest backend available on the current CPU.
+#[inline(always)]
+pub(crate) fn encrypt_par(key: &[u32], blocks: &mut [[u32; 4]; PAR]) {
+    cfg_if::cfg_if! {
+        if #[cfg(all(
+            target_arch = "aarch64",
+            target_feature = "neon",
+            not(rc6_backend = "soft")
+        ))] {
+            neon::encrypt_par(key, blocks)
+        } else {
+            #[cfg(all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                not(rc6_backend = "soft")
+            ))]
+            if let Some(token) = x86::Token::get() {
+                return x86::encrypt_par(token, key, blocks);
+            }
+            // SAFETY: the portable lanes use no target features
+            unsafe { encrypt::<[u32; PAR]>(key, blocks) }
+        }
+    }
+}
+
+/// Decrypt [`PAR`] blocks using the fastest backend available on the current CPU.
+#[inline(always)]
+pub(crate) fn decrypt_par(key: &[u32], blocks: &mut [[u32; 4]; PAR]) {
+    cfg_if::cfg_if! {
+        if #[cfg(all(
+            target_arch = "aarch64",
+            target_feature = "neon",
+            not(rc6_backend = "soft")
+        ))] {
+            neon::decrypt_par(key, blocks)
+        } else {
+            #[cfg(all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                not(rc6_backend = "soft")
+            ))]
+            if let Some(token) = x86::Token::get() {
+                return x86::decrypt_par(token, key, blocks);
+            }
+            // SAFETY: the portable lanes use no target features
+            unsafe { decrypt::<[u32; PAR]>(key, blocks) }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! NEON backend which processes four blocks using 128-bit vectors.
+//!
+//! This module is only built with `target_feature = "neon"` enabled for the
+//! crate, so the methods of `NeonLanes` can be called anywhere.
+#![allow(unsafe_code, unsafe_op_in_unsafe_fn)]
+
+use core::arch::aarch64::*;
+
+use super::{Lanes, PAR};
+
+/// Four 32-bit lanes.
+#[derive(Clone, Copy)]
+struct NeonLanes(uint32x4_t);
+
+impl Lanes for NeonLanes {
+    #[inline(always)]
+    unsafe fn load(w: &[u32; PAR]) -> Self {
+        Self(vld1q_u32(w.as_ptr()))
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, w: &mut [u32; PAR]) {
+        vst1q_u32(w.as_mut_ptr(), self.0)
+    }
+
+    #[inline(always)]
+    unsafe fn splat(v: u32) -> Self {
+        Self(vdupq_n_u32(v))
+    }
+
+    #[inline(always)]
+    unsafe fn add(self, rhs: Self) -> Self {
+        Self(vaddq_u32(self.0, rhs.0))
+    }
+
+    #[inline(always)]
+    unsafe fn sub(self, rhs: Self) -> Self {
+        Self(vsubq_u32(self.0, rhs.0))
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        Self(veorq_u32(self.0, rhs.0))
+    }
+
+    #[inline(always)]
+    unsafe fn mul(self, rhs: Self) -> Self {
+        Self(vmulq_u32(self.0, rhs.0))
+    }
+
+    #[inline(always)]
+    unsafe fn rotl_lg_w(self) -> Self {
+        Self(vsliq_n_u32::<5>(vshrq_n_u32::<27>(self.0), self.0))
+    }
+
+    #[inline(always)]
+    unsafe fn rotl(self, r: Self) -> Self {
+        // `vshlq_u32` shifts right for negative shift amounts
+        let l = vreinterpretq_s32_u32(vandq_u32(r.0, vdupq_n_u32(31)));
+        let r = vsubq_s32(l, vdupq_n_s32(32));
+        Self(vorrq_u32(vshlq_u32(self.0, l), vshlq_u32(self.0, r)))
+    }
+}
+
+#[inline(always)]
+pub(super) fn encrypt_par(key: &[u32], blocks: &mut [[u32; 4]; PAR]) {
+    // SAFETY: NEON is enabled at compile time
+    unsafe { super::encrypt::<NeonLanes>(key, blocks) }
+}
+
+#[inline(always)]
+pub(super) fn decrypt_par(key: &[u32], blocks: &mut [[u32; 4]; PAR]) {
+    // SAFETY: NEON is enabled at compile time
+    unsafe { super::decrypt::<NeonLanes>(key, blocks) }
+}
@@ -0,0 +1,189 @@
+//! SSE4.1 and AVX2 backends which process four blocks using 128-bit vectors.
+//!
+//! SSE4.1 has no per-lane variable shifts, so rotations are computed by
+//! multiplying with `2^r` and folding the high half of the 64-bit product
+//! back into the low half. AVX2 provides variable shifts directly.
+//!
+//! The methods of the lane types are compiled with their instruction set
+//! enabled, and the round functions using them are only called with a
+//! [`Token`] proving that the CPU supports it.
+#![allow(unsafe_code, unsafe_op_in_unsafe_fn)]
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::{Lanes, PAR};
+
+cpufeatures::new!(sse41_cpuid, "sse4.1");
+cpufeatures::new!(avx2_cpuid, "avx2");
+
+/// Proof that the selected instruction set is supported by the current CPU.
+#[derive(Clone, Copy)]
+pub(super) enum Token {
+    Sse41,
+    Avx2,
+}
+
+impl Token {
+    #[inline(always)]
+    pub(super) fn get() -> Option<Self> {
+        if avx2_cpuid::get() {
+            Some(Token::Avx2)
+        } else if sse41_cpuid::get() {
+            Some(Token::Sse41)
+        } else {
+            None
+        }
+    }
+}
+
+/// Implement the lane operations shared by both backends.
+macro_rules! impl_lanes {
+    ($name:ident, $feature:literal, $rotl:item) => {
+        impl Lanes for $name {
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn load(w: &[u32; PAR]) -> Self {
+                Self(_mm_loadu_si128(w.as_ptr().cast()))
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn store(self, w: &mut [u32; PAR]) {
+                _mm_storeu_si128(w.as_mut_ptr().cast(), self.0)
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn splat(v: u32) -> Self {
+                Self(_mm_set1_epi32(v as i32))
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn add(self, rhs: Self) -> Self {
+                Self(_mm_add_epi32(self.0, rhs.0))
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn sub(self, rhs: Self) -> Self {
+                Self(_mm_sub_epi32(self.0, rhs.0))
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn xor(self, rhs: Self) -> Self {
+                Self(_mm_xor_si128(self.0, rhs.0))
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn mul(self, rhs: Self) -> Self {
+                Self(_mm_mullo_epi32(self.0, rhs.0))
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn rotl_lg_w(self) -> Self {
+                Self(_mm_or_si128(_mm_slli_epi32(self.0, 5), _mm_srli_epi32(self.0, 27)))
+            }
+
+            $rotl
+        }
+    };
+}
+
+/// Four 32-bit lanes using SSE4.1 instructions.
+#[derive(Clone, Copy)]
+struct Sse41Lanes(__m128i);
+
+impl_lanes!(
+    Sse41Lanes,
+    "sse4.1",
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn rotl(self, r: Self) -> Self {
+        // 2^r computed through the exponent of an `f32`. For `r = 31` the
+        // conversion overflows to `0x8000_0000`, which is exactly 2^31.
+        let r = _mm_and_si128(r.0, _mm_set1_epi32(31));
+        let one = _mm_set1_epi32(0x3f80_0000);
+        let p = _mm_cvttps_epi32(_mm_castsi128_ps(_mm_add_epi32(_mm_slli_epi32(r, 23), one)));
+
+        // 64-bit products of lanes 0, 2 and 1, 3 hold `x << r` in the
+        // low half and `x >> (32 - r)` in the high half
+        let even = _mm_mul_epu32(self.0, p);
+        let odd = _mm_mul_epu32(_mm_srli_epi64(self.0, 32), _mm_srli_epi64(p, 32));
+        let even = _mm_or_si128(even, _mm_srli_epi64(even, 32));
+        let odd = _mm_or_si128(odd, _mm_slli_epi64(odd, 32));
+        Self(_mm_blend_epi16(even, odd, 0b1100_1100))
+    }
+);
+
+/// Four 32-bit lanes using the AVX2 variable shifts.
+#[derive(Clone, Copy)]
+struct Avx2Lanes(__m128i);
+
+impl_lanes!(
+    Avx2Lanes,
+    "avx2",
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn rotl(self, r: Self) -> Self {
+        let l = _mm_and_si128(r.0, _mm_set1_epi32(31));
+        let r = _mm_sub_epi32(_mm_set1_epi32(32), l);
+        Self(_mm_or_si128(
+            _mm_sllv_epi32(self.0, l),
+            _mm_srlv_epi32(self.0, r),
+        ))
+    }
+);
+
+#[target_feature(enable = "sse4.1")]
+unsafe fn encrypt_sse41(key: &[u32], blocks: &mut [[u32; 4]; PAR]) {
+    // SAFETY: SSE4.1 is enabled for this function
+    unsafe { super::encrypt::<Sse41Lanes>(key, blocks) }
+}
+
+#[target_feature(enable = "sse4.1")]
+unsafe fn decrypt_sse41(key: &[u32], blocks: &mut [[u32; 4]; PAR]) {
+    // SAFETY: SSE4.1 is enabled for this function
+    unsafe { super::decrypt::<Sse41Lanes>(key, blocks) }
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn encrypt_avx2(key: &[u32], blocks: &mut [[u32; 4]; PAR]) {
+    // SAFETY: AVX2 is enabled for this function
+    unsafe { super::encrypt::<Avx2Lanes>(key, blocks) }
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn decrypt_avx2(key: &[u32], blocks: &mut [[u32; 4]; PAR]) {
+    // SAFETY: AVX2 is enabled for this function
+    unsafe { super::decrypt::<Avx2Lanes>(key, blocks) }
+}
+
+#[inline(always)]
+pub(super) fn encrypt_par(token: Token, key: &[u32], blocks: &mut [[u32; 4]; PAR]) {
+    // SAFETY: each variant of `token` proves that the CPU supports the
+    // instruction set of the function called for it
+    unsafe {
+        match token {
+            Token::Sse41 => encrypt_sse41(key, blocks),
+            Token::Avx2 => encrypt_avx2(key, blocks),
+        }
+    }
+}
+
+#[inline(always)]
+pub(super) fn decrypt_par(token: Token, key: &[u32], blocks: &mut [[u32; 4]; PAR]) {
+    // SAFETY: as in `encrypt_par`
+    unsafe {
+        match token {
+            Token::Sse41 => decrypt_sse41(key, blocks),
+            Token::Avx2 => decrypt_avx2(key, blocks),
+        }
+    }
+}
@@ -1,13 +1,18 @@
 use cipher::{
     array::{Array, ArraySize},
-    typenum::{Diff, Prod, Quot, Sum, U1, U2, U4, U8},
+    typenum::{Diff, Prod, Quot, Sum, U1, U2, U4, U8, U16},
     zeroize::DefaultIsZeroes,
 };
 use core::ops::{Add, BitXor};
 
+use super::backend::{decrypt_words, encrypt_words};
+use super::par;
+
 pub type BlockSize<W> = Prod<<W as Word>::Bytes, U4>;
 pub type Block<W> = Array<u8, BlockSize<W>>;
 
+pub type ParBlocks<W> = Array<Block<W>, <W as Word>::ParBlocksSize>;
+
 pub type Key<B> = Array<u8, B>;
 
 pub type ExpandedKeyTable<W, R> = Array<W, ExpandedKeyTableSize<R>>;
@@ -18,6 +23,7 @@ pub type KeyAsWordsSize<W, B> = Quot<Diff<Sum<B, <W as Word>::Bytes>, U1>, <W as
 
 pub trait Word: Default + Copy + From<u8> + Add<Output = Self> + DefaultIsZeroes {
     type Bytes: ArraySize;
+    type ParBlocksSize: ArraySize;
 
     const ZERO: Self;
     const THREE: Self;
@@ -37,10 +43,27 @@ pub trait Word: Default + Copy + From<u8> + Add<Output = Self> + DefaultIsZeroes
     fn to_le_bytes(self) -> Array<u8, Self::Bytes>;
 
     fn bitxor(self, other: Self) -> Self;
+
+    /// Encrypt `Self::ParBlocksSize` blocks of words.
+    #[inline(always)]
+    fn encrypt_par(key: &[Self], blocks: &mut [[Self; 4]]) {
+        for block in blocks {
+            *block = encrypt_words(*block, key);
+        }
+    }
+
+    /// Decrypt `Self::ParBlocksSize` blocks of words.
+    #[inline(always)]
+    fn decrypt_par(key: &[Self], blocks: &mut [[Self; 4]]) {
+        for block in blocks {
+            *block = decrypt_words(*block, key);
+        }
+    }
 }
 
 impl Word for u8 {
     type Bytes = U1;
+    type ParBlocksSize = U1;
 
     const ZERO: Self = 0;
     const THREE: Self = 3;
@@ -92,6 +115,7 @@ impl Word for u8 {
 
 impl Word for u16 {
     type Bytes = U2;
+    type ParBlocksSize = U1;
 
     const ZERO: Self = 0;
     const THREE: Self = 3;
@@ -143,6 +167,7 @@ impl Word for u16 {
 
 impl Word for u32 {
     type Bytes = U4;
+    type ParBlocksSize = U4;
 
     const ZERO: Self = 0;
     const THREE: Self = 3;
@@ -190,10 +215,21 @@ impl Word for u32 {
     fn bitxor(self, other: Self) -> Self {
         <u32 as BitXor>::bitxor(self, other)
     }
+
+    #[inline(always)]
+    fn encrypt_par(key: &[Self], blocks: &mut [[Self; 4]]) {
+        par::encrypt_par(key, blocks.try_into().unwrap())
+    }
+
+    #[inline(always)]
+    fn decrypt_par(key: &[Self], blocks: &mut [[Self; 4]]) {
+        par::decrypt_par(key, blocks.try_into().unwrap())
+    }
 }
 
 impl Word for u64 {
     type Bytes = U8;
+    type ParBlocksSize = U1;
 
     const ZERO: Self = 0;
     const THREE: Self = 3;
@@ -244,3 +280,57 @@ impl Word for u64 {
         <u64 as BitXor>::bitxor(self, other)
     }
 }
+
+impl Word for u128 {
+    type Bytes = U16;
+    type ParBlocksSize = U1;
+
+    const ZERO: Self = 0;
+    const THREE: Self = 3;
+    const EIGHT: Self = 8;
+
+    const P: Self = 0xb7e151628aed2a6abf7158809cf4f3c7;
+    const Q: Self = 0x9e3779b97f4a7c15f39cc0605cedc835;
+
+    #[inline(always)]
+    fn wrapping_add(self, rhs: Self) -> Self {
+        u128::wrapping_add(self, rhs)
+    }
+
+    #[inline(always)]
+    fn wrapping_sub(self, rhs: Self) -> Self {
+        u128::wrapping_sub(self, rhs)
+    }
+
+    #[inline(always)]
+    fn wrapping_mul(self, rhs: Self) -> Self {
+        u128::wrapping_mul(self, rhs)
+    }
+
+    #[inline(always)]
+    fn rotate_left(self, n: Self) -> Self {
+        let size = Self::BITS;
+        u128::rotate_left(self, (n % size as u128) as u32)
+    }
+
+    #[inline(always)]
+    fn rotate_right(self, n: Self) -> Self {
+        let size = Self::BITS;
+        u128::rotate_right(self, (n % size as u128) as u32)
+    }
+
+    #[inline(always)]
+    fn from_le_bytes(bytes: &Array<u8, Self::Bytes>) -> Self {
+        u128::from_le_bytes(bytes.as_slice().try_into().unwrap())
+    }
+
+    #[inline(always)]
+    fn to_le_bytes(self) -> Array<u8, Self::Bytes> {
+        u128::to_le_bytes(self).into()
+    }
+
+    #[inline(always)]
+    fn bitxor(self, other: Self) -> Self {
+        <u128 as BitXor>::bitxor(self, other)
+    }
+}
@@ -1,4 +1,5 @@
 #![no_std]
+#![deny(unsafe_code)]
 
 mod block_cipher;
 mod core;
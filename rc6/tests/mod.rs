@@ -3,7 +3,8 @@
 #[allow(deprecated)] // uses `clone_from_slice`
 mod tests {
     use cipher::consts::*;
-    use cipher::{BlockCipherDecrypt, BlockCipherEncrypt, KeyInit, array::Array};
+    use cipher::{Array, BlockCipherDecrypt, BlockCipherEncrypt, KeyInit};
+    use hex_literal::hex;
     use rc6::RC6;
 
     #[test]
@@ -94,4 +95,118 @@ mod tests {
         rc6.decrypt_block(&mut block);
         assert_eq!(pt, block[..]);
     }
+
+    #[test]
+    fn enc_dec_128_28_32() {
+        let key = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D,
+            0x0E, 0x0F, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1A, 0x1B,
+            0x1C, 0x1D, 0x1E, 0x1F,
+        ];
+
+        let pt = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D,
+            0x0E, 0x0F, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1A, 0x1B,
+            0x1C, 0x1D, 0x1E, 0x1F, 0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x28, 0x29,
+            0x2A, 0x2B, 0x2C, 0x2D, 0x2E, 0x2F, 0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37,
+            0x38, 0x39, 0x3A, 0x3B, 0x3C, 0x3D, 0x3E, 0x3F,
+        ];
+        let ct = [
+            0x4E, 0xD8, 0x7C, 0x64, 0xBA, 0xFF, 0xEC, 0xD4, 0x30, 0x3E, 0xE6, 0xA7, 0x9A, 0xAF,
+            0xAE, 0xF5, 0x75, 0xB3, 0x51, 0xC0, 0x24, 0x27, 0x2B, 0xE7, 0x0A, 0x70, 0xB4, 0xA3,
+            0x92, 0xCF, 0xC1, 0x57, 0xDB, 0xA5, 0x2D, 0x52, 0x9A, 0x79, 0xE8, 0x38, 0x45, 0xBF,
+            0x43, 0xD6, 0x75, 0x45, 0x38, 0x3A, 0xED, 0x3D, 0xBF, 0x4F, 0x0D, 0x23, 0x64, 0x0E,
+            0x44, 0xCB, 0xF6, 0xCD, 0xAA, 0x03, 0x4D, 0xCB,
+        ];
+
+        let rc6 = <RC6<u128, U28, U32> as KeyInit>::new_from_slice(&key).unwrap();
+        let mut block = Array::clone_from_slice(&pt);
+        rc6.encrypt_block(&mut block);
+
+        assert_eq!(ct, block[..]);
+
+        rc6.decrypt_block(&mut block);
+        assert_eq!(pt, block[..]);
+    }
+
+    /// RC6-32/20/b test vectors from the AES submission
+    macro_rules! aes_candidate_test {
+        ($name:ident, $key_size:ty, $key:literal, $pt:literal, $ct:literal) => {
+            #[test]
+            fn $name() {
+                let key = hex!($key);
+                let pt = hex!($pt);
+                let ct = hex!($ct);
+
+                let rc6 = <RC6<u32, U20, $key_size> as KeyInit>::new_from_slice(&key).unwrap();
+                let mut block = Array::from(pt);
+                rc6.encrypt_block(&mut block);
+
+                assert_eq!(ct, block[..]);
+
+                rc6.decrypt_block(&mut block);
+                assert_eq!(pt, block[..]);
+            }
+        };
+    }
+
+    aes_candidate_test!(
+        aes_candidate_128_zero,
+        U16,
+        "00000000000000000000000000000000",
+        "00000000000000000000000000000000",
+        "8fc3a53656b1f778c129df4e9848a41e"
+    );
+    aes_candidate_test!(
+        aes_candidate_128,
+        U16,
+        "0123456789abcdef0112233445566778",
+        "02132435465768798a9bacbdcedfe0f1",
+        "524e192f4715c6231f51f6367ea43f18"
+    );
+    aes_candidate_test!(
+        aes_candidate_192_zero,
+        U24,
+        "000000000000000000000000000000000000000000000000",
+        "00000000000000000000000000000000",
+        "6cd61bcb190b30384e8a3f168690ae82"
+    );
+    aes_candidate_test!(
+        aes_candidate_192,
+        U24,
+        "0123456789abcdef0112233445566778899aabbccddeeff0",
+        "02132435465768798a9bacbdcedfe0f1",
+        "688329d019e505041e52e92af95291d4"
+    );
+    aes_candidate_test!(
+        aes_candidate_256_zero,
+        U32,
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "00000000000000000000000000000000",
+        "8f5fbd0510d15fa893fa3fda6e857ec2"
+    );
+    aes_candidate_test!(
+        aes_candidate_256,
+        U32,
+        "0123456789abcdef0112233445566778899aabbccddeeff01032547698badcfe",
+        "02132435465768798a9bacbdcedfe0f1",
+        "c8241816f0d7e48920ad16a1674e5d48"
+    );
+
+    /// Only RC6-32 has a multi-block backend.
+    #[test]
+    fn rc6_32_par_blocks() {
+        let key: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let rc6 = <RC6<u32, U20, U16> as KeyInit>::new(&key.into());
+        par_blocks_test::check_par_blocks(&rc6, 4);
+    }
+
+    #[test]
+    fn single_block_backends() {
+        let rc6 = <RC6<u8, U12, U4> as KeyInit>::new(&Default::default());
+        assert_eq!(par_blocks_test::par_blocks(&rc6), [1; 2]);
+
+        let rc6 = <RC6<u64, U24, U24> as KeyInit>::new(&Default::default());
+        assert_eq!(par_blocks_test::par_blocks(&rc6), [1; 2]);
+    }
 }
@@ -0,0 +1,295 @@
+//! Multi-block SM4 backends.
+//!
+//! Blocks are processed in a word-sliced layout: lane `k` of vector `x[j]`
+//! holds word `j` of block `k`, so every round operates on all blocks at once.
+//! Backends differ in the number of lanes and in the evaluation of the S-box
+//! layer `tau`, the remaining operations are shared.
+//!
+//! The SM4 S-box is affine-equivalent to the AES S-box: both are inversions in
+//! GF(2^8) surrounded by affine maps, and the fields are isomorphic. The x86
+//! and portable backends compute the S-box as `S(x) = A_out(S_AES(A_in(x)))`,
+//! with the AES S-box evaluated with `AESENCLAST`, `GF2P8AFFINEINVQB` or a
+//! bitsliced circuit, so they do not use secret-dependent memory accesses.
+//!
+//! The aarch64 backends use the `SM4E` instruction, or look up the S-box with
+//! the NEON `TBL` instruction, which accesses the whole table held in registers
+//! in constant time.
+#![allow(unsafe_code)]
+
+use crate::Backend;
+use cipher::{Array, BlockCipherDecClosure, BlockCipherEncClosure, consts::U16};
+
+#[cfg(all(
+    target_arch = "aarch64",
+    target_feature = "neon",
+    not(sm4_backend = "soft")
+))]
+mod aarch64;
+pub(crate) mod soft;
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    not(sm4_backend = "soft")
+))]
+mod x86;
+
+/// Backend selected at runtime, with a proof of support of its target
+/// features.
+#[derive(Clone, Copy)]
+pub(crate) enum Imp {
+    // Never constructed on aarch64 targets with NEON enabled
+    #[allow(dead_code)]
+    Soft,
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        not(sm4_backend = "soft")
+    ))]
+    X86(x86::Imp),
+    #[cfg(all(
+        target_arch = "aarch64",
+        target_feature = "neon",
+        not(sm4_backend = "soft")
+    ))]
+    Aarch64(aarch64::Imp),
+}
+
+impl Imp {
+    /// Select the fastest backend supported by the current CPU.
+    pub(crate) fn detect() -> Self {
+        #[cfg(all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            not(sm4_backend = "soft")
+        ))]
+        if let Some(imp) = x86::Imp::detect() {
+            return Self::X86(imp);
+        }
+        #[cfg(all(
+            target_arch = "aarch64",
+            target_feature = "neon",
+            not(sm4_backend = "soft")
+        ))]
+        return Self::Aarch64(aarch64::Imp::detect());
+        #[cfg(not(all(
+            target_arch = "aarch64",
+            target_feature = "neon",
+            not(sm4_backend = "soft")
+        )))]
+        Self::Soft
+    }
+
+    pub(crate) fn backend(self) -> Backend {
+        match self {
+            Self::Soft => Backend::Soft,
+            #[cfg(all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                not(sm4_backend = "soft")
+            ))]
+            Self::X86(imp) => imp.backend(),
+            #[cfg(all(
+                target_arch = "aarch64",
+                target_feature = "neon",
+                not(sm4_backend = "soft")
+            ))]
+            Self::Aarch64(imp) => imp.backend(),
+        }
+    }
+
+    /// Expand the key words `mk` into round keys.
+    pub(crate) fn expand_key(self, mk: &[u32; 4]) -> [u32; 32] {
+        match self {
+            #[cfg(all(
+                target_arch = "aarch64",
+                target_feature = "neon",
+                not(sm4_backend = "soft")
+            ))]
+            Self::Aarch64(imp) => imp.expand_key(mk),
+            _ => soft::expand_key(mk),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn encrypt(self, rk: &[u32; 32], f: impl BlockCipherEncClosure<BlockSize = U16>) {
+        match self {
+            Self::Soft => f.call(&soft::Sm4Soft::<false> { rk, token: () }),
+            #[cfg(all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                not(sm4_backend = "soft")
+            ))]
+            Self::X86(imp) => imp.encrypt(rk, f),
+            #[cfg(all(
+                target_arch = "aarch64",
+                target_feature = "neon",
+                not(sm4_backend = "soft")
+            ))]
+            Self::Aarch64(imp) => imp.encrypt(rk, f),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn decrypt(self, rk: &[u32; 32], f: impl BlockCipherDecClosure<BlockSize = U16>) {
+        match self {
+            Self::Soft => f.call(&soft::Sm4Soft::<true> { rk, token: () }),
+            #[cfg(all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                not(sm4_backend = "soft")
+            ))]
+            Self::X86(imp) => imp.decrypt(rk, f),
+            #[cfg(all(
+                target_arch = "aarch64",
+                target_feature = "neon",
+                not(sm4_backend = "soft")
+            ))]
+            Self::Aarch64(imp) => imp.decrypt(rk, f),
+        }
+    }
+}
+
+/// Vector of `P` 32-bit lanes.
+///
+/// The methods of SIMD implementations are compiled with the target features
+/// of their intrinsics, so callers must ensure that the CPU supports them.
+pub(crate) trait Lanes<const P: usize>: Copy {
+    unsafe fn load(w: &[u32; P]) -> Self;
+    unsafe fn store(self, w: &mut [u32; P]);
+    unsafe fn splat(v: u32) -> Self;
+    unsafe fn xor(self, rhs: Self) -> Self;
+    unsafe fn rotl(self, r: u32) -> Self;
+    /// Apply the SM4 S-box to every byte.
+    unsafe fn tau(self) -> Self;
+}
+
+/// Round function `T = L ∘ tau`.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+unsafe fn t<V: Lanes<P>, const P: usize>(x: V) -> V {
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        let b = x.tau();
+        // L(B) = B ⊕ (B <<< 2) ⊕ (B <<< 10) ⊕ (B <<< 18) ⊕ (B <<< 24)
+        let c = b.xor(b.rotl(8)).xor(b.rotl(16)).rotl(2);
+        b.xor(b.rotl(24)).xor(c)
+    }
+}
+
+/// Encrypt (or decrypt if `DEC` is true) `P` blocks of big-endian words.
+///
+/// The output words are stored in the order of the output block.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+pub(crate) unsafe fn crypt<V: Lanes<P>, const P: usize, const DEC: bool>(
+    rk: &[u32; 32],
+    blocks: &mut [[u32; 4]; P],
+) {
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        let mut x: [V; 4] =
+            core::array::from_fn(|j| V::load(&core::array::from_fn(|k| blocks[k][j])));
+
+        for i in 0..8 {
+            let k: [u32; 4] = core::array::from_fn(|j| {
+                if DEC {
+                    rk[31 - 4 * i - j]
+                } else {
+                    rk[4 * i + j]
+                }
+            });
+            x[0] = x[0].xor(t(x[1].xor(x[2]).xor(x[3]).xor(V::splat(k[0]))));
+            x[1] = x[1].xor(t(x[2].xor(x[3]).xor(x[0]).xor(V::splat(k[1]))));
+            x[2] = x[2].xor(t(x[3].xor(x[0]).xor(x[1]).xor(V::splat(k[2]))));
+            x[3] = x[3].xor(t(x[0].xor(x[1]).xor(x[2]).xor(V::splat(k[3]))));
+        }
+
+        for (j, x) in x.into_iter().rev().enumerate() {
+            let mut w = [0; P];
+            x.store(&mut w);
+            for (block, w) in blocks.iter_mut().zip(w) {
+                block[j] = w;
+            }
+        }
+    }
+}
+
+#[inline(always)]
+fn read_block(block: &Array<u8, U16>) -> [u32; 4] {
+    core::array::from_fn(|j| u32::from_be_bytes(block[4 * j..][..4].try_into().unwrap()))
+}
+
+#[inline(always)]
+fn write_block(w: &[u32; 4], block: &mut Array<u8, U16>) {
+    for (chunk, w) in block.chunks_exact_mut(4).zip(w) {
+        chunk.copy_from_slice(&w.to_be_bytes());
+    }
+}
+
+/// Define a cipher backend type `$name<'a, const DEC: bool>` which holds
+/// round keys and a `$token` proving support of the target features.
+///
+/// The backend encrypts `$par` blocks at once with
+/// `$crypt_par::<DEC>($token, rk, words)` and single blocks with
+/// `$crypt_block::<DEC>($token, rk, words)`.
+macro_rules! define_backend {
+    ($name:ident, $token:ty, $par:ty, $crypt_par:ident, $crypt_block:ident $(,)?) => {
+        pub(crate) struct $name<'a, const DEC: bool> {
+            pub(crate) rk: &'a [u32; 32],
+            pub(crate) token: $token,
+        }
+
+        impl<const DEC: bool> cipher::BlockSizeUser for $name<'_, DEC> {
+            type BlockSize = cipher::consts::U16;
+        }
+
+        impl<const DEC: bool> cipher::ParBlocksSizeUser for $name<'_, DEC> {
+            type ParBlocksSize = $par;
+        }
+
+        impl<const DEC: bool> $name<'_, DEC> {
+            #[inline(always)]
+            fn block(&self, mut block: cipher::InOut<'_, '_, cipher::Block<Self>>) {
+                let mut w = $crate::backends::read_block(block.get_in());
+                $crypt_block::<DEC>(self.token, self.rk, &mut w);
+                $crate::backends::write_block(&w, block.get_out());
+            }
+
+            #[inline(always)]
+            fn par_blocks(&self, mut blocks: cipher::InOut<'_, '_, cipher::ParBlocks<Self>>) {
+                let mut words = [[0u32; 4]; <$par as cipher::typenum::Unsigned>::USIZE];
+                for (w, b) in words.iter_mut().zip(blocks.get_in().iter()) {
+                    *w = $crate::backends::read_block(b);
+                }
+                $crypt_par::<DEC>(self.token, self.rk, &mut words);
+                for (w, b) in words.iter().zip(blocks.get_out().iter_mut()) {
+                    $crate::backends::write_block(w, b);
+                }
+            }
+        }
+
+        impl cipher::BlockCipherEncBackend for $name<'_, false> {
+            #[inline]
+            fn encrypt_block(&self, block: cipher::InOut<'_, '_, cipher::Block<Self>>) {
+                self.block(block)
+            }
+
+            #[inline]
+            fn encrypt_par_blocks(&self, blocks: cipher::InOut<'_, '_, cipher::ParBlocks<Self>>) {
+                self.par_blocks(blocks)
+            }
+        }
+
+        impl cipher::BlockCipherDecBackend for $name<'_, true> {
+            #[inline]
+            fn decrypt_block(&self, block: cipher::InOut<'_, '_, cipher::Block<Self>>) {
+                self.block(block)
+            }
+
+            #[inline]
+            fn decrypt_par_blocks(&self, blocks: cipher::InOut<'_, '_, cipher::ParBlocks<Self>>) {
+                self.par_blocks(blocks)
+            }
+        }
+    };
+}
+
+pub(crate) use define_backend;
@@ -0,0 +1,60 @@
+//! aarch64 backends using the SM4 instructions of the ARMv8 Cryptographic
+//! Extension or NEON.
+//!
+//! The `SM4E` instruction computes four rounds of one block and `SM4EKEY`
+//! computes four round keys. Their intrinsics are unstable, so they are
+//! emitted with inline assembly.
+//!
+//! CPUs without the SM4 instructions use NEON, which is enabled at compile
+//! time on all targets these backends are built for. The S-box is looked up
+//! with `TBL` and `TBX` from a table held in 16 vector registers.
+#![allow(unsafe_code)]
+
+use super::soft;
+use crate::Backend;
+use cipher::{BlockCipherDecClosure, BlockCipherEncClosure, consts::U16};
+
+mod ce;
+mod neon;
+
+#[derive(Clone, Copy)]
+pub(crate) enum Imp {
+    Neon,
+    Ce(ce::Token),
+}
+
+impl Imp {
+    pub(crate) fn detect() -> Self {
+        ce::Token::get().map_or(Self::Neon, Self::Ce)
+    }
+
+    pub(crate) fn backend(self) -> Backend {
+        match self {
+            Self::Neon => Backend::Neon,
+            Self::Ce(_) => Backend::Sm4Ce,
+        }
+    }
+
+    pub(crate) fn expand_key(self, mk: &[u32; 4]) -> [u32; 32] {
+        match self {
+            Self::Neon => soft::expand_key(mk),
+            Self::Ce(token) => ce::expand_key(token, mk),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn encrypt(self, rk: &[u32; 32], f: impl BlockCipherEncClosure<BlockSize = U16>) {
+        match self {
+            Self::Neon => f.call(&neon::Sm4Neon::<false> { rk, token: () }),
+            Self::Ce(token) => f.call(&ce::Sm4Ce::<false> { rk, token }),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn decrypt(self, rk: &[u32; 32], f: impl BlockCipherDecClosure<BlockSize = U16>) {
+        match self {
+            Self::Neon => f.call(&neon::Sm4Neon::<true> { rk, token: () }),
+            Self::Ce(token) => f.call(&ce::Sm4Ce::<true> { rk, token }),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Backend using the `SM4E` and `SM4EKEY` instructions which processes eight
+//! blocks in parallel.
+//!
+//! Vectors hold the four words of a block, with the first word in lane 0.
+
+use core::arch::{aarch64::*, asm};
+
+use crate::backends::define_backend;
+use crate::consts::{CK, FK};
+use cipher::consts::U8;
+
+cpufeatures::new!(sm4_cpuid, "sm4");
+
+/// Proof that the SM4 instructions are supported by the current CPU.
+#[derive(Clone, Copy)]
+pub(crate) struct Token(());
+
+impl Token {
+    #[inline(always)]
+    pub(crate) fn get() -> Option<Self> {
+        sm4_cpuid::get().then_some(Token(()))
+    }
+}
+
+/// Apply four rounds with the round keys `rk` to the block `x`.
+#[inline]
+#[target_feature(enable = "sm4")]
+unsafe fn sm4e(mut x: uint32x4_t, rk: uint32x4_t) -> uint32x4_t {
+    unsafe {
+        asm!(
+            "sm4e {x:v}.4s, {rk:v}.4s",
+            x = inout(vreg) x,
+            rk = in(vreg) rk,
+            options(pure, nomem, nostack, preserves_flags),
+        );
+    }
+    x
+}
+
+/// Compute the four round keys following the round keys `k` using the
+/// constants `ck`.
+#[inline]
+#[target_feature(enable = "sm4")]
+unsafe fn sm4ekey(k: uint32x4_t, ck: uint32x4_t) -> uint32x4_t {
+    let out;
+    unsafe {
+        asm!(
+            "sm4ekey {out:v}.4s, {k:v}.4s, {ck:v}.4s",
+            out = lateout(vreg) out,
+            k = in(vreg) k,
+            ck = in(vreg) ck,
+            options(pure, nomem, nostack, preserves_flags),
+        );
+    }
+    out
+}
+
+/// Reverse the order of the lanes.
+#[inline(always)]
+unsafe fn rev(x: uint32x4_t) -> uint32x4_t {
+    unsafe {
+        let x = vrev64q_u32(x);
+        vextq_u32::<2>(x, x)
+    }
+}
+
+#[target_feature(enable = "sm4")]
+unsafe fn expand(mk: &[u32; 4]) -> [u32; 32] {
+    let mut rk = [0u32; 32];
+    unsafe {
+        let mut k = veorq_u32(vld1q_u32(mk.as_ptr()), vld1q_u32(FK.as_ptr()));
+        for (rk, ck) in rk.chunks_exact_mut(4).zip(CK.chunks_exact(4)) {
+            k = sm4ekey(k, vld1q_u32(ck.as_ptr()));
+            vst1q_u32(rk.as_mut_ptr(), k);
+        }
+    }
+    rk
+}
+
+/// Encrypt (or decrypt if `DEC` is true) `P` blocks of big-endian words.
+#[target_feature(enable = "sm4")]
+unsafe fn crypt<const DEC: bool, const P: usize>(rk: &[u32; 32], blocks: &mut [[u32; 4]; P]) {
+    unsafe {
+        let mut k = [vdupq_n_u32(0); 8];
+        for (i, k) in k.iter_mut().enumerate() {
+            *k = if DEC {
+                rev(vld1q_u32(rk[28 - 4 * i..].as_ptr()))
+            } else {
+                vld1q_u32(rk[4 * i..].as_ptr())
+            };
+        }
+
+        let mut x = [vdupq_n_u32(0); P];
+        for (x, block) in x.iter_mut().zip(blocks.iter()) {
+            *x = vld1q_u32(block.as_ptr());
+        }
+        for k in k {
+            for x in x.iter_mut() {
+                *x = sm4e(*x, k);
+            }
+        }
+        for (x, block) in x.into_iter().zip(blocks.iter_mut()) {
+            vst1q_u32(block.as_mut_ptr(), rev(x));
+        }
+    }
+}
+
+pub(super) fn expand_key(_token: Token, mk: &[u32; 4]) -> [u32; 32] {
+    // SAFETY: `Token` can only be obtained if the SM4 instructions are available
+    unsafe { expand(mk) }
+}
+
+#[inline(always)]
+fn crypt_par<const DEC: bool>(_token: Token, rk: &[u32; 32], blocks: &mut [[u32; 4]; 8]) {
+    // SAFETY: `Token` can only be obtained if the SM4 instructions are available
+    unsafe { crypt::<DEC, 8>(rk, blocks) }
+}
+
+#[inline(always)]
+fn crypt_block<const DEC: bool>(_token: Token, rk: &[u32; 32], block: &mut [u32; 4]) {
+    // SAFETY: `Token` can only be obtained if the SM4 instructions are available
+    unsafe { crypt::<DEC, 1>(rk, core::array::from_mut(block)) }
+}
+
+define_backend!(Sm4Ce, Token, U8, crypt_par, crypt_block);
@@ -0,0 +1,145 @@
+//! NEON backend which processes eight blocks using pairs of 128-bit vectors.
+//!
+//! This module is only compiled with `target_feature = "neon"` enabled for
+//! the crate, so the methods of `U32x4` can be called anywhere.
+#![allow(unsafe_op_in_unsafe_fn)]
+
+use core::arch::aarch64::*;
+
+use crate::backends::{Lanes, crypt, define_backend};
+use cipher::consts::U8;
+
+const SBOX: [u8; 256] = [
+    0xd6, 0x90, 0xe9, 0xfe, 0xcc, 0xe1, 0x3d, 0xb7, 0x16, 0xb6, 0x14, 0xc2, 0x28, 0xfb, 0x2c, 0x05,
+    0x2b, 0x67, 0x9a, 0x76, 0x2a, 0xbe, 0x04, 0xc3, 0xaa, 0x44, 0x13, 0x26, 0x49, 0x86, 0x06, 0x99,
+    0x9c, 0x42, 0x50, 0xf4, 0x91, 0xef, 0x98, 0x7a, 0x33, 0x54, 0x0b, 0x43, 0xed, 0xcf, 0xac, 0x62,
+    0xe4, 0xb3, 0x1c, 0xa9, 0xc9, 0x08, 0xe8, 0x95, 0x80, 0xdf, 0x94, 0xfa, 0x75, 0x8f, 0x3f, 0xa6,
+    0x47, 0x07, 0xa7, 0xfc, 0xf3, 0x73, 0x17, 0xba, 0x83, 0x59, 0x3c, 0x19, 0xe6, 0x85, 0x4f, 0xa8,
+    0x68, 0x6b, 0x81, 0xb2, 0x71, 0x64, 0xda, 0x8b, 0xf8, 0xeb, 0x0f, 0x4b, 0x70, 0x56, 0x9d, 0x35,
+    0x1e, 0x24, 0x0e, 0x5e, 0x63, 0x58, 0xd1, 0xa2, 0x25, 0x22, 0x7c, 0x3b, 0x01, 0x21, 0x78, 0x87,
+    0xd4, 0x00, 0x46, 0x57, 0x9f, 0xd3, 0x27, 0x52, 0x4c, 0x36, 0x02, 0xe7, 0xa0, 0xc4, 0xc8, 0x9e,
+    0xea, 0xbf, 0x8a, 0xd2, 0x40, 0xc7, 0x38, 0xb5, 0xa3, 0xf7, 0xf2, 0xce, 0xf9, 0x61, 0x15, 0xa1,
+    0xe0, 0xae, 0x5d, 0xa4, 0x9b, 0x34, 0x1a, 0x55, 0xad, 0x93, 0x32, 0x30, 0xf5, 0x8c, 0xb1, 0xe3,
+    0x1d, 0xf6, 0xe2, 0x2e, 0x82, 0x66, 0xca, 0x60, 0xc0, 0x29, 0x23, 0xab, 0x0d, 0x53, 0x4e, 0x6f,
+    0xd5, 0xdb, 0x37, 0x45, 0xde, 0xfd, 0x8e, 0x2f, 0x03, 0xff, 0x6a, 0x72, 0x6d, 0x6c, 0x5b, 0x51,
+    0x8d, 0x1b, 0xaf, 0x92, 0xbb, 0xdd, 0xbc, 0x7f, 0x11, 0xd9, 0x5c, 0x41, 0x1f, 0x10, 0x5a, 0xd8,
+    0x0a, 0xc1, 0x31, 0x88, 0xa5, 0xcd, 0x7b, 0xbd, 0x2d, 0x74, 0xd0, 0x12, 0xb8, 0xe5, 0xb4, 0xb0,
+    0x89, 0x69, 0x97, 0x4a, 0x0c, 0x96, 0x77, 0x7e, 0x65, 0xb9, 0xf1, 0x09, 0xc5, 0x6e, 0xc6, 0x84,
+    0x18, 0xf0, 0x7d, 0xec, 0x3a, 0xdc, 0x4d, 0x20, 0x79, 0xee, 0x5f, 0x3e, 0xd7, 0xcb, 0x39, 0x48,
+];
+
+/// Four 32-bit lanes.
+#[derive(Clone, Copy)]
+struct U32x4(uint32x4_t);
+
+/// Rotate 32-bit lanes left by `L` bits, `R` must be equal to `32 - L`.
+#[inline(always)]
+unsafe fn rotl_n<const L: i32, const R: i32>(x: uint32x4_t) -> uint32x4_t {
+    vsliq_n_u32::<L>(vshrq_n_u32::<R>(x), x)
+}
+
+impl Lanes<4> for U32x4 {
+    #[inline(always)]
+    unsafe fn load(w: &[u32; 4]) -> Self {
+        Self(vld1q_u32(w.as_ptr()))
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, w: &mut [u32; 4]) {
+        vst1q_u32(w.as_mut_ptr(), self.0)
+    }
+
+    #[inline(always)]
+    unsafe fn splat(v: u32) -> Self {
+        Self(vdupq_n_u32(v))
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        Self(veorq_u32(self.0, rhs.0))
+    }
+
+    #[inline(always)]
+    unsafe fn rotl(self, r: u32) -> Self {
+        Self(match r {
+            2 => rotl_n::<2, 30>(self.0),
+            8 => rotl_n::<8, 24>(self.0),
+            16 => vreinterpretq_u32_u16(vrev32q_u16(vreinterpretq_u16_u32(self.0))),
+            24 => rotl_n::<24, 8>(self.0),
+            _ => vorrq_u32(
+                vshlq_u32(self.0, vdupq_n_s32(r as i32)),
+                vshlq_u32(self.0, vdupq_n_s32(r as i32 - 32)),
+            ),
+        })
+    }
+
+    /// Look up each byte in the quarter of the table selected by its two most
+    /// significant bits. `TBL` returns zero and `TBX` keeps the destination
+    /// byte for indices outside of the 64-byte table, so every byte is
+    /// replaced exactly once.
+    #[inline(always)]
+    unsafe fn tau(self) -> Self {
+        let offset = vdupq_n_u8(64);
+        let mut i = vreinterpretq_u8_u32(self.0);
+        let mut s = vqtbl4q_u8(vld1q_u8_x4(SBOX.as_ptr()), i);
+        for t in SBOX[64..].chunks_exact(64) {
+            i = vsubq_u8(i, offset);
+            s = vqtbx4q_u8(s, vld1q_u8_x4(t.as_ptr()), i);
+        }
+        Self(vreinterpretq_u32_u8(s))
+    }
+}
+
+/// Eight lanes held in two vectors, which are processed independently.
+impl Lanes<8> for [U32x4; 2] {
+    #[inline(always)]
+    unsafe fn load(w: &[u32; 8]) -> Self {
+        let (a, b) = w.split_at(4);
+        [
+            U32x4::load(a.try_into().unwrap()),
+            U32x4::load(b.try_into().unwrap()),
+        ]
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, w: &mut [u32; 8]) {
+        let (a, b) = w.split_at_mut(4);
+        self[0].store(a.try_into().unwrap());
+        self[1].store(b.try_into().unwrap());
+    }
+
+    #[inline(always)]
+    unsafe fn splat(v: u32) -> Self {
+        [U32x4::splat(v); 2]
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        [self[0].xor(rhs[0]), self[1].xor(rhs[1])]
+    }
+
+    #[inline(always)]
+    unsafe fn rotl(self, r: u32) -> Self {
+        [self[0].rotl(r), self[1].rotl(r)]
+    }
+
+    #[inline(always)]
+    unsafe fn tau(self) -> Self {
+        [self[0].tau(), self[1].tau()]
+    }
+}
+
+fn crypt_par<const DEC: bool>(_: (), rk: &[u32; 32], blocks: &mut [[u32; 4]; 8]) {
+    // SAFETY: NEON is enabled at compile time
+    unsafe { crypt::<[U32x4; 2], 8, DEC>(rk, blocks) }
+}
+
+fn crypt_block<const DEC: bool>(_: (), rk: &[u32; 32], block: &mut [u32; 4]) {
+    let mut blocks = [[0; 4]; 4];
+    blocks[0] = *block;
+    // SAFETY: NEON is enabled at compile time
+    unsafe { crypt::<U32x4, 4, DEC>(rk, &mut blocks) };
+    *block = blocks[0];
+}
+
+define_backend!(Sm4Neon, (), U8, crypt_par, crypt_block);
@@ -0,0 +1,304 @@
+//! Portable constant-time backend which processes eight blocks with a
+//! bitsliced S-box.
+//!
+//! The bytes of eight words are transposed into eight bit planes, so the S-box
+//! layer of eight blocks is a single evaluation of a Boolean circuit on `u32`
+//! values. The circuit computes the AES S-box without the final constant
+//! (Boyar and Peralta, "A depth-16 circuit for the AES S-box") between the
+//! linear layers of the affine isomorphism.
+//!
+//! Single blocks and the key schedule use one lane: the four bytes of a word
+//! are transposed as half of a 64-bit bit matrix, which leaves the bit planes
+//! a quarter full but skips the linear layer of the seven unused lanes.
+
+use super::{Lanes, crypt, define_backend};
+use crate::consts::{CK, FK};
+use cipher::consts::U8;
+
+impl Lanes<8> for [u32; 8] {
+    #[inline(always)]
+    unsafe fn load(w: &[u32; 8]) -> Self {
+        *w
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, w: &mut [u32; 8]) {
+        *w = self;
+    }
+
+    #[inline(always)]
+    unsafe fn splat(v: u32) -> Self {
+        [v; 8]
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        core::array::from_fn(|k| self[k] ^ rhs[k])
+    }
+
+    #[inline(always)]
+    unsafe fn rotl(self, r: u32) -> Self {
+        self.map(|v| v.rotate_left(r))
+    }
+
+    #[inline(always)]
+    unsafe fn tau(mut self) -> Self {
+        transpose(&mut self);
+        sbox(&mut self);
+        transpose(&mut self);
+        self
+    }
+}
+
+impl Lanes<1> for u32 {
+    #[inline(always)]
+    unsafe fn load(w: &[u32; 1]) -> Self {
+        w[0]
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, w: &mut [u32; 1]) {
+        w[0] = self;
+    }
+
+    #[inline(always)]
+    unsafe fn splat(v: u32) -> Self {
+        v
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        self ^ rhs
+    }
+
+    #[inline(always)]
+    unsafe fn rotl(self, r: u32) -> Self {
+        self.rotate_left(r)
+    }
+
+    #[inline(always)]
+    unsafe fn tau(self) -> Self {
+        let m = transpose64(self.into());
+        let mut x: [u32; 8] = core::array::from_fn(|i| (m >> (8 * i)) as u32 & 0xff);
+        sbox(&mut x);
+        let m = x
+            .iter()
+            .enumerate()
+            .fold(0, |m, (i, &p)| m | (u64::from(p & 0xff) << (8 * i)));
+        transpose64(m) as u32
+    }
+}
+
+/// Transpose the 8x8 bit matrix whose row `i` is byte `i` of `x`.
+///
+/// The transposition is an involution.
+#[inline(always)]
+fn transpose64(mut x: u64) -> u64 {
+    for (shift, mask) in [
+        (7, 0x00aa_00aa_00aa_00aa),
+        (14, 0x0000_cccc_0000_cccc),
+        (28, 0x0000_0000_f0f0_f0f0),
+    ] {
+        let t = (x ^ (x >> shift)) & mask;
+        x ^= t ^ (t << shift);
+    }
+    x
+}
+
+/// Transpose the 8x8 bit matrices formed by the bytes at the same position of
+/// the eight words, so word `i` holds bit `i` of every byte.
+///
+/// The transposition is an involution.
+#[inline(always)]
+fn transpose(x: &mut [u32; 8]) {
+    for (shift, mask) in [(1, 0x55555555), (2, 0x33333333), (4, 0x0f0f0f0f)] {
+        for i in 0..8 {
+            if i & shift == 0 {
+                let t = ((x[i] >> shift) ^ x[i + shift]) & mask;
+                x[i + shift] ^= t;
+                x[i] ^= t << shift;
+            }
+        }
+    }
+}
+
+/// Bitsliced SM4 S-box, `x[i]` holds bit `i` of the bytes.
+///
+/// The circuit numbers the input bits `u0..u7` and the output bits `s0..s7`
+/// from the most significant one.
+#[inline(always)]
+fn sbox(x: &mut [u32; 8]) {
+    // Input affine map into the AES field
+    let u7 = !(x[1] ^ x[2]);
+    let u6 = !(x[0] ^ x[1] ^ x[2] ^ x[4]);
+    let u5 = x[1] ^ x[3];
+    let u4 = x[0] ^ x[2] ^ x[4] ^ x[5];
+    let u3 = x[1] ^ x[3] ^ x[4] ^ x[5];
+    let u2 = !(x[1] ^ x[4] ^ x[5] ^ x[6]);
+    let u1 = x[0] ^ x[1] ^ x[3] ^ x[4] ^ x[7];
+    let u0 = x[0] ^ x[2] ^ x[3];
+
+    let y14 = u3 ^ u5;
+    let y13 = u0 ^ u6;
+    let y12 = y13 ^ y14;
+    let t1 = u4 ^ y12;
+    let y15 = t1 ^ u5;
+    let t2 = y12 & y15;
+    let y6 = y15 ^ u7;
+    let y20 = t1 ^ u1;
+    let y9 = u0 ^ u3;
+    let y11 = y20 ^ y9;
+    let t12 = y9 & y11;
+    let y7 = u7 ^ y11;
+    let y8 = u0 ^ u5;
+    let t0 = u1 ^ u2;
+    let y10 = y15 ^ t0;
+    let y17 = y10 ^ y11;
+    let t13 = y14 & y17;
+    let t14 = t13 ^ t12;
+    let y19 = y10 ^ y8;
+    let t15 = y8 & y10;
+    let t16 = t15 ^ t12;
+    let y16 = t0 ^ y11;
+    let y21 = y13 ^ y16;
+    let t7 = y13 & y16;
+    let y18 = u0 ^ y16;
+    let y1 = t0 ^ u7;
+    let y4 = y1 ^ u3;
+    let t5 = y4 & u7;
+    let t6 = t5 ^ t2;
+    let t18 = t6 ^ t16;
+    let t22 = t18 ^ y19;
+    let y2 = y1 ^ u0;
+    let t10 = y2 & y7;
+    let t11 = t10 ^ t7;
+    let t20 = t11 ^ t16;
+    let t24 = t20 ^ y18;
+    let y5 = y1 ^ u6;
+    let t8 = y5 & y1;
+    let t9 = t8 ^ t7;
+    let t19 = t9 ^ t14;
+    let t23 = t19 ^ y21;
+    let y3 = y5 ^ y8;
+    let t3 = y3 & y6;
+    let t4 = t3 ^ t2;
+    let t17 = t4 ^ y20;
+    let t21 = t17 ^ t14;
+    let t26 = t21 & t23;
+    let t27 = t24 ^ t26;
+    let t31 = t22 ^ t26;
+    let t25 = t21 ^ t22;
+    let t28 = t25 & t27;
+    let t29 = t28 ^ t22;
+    let z14 = t29 & y2;
+    let z5 = t29 & y7;
+    let t30 = t23 ^ t24;
+    let t32 = t31 & t30;
+    let t33 = t32 ^ t24;
+    let t35 = t27 ^ t33;
+    let t36 = t24 & t35;
+    let t38 = t27 ^ t36;
+    let t39 = t29 & t38;
+    let t40 = t25 ^ t39;
+    let t43 = t29 ^ t40;
+    let z3 = t43 & y16;
+    let tc12 = z3 ^ z5;
+    let z12 = t43 & y13;
+    let z13 = t40 & y5;
+    let z4 = t40 & y1;
+    let tc6 = z3 ^ z4;
+    let t34 = t23 ^ t33;
+    let t37 = t36 ^ t34;
+    let t41 = t40 ^ t37;
+    let z8 = t41 & y10;
+    let z17 = t41 & y8;
+    let t44 = t33 ^ t37;
+    let z0 = t44 & y15;
+    let z9 = t44 & y12;
+    let z10 = t37 & y3;
+    let z1 = t37 & y6;
+    let tc5 = z1 ^ z0;
+    let tc11 = tc6 ^ tc5;
+    let z11 = t33 & y4;
+    let t42 = t29 ^ t33;
+    let t45 = t42 ^ t41;
+    let z7 = t45 & y17;
+    let tc8 = z7 ^ tc6;
+    let z16 = t45 & y14;
+    let z6 = t42 & y11;
+    let tc16 = z6 ^ tc8;
+    let z15 = t42 & y9;
+    let tc20 = z15 ^ tc16;
+    let tc1 = z15 ^ z16;
+    let tc2 = z10 ^ tc1;
+    let tc21 = tc2 ^ z11;
+    let tc3 = z9 ^ tc2;
+    let s0 = tc3 ^ tc16;
+    let s3 = tc3 ^ tc11;
+    let s1 = s3 ^ tc16;
+    let tc13 = z13 ^ tc1;
+    let z2 = t33 & u7;
+    let tc4 = z0 ^ z2;
+    let tc7 = z12 ^ tc4;
+    let tc9 = z8 ^ tc7;
+    let tc10 = tc8 ^ tc9;
+    let tc17 = z14 ^ tc10;
+    let s5 = tc21 ^ tc17;
+    let tc26 = tc17 ^ tc20;
+    let s2 = tc26 ^ z17;
+    let tc14 = tc4 ^ tc12;
+    let tc18 = tc13 ^ tc14;
+    let s6 = tc10 ^ tc18;
+    let s7 = z12 ^ tc18;
+    let s4 = tc14 ^ s3;
+
+    // Output affine map from the AES field
+    x[0] = !(s0 ^ s3 ^ s4 ^ s5);
+    x[1] = !(s2 ^ s3 ^ s4 ^ s6);
+    x[2] = s0 ^ s4 ^ s5;
+    x[3] = s0 ^ s1 ^ s5;
+    x[4] = !(s1 ^ s2 ^ s3 ^ s5);
+    x[5] = s0 ^ s1 ^ s6 ^ s7;
+    x[6] = !(s1 ^ s2 ^ s7);
+    x[7] = !(s0 ^ s2 ^ s4);
+}
+
+#[inline]
+fn t_prime(val: u32) -> u32 {
+    // SAFETY: the portable lanes use no target features
+    let b = unsafe { val.tau() };
+    // L': linear transformation of the key schedule
+    b ^ b.rotate_left(13) ^ b.rotate_left(23)
+}
+
+/// Expand the key words `mk` into round keys.
+pub(crate) fn expand_key(mk: &[u32; 4]) -> [u32; 32] {
+    let mut rk = [0u32; 32];
+    let mut k = [mk[0] ^ FK[0], mk[1] ^ FK[1], mk[2] ^ FK[2], mk[3] ^ FK[3]];
+
+    for i in 0..8 {
+        k[0] ^= t_prime(k[1] ^ k[2] ^ k[3] ^ CK[i * 4]);
+        k[1] ^= t_prime(k[2] ^ k[3] ^ k[0] ^ CK[i * 4 + 1]);
+        k[2] ^= t_prime(k[3] ^ k[0] ^ k[1] ^ CK[i * 4 + 2]);
+        k[3] ^= t_prime(k[0] ^ k[1] ^ k[2] ^ CK[i * 4 + 3]);
+
+        rk[i * 4] = k[0];
+        rk[i * 4 + 1] = k[1];
+        rk[i * 4 + 2] = k[2];
+        rk[i * 4 + 3] = k[3];
+    }
+
+    rk
+}
+
+fn crypt_par<const DEC: bool>(_: (), rk: &[u32; 32], blocks: &mut [[u32; 4]; 8]) {
+    // SAFETY: the portable lanes use no target features
+    unsafe { crypt::<[u32; 8], 8, DEC>(rk, blocks) }
+}
+
+fn crypt_block<const DEC: bool>(_: (), rk: &[u32; 32], block: &mut [u32; 4]) {
+    // SAFETY: the portable lanes use no target features
+    unsafe { crypt::<u32, 1, DEC>(rk, core::array::from_mut(block)) }
+}
+
+define_backend!(Sm4Soft, (), U8, crypt_par, crypt_block);
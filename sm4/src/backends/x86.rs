@@ -0,0 +1,107 @@
+//! x86 backends using AES-NI and GFNI for the S-box layer.
+//!
+//! The AES-NI backends evaluate the AES S-box with `AESENCLAST` and a zero
+//! round key. The instruction also applies `ShiftRows`, which is undone by
+//! shuffling the bytes with the inverse permutation in advance, and the affine
+//! maps are computed with `PSHUFB` lookups of the low and high nibbles.
+//!
+//! The GFNI backend computes the input affine map with `GF2P8AFFINEQB`, and the
+//! inversion in the AES field followed by the output affine map with
+//! `GF2P8AFFINEINVQB`.
+#![allow(unsafe_code)]
+
+use crate::Backend;
+use cipher::{BlockCipherDecClosure, BlockCipherEncClosure, consts::U16};
+
+mod aesni;
+mod avx2;
+#[cfg(sm4_gfni)]
+mod gfni;
+
+#[derive(Clone, Copy)]
+pub(crate) enum Imp {
+    AesNi(aesni::Token),
+    Avx2(avx2::Token),
+    #[cfg(sm4_gfni)]
+    Gfni(gfni::Token),
+}
+
+impl Imp {
+    pub(crate) fn detect() -> Option<Self> {
+        #[cfg(sm4_gfni)]
+        if let Some(token) = gfni::Token::get() {
+            return Some(Self::Gfni(token));
+        }
+        if let Some(token) = avx2::Token::get() {
+            return Some(Self::Avx2(token));
+        }
+        aesni::Token::get().map(Self::AesNi)
+    }
+
+    pub(crate) fn backend(self) -> Backend {
+        match self {
+            Self::AesNi(_) => Backend::AesNi,
+            Self::Avx2(_) => Backend::Avx2,
+            #[cfg(sm4_gfni)]
+            Self::Gfni(_) => Backend::Gfni,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn encrypt(self, rk: &[u32; 32], f: impl BlockCipherEncClosure<BlockSize = U16>) {
+        match self {
+            Self::AesNi(token) => f.call(&aesni::Sm4AesNi::<false> { rk, token }),
+            Self::Avx2(token) => f.call(&avx2::Sm4Avx2::<false> { rk, token }),
+            #[cfg(sm4_gfni)]
+            Self::Gfni(token) => f.call(&gfni::Sm4Gfni::<false> { rk, token }),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn decrypt(self, rk: &[u32; 32], f: impl BlockCipherDecClosure<BlockSize = U16>) {
+        match self {
+            Self::AesNi(token) => f.call(&aesni::Sm4AesNi::<true> { rk, token }),
+            Self::Avx2(token) => f.call(&avx2::Sm4Avx2::<true> { rk, token }),
+            #[cfg(sm4_gfni)]
+            Self::Gfni(token) => f.call(&gfni::Sm4Gfni::<true> { rk, token }),
+        }
+    }
+}
+
+/// Lookups of the low and high nibbles of a byte for the map into the AES
+/// field `A_in(x) = M·x ⊕ 0x23`.
+const IN_LO: [u8; 16] = [
+    0x23, 0xe9, 0x54, 0x9e, 0xa8, 0x62, 0xdf, 0x15, 0xf7, 0x3d, 0x80, 0x4a, 0x7c, 0xb6, 0x0b, 0xc1,
+];
+const IN_HI: [u8; 16] = [
+    0x00, 0x7a, 0x38, 0x42, 0x20, 0x5a, 0x18, 0x62, 0x40, 0x3a, 0x78, 0x02, 0x60, 0x1a, 0x58, 0x22,
+];
+
+/// Lookups of the low and high nibbles of a byte for the map from the output
+/// of the AES S-box.
+const OUT_LO: [u8; 16] = [
+    0x3b, 0x5b, 0x19, 0x79, 0x26, 0x46, 0x04, 0x64, 0xbc, 0xdc, 0x9e, 0xfe, 0xa1, 0xc1, 0x83, 0xe3,
+];
+const OUT_HI: [u8; 16] = [
+    0x00, 0x13, 0xd2, 0xc1, 0x78, 0x6b, 0xaa, 0xb9, 0xad, 0xbe, 0x7f, 0x6c, 0xd5, 0xc6, 0x07, 0x14,
+];
+
+/// Byte permutation which is undone by `ShiftRows`.
+const INV_SHIFT_ROWS: [u8; 16] = [0, 13, 10, 7, 4, 1, 14, 11, 8, 5, 2, 15, 12, 9, 6, 3];
+
+/// Byte permutations rotating 32-bit lanes left by 8, 16 and 24 bits.
+const ROTL8: [u8; 16] = [3, 0, 1, 2, 7, 4, 5, 6, 11, 8, 9, 10, 15, 12, 13, 14];
+const ROTL16: [u8; 16] = [2, 3, 0, 1, 6, 7, 4, 5, 10, 11, 8, 9, 14, 15, 12, 13];
+const ROTL24: [u8; 16] = [1, 2, 3, 0, 5, 6, 7, 4, 9, 10, 11, 8, 13, 14, 15, 12];
+
+/// Byte permutation rotating 32-bit lanes left by `r` bits, if `r` is a
+/// multiple of 8.
+#[inline(always)]
+const fn rotl_bytes(r: u32) -> Option<&'static [u8; 16]> {
+    match r {
+        8 => Some(&ROTL8),
+        16 => Some(&ROTL16),
+        24 => Some(&ROTL24),
+        _ => None,
+    }
+}
@@ -0,0 +1,122 @@
+//! AES-NI backend which processes four blocks using 128-bit vectors.
+//!
+//! The methods of `U32x4` are compiled with AES-NI and SSSE3 enabled, and the
+//! round functions using them are only called with a [`Token`] proving that
+//! the CPU supports them.
+#![allow(unsafe_op_in_unsafe_fn)]
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::{IN_HI, IN_LO, INV_SHIFT_ROWS, OUT_HI, OUT_LO, rotl_bytes};
+use crate::backends::{Lanes, crypt, define_backend};
+use cipher::consts::U4;
+
+cpufeatures::new!(aesni_cpuid, "aes", "ssse3");
+
+/// Proof that AES-NI and SSSE3 are supported by the current CPU.
+#[derive(Clone, Copy)]
+pub(crate) struct Token(());
+
+impl Token {
+    #[inline(always)]
+    pub(crate) fn get() -> Option<Self> {
+        aesni_cpuid::get().then_some(Token(()))
+    }
+}
+
+/// Four 32-bit lanes.
+#[derive(Clone, Copy)]
+struct U32x4(__m128i);
+
+#[inline]
+#[target_feature(enable = "aes,ssse3")]
+unsafe fn load128(bytes: &[u8; 16]) -> __m128i {
+    _mm_loadu_si128(bytes.as_ptr().cast())
+}
+
+/// Apply the affine map given by nibble lookup tables `lo` and `hi`.
+#[inline]
+#[target_feature(enable = "aes,ssse3")]
+unsafe fn affine(x: __m128i, lo: &[u8; 16], hi: &[u8; 16]) -> __m128i {
+    let mask = _mm_set1_epi8(0x0f);
+    let l = _mm_and_si128(x, mask);
+    let h = _mm_and_si128(_mm_srli_epi32::<4>(x), mask);
+    _mm_xor_si128(
+        _mm_shuffle_epi8(load128(lo), l),
+        _mm_shuffle_epi8(load128(hi), h),
+    )
+}
+
+impl Lanes<4> for U32x4 {
+    #[inline]
+    #[target_feature(enable = "aes,ssse3")]
+    unsafe fn load(w: &[u32; 4]) -> Self {
+        Self(_mm_loadu_si128(w.as_ptr().cast()))
+    }
+
+    #[inline]
+    #[target_feature(enable = "aes,ssse3")]
+    unsafe fn store(self, w: &mut [u32; 4]) {
+        _mm_storeu_si128(w.as_mut_ptr().cast(), self.0)
+    }
+
+    #[inline]
+    #[target_feature(enable = "aes,ssse3")]
+    unsafe fn splat(v: u32) -> Self {
+        Self(_mm_set1_epi32(v as i32))
+    }
+
+    #[inline]
+    #[target_feature(enable = "aes,ssse3")]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        Self(_mm_xor_si128(self.0, rhs.0))
+    }
+
+    #[inline]
+    #[target_feature(enable = "aes,ssse3")]
+    unsafe fn rotl(self, r: u32) -> Self {
+        if let Some(mask) = rotl_bytes(r) {
+            return Self(_mm_shuffle_epi8(self.0, load128(mask)));
+        }
+        let l = _mm_cvtsi32_si128(r as i32);
+        let r = _mm_cvtsi32_si128(32 - r as i32);
+        Self(_mm_or_si128(
+            _mm_sll_epi32(self.0, l),
+            _mm_srl_epi32(self.0, r),
+        ))
+    }
+
+    #[inline]
+    #[target_feature(enable = "aes,ssse3")]
+    unsafe fn tau(self) -> Self {
+        let x = affine(self.0, &IN_LO, &IN_HI);
+        let x = _mm_shuffle_epi8(x, load128(&INV_SHIFT_ROWS));
+        let x = _mm_aesenclast_si128(x, _mm_setzero_si128());
+        Self(affine(x, &OUT_LO, &OUT_HI))
+    }
+}
+
+#[target_feature(enable = "aes,ssse3")]
+pub(super) unsafe fn crypt4<const DEC: bool>(rk: &[u32; 32], blocks: &mut [[u32; 4]; 4]) {
+    // SAFETY: AES-NI and SSSE3 are enabled for this function
+    unsafe { crypt::<U32x4, 4, DEC>(rk, blocks) }
+}
+
+#[inline(always)]
+fn crypt_par<const DEC: bool>(_token: Token, rk: &[u32; 32], blocks: &mut [[u32; 4]; 4]) {
+    // SAFETY: `Token` can only be obtained if AES-NI and SSSE3 are available
+    unsafe { crypt4::<DEC>(rk, blocks) }
+}
+
+#[inline(always)]
+fn crypt_block<const DEC: bool>(token: Token, rk: &[u32; 32], block: &mut [u32; 4]) {
+    let mut blocks = [[0; 4]; 4];
+    blocks[0] = *block;
+    crypt_par::<DEC>(token, rk, &mut blocks);
+    *block = blocks[0];
+}
+
+define_backend!(Sm4AesNi, Token, U4, crypt_par, crypt_block);
@@ -0,0 +1,130 @@
+//! AVX2 backend which processes eight blocks using 256-bit vectors.
+//!
+//! `AESENCLAST` is applied to both 128-bit halves separately, so the backend
+//! does not require VAES.
+//!
+//! The methods of `U32x8` are compiled with AVX2 and AES-NI enabled, and the
+//! round functions using them are only called with a [`Token`] proving that
+//! the CPU supports them.
+#![allow(unsafe_op_in_unsafe_fn)]
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::{IN_HI, IN_LO, INV_SHIFT_ROWS, OUT_HI, OUT_LO, aesni, rotl_bytes};
+use crate::backends::{Lanes, crypt, define_backend};
+use cipher::consts::U8;
+
+cpufeatures::new!(avx2_cpuid, "avx2", "aes");
+
+/// Proof that AVX2 and AES-NI are supported by the current CPU.
+#[derive(Clone, Copy)]
+pub(crate) struct Token(());
+
+impl Token {
+    #[inline(always)]
+    pub(crate) fn get() -> Option<Self> {
+        avx2_cpuid::get().then_some(Token(()))
+    }
+}
+
+/// Eight 32-bit lanes.
+#[derive(Clone, Copy)]
+struct U32x8(__m256i);
+
+#[inline]
+#[target_feature(enable = "avx2,aes")]
+unsafe fn load256(bytes: &[u8; 16]) -> __m256i {
+    _mm256_broadcastsi128_si256(_mm_loadu_si128(bytes.as_ptr().cast()))
+}
+
+/// Apply the affine map given by nibble lookup tables `lo` and `hi`.
+#[inline]
+#[target_feature(enable = "avx2,aes")]
+unsafe fn affine(x: __m256i, lo: &[u8; 16], hi: &[u8; 16]) -> __m256i {
+    let mask = _mm256_set1_epi8(0x0f);
+    let l = _mm256_and_si256(x, mask);
+    let h = _mm256_and_si256(_mm256_srli_epi32::<4>(x), mask);
+    _mm256_xor_si256(
+        _mm256_shuffle_epi8(load256(lo), l),
+        _mm256_shuffle_epi8(load256(hi), h),
+    )
+}
+
+impl Lanes<8> for U32x8 {
+    #[inline]
+    #[target_feature(enable = "avx2,aes")]
+    unsafe fn load(w: &[u32; 8]) -> Self {
+        Self(_mm256_loadu_si256(w.as_ptr().cast()))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,aes")]
+    unsafe fn store(self, w: &mut [u32; 8]) {
+        _mm256_storeu_si256(w.as_mut_ptr().cast(), self.0)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,aes")]
+    unsafe fn splat(v: u32) -> Self {
+        Self(_mm256_set1_epi32(v as i32))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,aes")]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        Self(_mm256_xor_si256(self.0, rhs.0))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,aes")]
+    unsafe fn rotl(self, r: u32) -> Self {
+        if let Some(mask) = rotl_bytes(r) {
+            return Self(_mm256_shuffle_epi8(self.0, load256(mask)));
+        }
+        let l = _mm_cvtsi32_si128(r as i32);
+        let r = _mm_cvtsi32_si128(32 - r as i32);
+        Self(_mm256_or_si256(
+            _mm256_sll_epi32(self.0, l),
+            _mm256_srl_epi32(self.0, r),
+        ))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,aes")]
+    unsafe fn tau(self) -> Self {
+        let x = affine(self.0, &IN_LO, &IN_HI);
+        let x = _mm256_shuffle_epi8(x, load256(&INV_SHIFT_ROWS));
+        let zero = _mm_setzero_si128();
+        let lo = _mm_aesenclast_si128(_mm256_castsi256_si128(x), zero);
+        let hi = _mm_aesenclast_si128(_mm256_extracti128_si256::<1>(x), zero);
+        Self(affine(_mm256_set_m128i(hi, lo), &OUT_LO, &OUT_HI))
+    }
+}
+
+#[target_feature(enable = "avx2,aes")]
+unsafe fn crypt8<const DEC: bool>(rk: &[u32; 32], blocks: &mut [[u32; 4]; 8]) {
+    // SAFETY: AVX2 and AES-NI are enabled for this function
+    unsafe { crypt::<U32x8, 8, DEC>(rk, blocks) }
+}
+
+#[inline(always)]
+fn crypt_par<const DEC: bool>(_token: Token, rk: &[u32; 32], blocks: &mut [[u32; 4]; 8]) {
+    // SAFETY: `Token` can only be obtained if AVX2 and AES-NI are available
+    unsafe { crypt8::<DEC>(rk, blocks) }
+}
+
+/// Single blocks are processed by the four-lane AES-NI implementation.
+#[inline(always)]
+fn crypt_block<const DEC: bool>(_token: Token, rk: &[u32; 32], block: &mut [u32; 4]) {
+    let mut blocks = [[0; 4]; 4];
+    blocks[0] = *block;
+    // SAFETY: `Token` can only be obtained if AVX2 and AES-NI are available,
+    // and AVX2 implies SSSE3
+    unsafe { aesni::crypt4::<DEC>(rk, &mut blocks) }
+    *block = blocks[0];
+}
+
+define_backend!(Sm4Avx2, Token, U8, crypt_par, crypt_block);
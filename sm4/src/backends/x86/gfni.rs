@@ -0,0 +1,164 @@
+//! GFNI backend which processes sixteen blocks using pairs of 256-bit
+//! vectors.
+//!
+//! The GFNI intrinsics require Rust 1.89, so the backend is only compiled with
+//! the `sm4_gfni` configuration flag.
+//!
+//! The methods of `U32x8` are compiled with AVX2 and GFNI enabled, and the
+//! round functions using them are only called with a [`Token`] proving that
+//! the CPU supports them.
+#![allow(clippy::incompatible_msrv, unsafe_op_in_unsafe_fn)]
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::rotl_bytes;
+use crate::backends::{Lanes, crypt, define_backend};
+use cipher::consts::U16;
+
+cpufeatures::new!(gfni_cpuid, "avx2", "gfni");
+
+/// Proof that AVX2 and GFNI are supported by the current CPU.
+#[derive(Clone, Copy)]
+pub(crate) struct Token(());
+
+impl Token {
+    #[inline(always)]
+    pub(crate) fn get() -> Option<Self> {
+        gfni_cpuid::get().then_some(Token(()))
+    }
+}
+
+/// Matrix of the map into the AES field in the `GF2P8AFFINEQB` format, the
+/// map adds the constant `0x23`.
+const IN_MATRIX: i64 = 0x0617_0a35_3a72_9b0d;
+const IN_CONST: i32 = 0x23;
+/// Matrix of the map applied to the inverse in the AES field in the
+/// `GF2P8AFFINEINVQB` format, the map adds the constant `0xd3`.
+const OUT_MATRIX: i64 = 0xaf4d_b043_9a96_b349_u64 as i64;
+const OUT_CONST: i32 = 0xd3;
+
+/// Eight 32-bit lanes.
+#[derive(Clone, Copy)]
+struct U32x8(__m256i);
+
+impl Lanes<8> for U32x8 {
+    #[inline]
+    #[target_feature(enable = "avx2,gfni")]
+    unsafe fn load(w: &[u32; 8]) -> Self {
+        Self(_mm256_loadu_si256(w.as_ptr().cast()))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,gfni")]
+    unsafe fn store(self, w: &mut [u32; 8]) {
+        _mm256_storeu_si256(w.as_mut_ptr().cast(), self.0)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,gfni")]
+    unsafe fn splat(v: u32) -> Self {
+        Self(_mm256_set1_epi32(v as i32))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,gfni")]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        Self(_mm256_xor_si256(self.0, rhs.0))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,gfni")]
+    unsafe fn rotl(self, r: u32) -> Self {
+        if let Some(mask) = rotl_bytes(r) {
+            let mask = _mm256_broadcastsi128_si256(_mm_loadu_si128(mask.as_ptr().cast()));
+            return Self(_mm256_shuffle_epi8(self.0, mask));
+        }
+        let l = _mm_cvtsi32_si128(r as i32);
+        let r = _mm_cvtsi32_si128(32 - r as i32);
+        Self(_mm256_or_si256(
+            _mm256_sll_epi32(self.0, l),
+            _mm256_srl_epi32(self.0, r),
+        ))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,gfni")]
+    unsafe fn tau(self) -> Self {
+        let x = _mm256_gf2p8affine_epi64_epi8::<IN_CONST>(self.0, _mm256_set1_epi64x(IN_MATRIX));
+        Self(_mm256_gf2p8affineinv_epi64_epi8::<OUT_CONST>(
+            x,
+            _mm256_set1_epi64x(OUT_MATRIX),
+        ))
+    }
+}
+
+/// Sixteen lanes held in two vectors, which are processed independently.
+impl Lanes<16> for [U32x8; 2] {
+    #[inline(always)]
+    unsafe fn load(w: &[u32; 16]) -> Self {
+        let (a, b) = w.split_at(8);
+        [
+            U32x8::load(a.try_into().unwrap()),
+            U32x8::load(b.try_into().unwrap()),
+        ]
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, w: &mut [u32; 16]) {
+        let (a, b) = w.split_at_mut(8);
+        self[0].store(a.try_into().unwrap());
+        self[1].store(b.try_into().unwrap());
+    }
+
+    #[inline(always)]
+    unsafe fn splat(v: u32) -> Self {
+        [U32x8::splat(v); 2]
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        [self[0].xor(rhs[0]), self[1].xor(rhs[1])]
+    }
+
+    #[inline(always)]
+    unsafe fn rotl(self, r: u32) -> Self {
+        [self[0].rotl(r), self[1].rotl(r)]
+    }
+
+    #[inline(always)]
+    unsafe fn tau(self) -> Self {
+        [self[0].tau(), self[1].tau()]
+    }
+}
+
+#[target_feature(enable = "avx2,gfni")]
+unsafe fn crypt8<const DEC: bool>(rk: &[u32; 32], blocks: &mut [[u32; 4]; 8]) {
+    // SAFETY: AVX2 and GFNI are enabled for this function
+    unsafe { crypt::<U32x8, 8, DEC>(rk, blocks) }
+}
+
+#[target_feature(enable = "avx2,gfni")]
+unsafe fn crypt16<const DEC: bool>(rk: &[u32; 32], blocks: &mut [[u32; 4]; 16]) {
+    // SAFETY: AVX2 and GFNI are enabled for this function
+    unsafe { crypt::<[U32x8; 2], 16, DEC>(rk, blocks) }
+}
+
+#[inline(always)]
+fn crypt_par<const DEC: bool>(_token: Token, rk: &[u32; 32], blocks: &mut [[u32; 4]; 16]) {
+    // SAFETY: `Token` can only be obtained if AVX2 and GFNI are available
+    unsafe { crypt16::<DEC>(rk, blocks) }
+}
+
+#[inline(always)]
+fn crypt_block<const DEC: bool>(_token: Token, rk: &[u32; 32], block: &mut [u32; 4]) {
+    let mut blocks = [[0; 4]; 8];
+    blocks[0] = *block;
+    // SAFETY: `Token` can only be obtained if AVX2 and GFNI are available
+    unsafe { crypt8::<DEC>(rk, &mut blocks) }
+    *block = blocks[0];
+}
+
+define_backend!(Sm4Gfni, Token, U16, crypt_par, crypt_block);
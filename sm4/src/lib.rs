@@ -8,6 +8,48 @@
 //!
 //! USE AT YOUR OWN RISK!
 //!
+//! # Backends
+//!
+//! All backends evaluate the SM4 S-box in constant time. It is affine-equivalent
+//! to the AES S-box, which allows the x86 and portable backends to compute it
+//! without lookup tables.
+//!
+//! On x86 and x86-64 targets the backend is selected at runtime. CPUs
+//! supporting AVX2 and GFNI process sixteen blocks in parallel with
+//! `GF2P8AFFINEQB` and `GF2P8AFFINEINVQB` if the backend is enabled with the
+//! `sm4_gfni` flag, CPUs supporting AVX2 and AES-NI process eight blocks in
+//! parallel with `AESENCLAST`, and CPUs supporting AES-NI and SSSE3 process
+//! four blocks.
+//!
+//! On aarch64 targets CPUs supporting the `SM4E` and `SM4EKEY` instructions
+//! of the ARMv8 Cryptographic Extension use them for encryption and key
+//! expansion, other CPUs process eight blocks in parallel with NEON and look
+//! up the S-box held in vector registers with the `TBL` instruction.
+//!
+//! Other targets use a portable implementation with a bitsliced S-box which
+//! processes eight blocks in parallel.
+//!
+//! The [`backend`] function returns the backend selected on the current CPU,
+//! and [`Sm4::backend`] returns the backend used by an instance:
+//!
+//! ```
+//! use sm4::{Sm4, cipher::KeyInit};
+//!
+//! let cipher = Sm4::new(&[0x42; 16].into());
+//! assert_eq!(cipher.backend(), sm4::backend());
+//! ```
+//!
+//! # Configuration Flags
+//!
+//! You can force the portable backend using the `sm4_backend` configuration
+//! flag set to `soft`. The `sm4_gfni` flag builds the GFNI backend on x86
+//! targets, it requires Rust 1.89 or later and is not built by default, so
+//! without the flag CPUs supporting GFNI use the AVX2 backend.
+//!
+//! The flags can be enabled using `RUSTFLAGS` environment variable
+//! (e.g. `RUSTFLAGS='--cfg sm4_backend="soft"'`) or by modifying
+//! `.cargo/config`.
+//!
 //! [SM4]: https://en.wikipedia.org/wiki/SM4_(cipher)
 
 #![no_std]
@@ -22,54 +64,51 @@
 pub use cipher;
 
 use cipher::{
-    AlgorithmName, Block, BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherDecrypt,
-    BlockCipherEncBackend, BlockCipherEncClosure, BlockCipherEncrypt, BlockSizeUser, InOut, Key,
-    KeyInit, KeySizeUser, ParBlocksSizeUser,
-    consts::{U1, U16},
+    AlgorithmName, BlockCipherDecClosure, BlockCipherDecrypt, BlockCipherEncClosure,
+    BlockCipherEncrypt, BlockSizeUser, Key, KeyInit, KeySizeUser, consts::U16,
 };
 use core::fmt;
 
 #[cfg(feature = "zeroize")]
 use cipher::zeroize::{Zeroize, ZeroizeOnDrop};
 
+mod backends;
 mod consts;
-use consts::{CK, FK, SBOX};
-
-#[inline]
-fn tau(a: u32) -> u32 {
-    let mut buf = a.to_be_bytes();
-    buf[0] = SBOX[buf[0] as usize];
-    buf[1] = SBOX[buf[1] as usize];
-    buf[2] = SBOX[buf[2] as usize];
-    buf[3] = SBOX[buf[3] as usize];
-    u32::from_be_bytes(buf)
-}
-
-/// L: linear transformation
-#[inline]
-fn el(b: u32) -> u32 {
-    b ^ b.rotate_left(2) ^ b.rotate_left(10) ^ b.rotate_left(18) ^ b.rotate_left(24)
-}
-
-#[inline]
-fn el_prime(b: u32) -> u32 {
-    b ^ b.rotate_left(13) ^ b.rotate_left(23)
-}
-
-#[inline]
-fn t(val: u32) -> u32 {
-    el(tau(val))
-}
 
-#[inline]
-fn t_prime(val: u32) -> u32 {
-    el_prime(tau(val))
+use backends::Imp;
+
+/// SM4 implementation used by a cipher instance.
+///
+/// See the [crate documentation][crate#backends] for details on how the
+/// backend is selected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// Portable constant-time implementation with a bitsliced S-box.
+    Soft,
+    /// AES-NI implementation processing four blocks in parallel.
+    AesNi,
+    /// AVX2 and AES-NI implementation processing eight blocks in parallel.
+    Avx2,
+    /// AVX2 and GFNI implementation processing sixteen blocks in parallel.
+    Gfni,
+    /// NEON implementation processing eight blocks in parallel.
+    Neon,
+    /// ARMv8 SM4 instructions implementation processing eight blocks in
+    /// parallel.
+    Sm4Ce,
+}
+
+/// Returns the backend used by ciphers initialized on the current CPU.
+pub fn backend() -> Backend {
+    Imp::detect().backend()
 }
 
 /// SM4 block cipher.
 #[derive(Clone)]
 pub struct Sm4 {
     rk: [u32; 32],
+    imp: Imp,
 }
 
 impl KeySizeUser for Sm4 {
@@ -84,22 +123,11 @@ impl KeyInit for Sm4 {
             u32::from_be_bytes(key[8..12].try_into().unwrap()),
             u32::from_be_bytes(key[12..16].try_into().unwrap()),
         ];
-        let mut rk = [0u32; 32];
-        let mut k = [mk[0] ^ FK[0], mk[1] ^ FK[1], mk[2] ^ FK[2], mk[3] ^ FK[3]];
-
-        for i in 0..8 {
-            k[0] ^= t_prime(k[1] ^ k[2] ^ k[3] ^ CK[i * 4]);
-            k[1] ^= t_prime(k[2] ^ k[3] ^ k[0] ^ CK[i * 4 + 1]);
-            k[2] ^= t_prime(k[3] ^ k[0] ^ k[1] ^ CK[i * 4 + 2]);
-            k[3] ^= t_prime(k[0] ^ k[1] ^ k[2] ^ CK[i * 4 + 3]);
-
-            rk[i * 4] = k[0];
-            rk[i * 4 + 1] = k[1];
-            rk[i * 4 + 2] = k[2];
-            rk[i * 4 + 3] = k[3];
+        let imp = Imp::detect();
+        Sm4 {
+            rk: imp.expand_key(&mk),
+            imp,
         }
-
-        Sm4 { rk }
     }
 }
 
@@ -107,75 +135,24 @@ impl BlockSizeUser for Sm4 {
     type BlockSize = U16;
 }
 
-impl ParBlocksSizeUser for Sm4 {
-    type ParBlocksSize = U1;
-}
-
 impl BlockCipherEncrypt for Sm4 {
     #[inline]
     fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = Self::BlockSize>) {
-        f.call(self)
-    }
-}
-
-impl BlockCipherEncBackend for Sm4 {
-    #[inline]
-    fn encrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
-        let b = block.get_in();
-        let mut x = [
-            u32::from_be_bytes(b[0..4].try_into().unwrap()),
-            u32::from_be_bytes(b[4..8].try_into().unwrap()),
-            u32::from_be_bytes(b[8..12].try_into().unwrap()),
-            u32::from_be_bytes(b[12..16].try_into().unwrap()),
-        ];
-
-        let rk = &self.rk;
-        for i in 0..8 {
-            x[0] ^= t(x[1] ^ x[2] ^ x[3] ^ rk[i * 4]);
-            x[1] ^= t(x[2] ^ x[3] ^ x[0] ^ rk[i * 4 + 1]);
-            x[2] ^= t(x[3] ^ x[0] ^ x[1] ^ rk[i * 4 + 2]);
-            x[3] ^= t(x[0] ^ x[1] ^ x[2] ^ rk[i * 4 + 3]);
-        }
-
-        let block = block.get_out();
-        block[0..4].copy_from_slice(&x[3].to_be_bytes());
-        block[4..8].copy_from_slice(&x[2].to_be_bytes());
-        block[8..12].copy_from_slice(&x[1].to_be_bytes());
-        block[12..16].copy_from_slice(&x[0].to_be_bytes());
+        self.imp.encrypt(&self.rk, f)
     }
 }
 
 impl BlockCipherDecrypt for Sm4 {
     #[inline]
     fn decrypt_with_backend(&self, f: impl BlockCipherDecClosure<BlockSize = Self::BlockSize>) {
-        f.call(self)
+        self.imp.decrypt(&self.rk, f)
     }
 }
 
-impl BlockCipherDecBackend for Sm4 {
-    #[inline]
-    fn decrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
-        let b = block.get_in();
-        let mut x = [
-            u32::from_be_bytes(b[0..4].try_into().unwrap()),
-            u32::from_be_bytes(b[4..8].try_into().unwrap()),
-            u32::from_be_bytes(b[8..12].try_into().unwrap()),
-            u32::from_be_bytes(b[12..16].try_into().unwrap()),
-        ];
-
-        let rk = &self.rk;
-        for i in 0..8 {
-            x[0] ^= t(x[1] ^ x[2] ^ x[3] ^ rk[31 - i * 4]);
-            x[1] ^= t(x[2] ^ x[3] ^ x[0] ^ rk[31 - (i * 4 + 1)]);
-            x[2] ^= t(x[3] ^ x[0] ^ x[1] ^ rk[31 - (i * 4 + 2)]);
-            x[3] ^= t(x[0] ^ x[1] ^ x[2] ^ rk[31 - (i * 4 + 3)]);
-        }
-
-        let block = block.get_out();
-        block[0..4].copy_from_slice(&x[3].to_be_bytes());
-        block[4..8].copy_from_slice(&x[2].to_be_bytes());
-        block[8..12].copy_from_slice(&x[1].to_be_bytes());
-        block[12..16].copy_from_slice(&x[0].to_be_bytes());
+impl Sm4 {
+    /// Returns the backend used by this instance.
+    pub fn backend(&self) -> Backend {
+        self.imp.backend()
     }
 }
 
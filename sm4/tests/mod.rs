@@ -1,8 +1,8 @@
 //! Test vectors are from GM/T 0002-2012
 
-use cipher::{BlockCipherDecrypt, BlockCipherEncrypt, KeyInit};
+use cipher::{Array, BlockCipherDecrypt, BlockCipherEncrypt, KeyInit};
 use hex_literal::hex;
-use sm4::Sm4;
+use sm4::{Backend, Sm4};
 
 #[test]
 fn sm4_example_1() {
@@ -39,3 +39,31 @@ fn sm4_example_2() {
     }
     assert_eq!(&plaintext, block.as_slice());
 }
+
+/// Check the parallel path of the detected backend against single blocks, and
+/// the example of GM/T 0002-2012 at the start and the end of a batch.
+#[test]
+fn sm4_par() {
+    let key = hex!("0123456789abcdeffedcba9876543210");
+    let cipher = Sm4::new(&key.into());
+
+    let par = match sm4::backend() {
+        Backend::AesNi => 4,
+        Backend::Gfni => 16,
+        _ => 8,
+    };
+    par_blocks_test::check_par_blocks(&cipher, par);
+
+    let mut blocks = vec![Array::from(key); par];
+    cipher.encrypt_blocks(&mut blocks);
+    let ciphertext = hex!("681EDF34D206965E86B3E94F536E4246");
+    assert_eq!(blocks[0], ciphertext);
+    assert_eq!(blocks[par - 1], ciphertext);
+}
+
+#[test]
+fn backend() {
+    let cipher = Sm4::new(&[0x42; 16].into());
+    assert_eq!(cipher.backend(), sm4::backend());
+    assert_eq!(cipher.clone().backend(), sm4::backend());
+}
@@ -0,0 +1,41 @@
+#![feature(test)]
+extern crate test;
+
+use cipher::{block_decryptor_bench, block_encryptor_bench};
+use speck_cipher::{Speck64_128, Speck128_128, Speck128_256};
+
+// `*_block` benches measure the scalar single-block path and `*_blocks` the
+// multi-block backend.
+
+block_encryptor_bench!(
+    Key: Speck64_128,
+    speck64_128_encrypt_block,
+    speck64_128_encrypt_blocks,
+);
+block_decryptor_bench!(
+    Key: Speck64_128,
+    speck64_128_decrypt_block,
+    speck64_128_decrypt_blocks,
+);
+
+block_encryptor_bench!(
+    Key: Speck128_128,
+    speck128_128_encrypt_block,
+    speck128_128_encrypt_blocks,
+);
+block_decryptor_bench!(
+    Key: Speck128_128,
+    speck128_128_decrypt_block,
+    speck128_128_decrypt_blocks,
+);
+
+block_encryptor_bench!(
+    Key: Speck128_256,
+    speck128_256_encrypt_block,
+    speck128_256_encrypt_blocks,
+);
+block_decryptor_bench!(
+    Key: Speck128_256,
+    speck128_256_decrypt_block,
+    speck128_256_decrypt_blocks,
+);
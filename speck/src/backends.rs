@@ -0,0 +1,226 @@
+//! Multi-block Speck64 and Speck128 backends.
+//!
+//! Blocks are processed in a word-sliced layout: lane `k` of vector `x` holds
+//! word `x` of block `k` and likewise for `y`, so every round operates on all
+//! blocks at once. Both block sizes use rotation amounts `alpha = 8` and
+//! `beta = 3`.
+#![allow(unsafe_code)]
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    not(speck_backend = "soft")
+))]
+mod avx2;
+#[cfg(all(
+    target_arch = "aarch64",
+    target_feature = "neon",
+    not(speck_backend = "soft")
+))]
+mod neon;
+
+const ALPHA: u32 = 8;
+const BETA: u32 = 3;
+
+/// Number of Speck64 blocks processed in parallel.
+pub(crate) const PAR64: usize = 8;
+/// Number of Speck128 blocks processed in parallel.
+pub(crate) const PAR128: usize = 4;
+
+/// Vector of `P` lanes of word type `W`.
+///
+/// The methods of SIMD implementations are compiled with the target features
+/// of their intrinsics, so callers must ensure that the CPU supports them.
+pub(crate) trait Lanes<W, const P: usize>: Copy {
+    unsafe fn load(w: &[W; P]) -> Self;
+    unsafe fn store(self, w: &mut [W; P]);
+    unsafe fn splat(v: W) -> Self;
+    unsafe fn add(self, rhs: Self) -> Self;
+    unsafe fn sub(self, rhs: Self) -> Self;
+    unsafe fn xor(self, rhs: Self) -> Self;
+    unsafe fn rotl(self, r: u32) -> Self;
+    unsafe fn rotr(self, r: u32) -> Self;
+}
+
+macro_rules! impl_soft_lanes {
+    ($word:ty, $par:expr) => {
+        impl Lanes<$word, $par> for [$word; $par] {
+            #[inline(always)]
+            unsafe fn load(w: &[$word; $par]) -> Self {
+                *w
+            }
+
+            #[inline(always)]
+            unsafe fn store(self, w: &mut [$word; $par]) {
+                *w = self;
+            }
+
+            #[inline(always)]
+            unsafe fn splat(v: $word) -> Self {
+                [v; $par]
+            }
+
+            #[inline(always)]
+            unsafe fn add(self, rhs: Self) -> Self {
+                core::array::from_fn(|k| self[k].wrapping_add(rhs[k]))
+            }
+
+            #[inline(always)]
+            unsafe fn sub(self, rhs: Self) -> Self {
+                core::array::from_fn(|k| self[k].wrapping_sub(rhs[k]))
+            }
+
+            #[inline(always)]
+            unsafe fn xor(self, rhs: Self) -> Self {
+                core::array::from_fn(|k| self[k] ^ rhs[k])
+            }
+
+            #[inline(always)]
+            unsafe fn rotl(self, r: u32) -> Self {
+                self.map(|v| v.rotate_left(r))
+            }
+
+            #[inline(always)]
+            unsafe fn rotr(self, r: u32) -> Self {
+                self.map(|v| v.rotate_right(r))
+            }
+        }
+    };
+}
+
+impl_soft_lanes!(u32, PAR64);
+impl_soft_lanes!(u64, PAR128);
+
+/// Transpose `blocks` into the word-sliced layout.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+unsafe fn load<W: Copy + Default, V: Lanes<W, P>, const P: usize>(blocks: &[[W; 2]; P]) -> (V, V) {
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        let mut x = [W::default(); P];
+        let mut y = [W::default(); P];
+        for ((x, y), block) in x.iter_mut().zip(y.iter_mut()).zip(blocks) {
+            *x = block[0];
+            *y = block[1];
+        }
+        (V::load(&x), V::load(&y))
+    }
+}
+
+/// Transpose the word-sliced state back into `blocks`.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+unsafe fn store<W: Copy + Default, V: Lanes<W, P>, const P: usize>(
+    x: V,
+    y: V,
+    blocks: &mut [[W; 2]; P],
+) {
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        let mut xw = [W::default(); P];
+        let mut yw = [W::default(); P];
+        x.store(&mut xw);
+        y.store(&mut yw);
+        for ((x, y), block) in xw.into_iter().zip(yw).zip(blocks) {
+            *block = [x, y];
+        }
+    }
+}
+
+/// Encrypt `P` blocks of `[x, y]` words with round keys `k`.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+pub(crate) unsafe fn encrypt<W: Copy + Default, V: Lanes<W, P>, const P: usize>(
+    k: &[W],
+    blocks: &mut [[W; 2]; P],
+) {
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        let (mut x, mut y) = load::<W, V, P>(blocks);
+        for &k in k {
+            x = x.rotr(ALPHA).add(y).xor(V::splat(k));
+            y = y.rotl(BETA).xor(x);
+        }
+        store(x, y, blocks);
+    }
+}
+
+/// Decrypt `P` blocks of `[x, y]` words with round keys `k`.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+pub(crate) unsafe fn decrypt<W: Copy + Default, V: Lanes<W, P>, const P: usize>(
+    k: &[W],
+    blocks: &mut [[W; 2]; P],
+) {
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        let (mut x, mut y) = load::<W, V, P>(blocks);
+        for &k in k.iter().rev() {
+            y = y.xor(x).rotr(BETA);
+            x = x.xor(V::splat(k)).sub(y).rotl(ALPHA);
+        }
+        store(x, y, blocks);
+    }
+}
+
+macro_rules! define_dispatch {
+    ($encrypt:ident, $decrypt:ident, $word:ty, $par:expr) => {
+        /// Encrypt blocks using the fastest backend available on the current CPU.
+        #[inline(always)]
+        pub(crate) fn $encrypt(k: &[$word], blocks: &mut [[$word; 2]; $par]) {
+            cfg_if::cfg_if! {
+                if #[cfg(all(
+                    target_arch = "aarch64",
+                    target_feature = "neon",
+                    not(speck_backend = "soft")
+                ))] {
+                    neon::$encrypt(k, blocks)
+                } else {
+                    #[cfg(all(
+                        any(target_arch = "x86", target_arch = "x86_64"),
+                        not(speck_backend = "soft")
+                    ))]
+                    if let Some(token) = avx2::Token::get() {
+                        return avx2::$encrypt(token, k, blocks);
+                    }
+                    // SAFETY: the portable lanes use no target features
+                    unsafe { encrypt::<$word, [$word; $par], $par>(k, blocks) }
+                }
+            }
+        }
+
+        /// Decrypt blocks using the fastest backend available on the current CPU.
+        #[inline(always)]
+        pub(crate) fn $decrypt(k: &[$word], blocks: &mut [[$word; 2]; $par]) {
+            cfg_if::cfg_if! {
+                if #[cfg(all(
+                    target_arch = "aarch64",
+                    target_feature = "neon",
+                    not(speck_backend = "soft")
+                ))] {
+                    neon::$decrypt(k, blocks)
+                } else {
+                    #[cfg(all(
+                        any(target_arch = "x86", target_arch = "x86_64"),
+                        not(speck_backend = "soft")
+                    ))]
+                    if let Some(token) = avx2::Token::get() {
+                        return avx2::$decrypt(token, k, blocks);
+                    }
+                    // SAFETY: the portable lanes use no target features
+                    unsafe { decrypt::<$word, [$word; $par], $par>(k, blocks) }
+                }
+            }
+        }
+    };
+}
+
+define_dispatch!(encrypt_par64, decrypt_par64, u32, PAR64);
+define_dispatch!(encrypt_par128, decrypt_par128, u64, PAR128);
@@ -0,0 +1,196 @@
+//! AVX2 backend which processes eight Speck64 or four Speck128 blocks using
+//! 256-bit vectors.
+//!
+//! The methods of `U32x8` and `U64x4` are compiled with AVX2 enabled, and the
+//! round functions using them are only called with a [`Token`] proving that
+//! the CPU supports it.
+#![allow(unsafe_code, unsafe_op_in_unsafe_fn)]
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::{Lanes, PAR64, PAR128};
+
+cpufeatures::new!(avx2_cpuid, "avx2");
+
+/// Proof that AVX2 is supported by the current CPU.
+#[derive(Clone, Copy)]
+pub(super) struct Token(());
+
+impl Token {
+    #[inline(always)]
+    pub(super) fn get() -> Option<Self> {
+        avx2_cpuid::get().then_some(Token(()))
+    }
+}
+
+/// Eight 32-bit lanes.
+#[derive(Clone, Copy)]
+struct U32x8(__m256i);
+
+/// Four 64-bit lanes.
+#[derive(Clone, Copy)]
+struct U64x4(__m256i);
+
+impl Lanes<u32, PAR64> for U32x8 {
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn load(w: &[u32; PAR64]) -> Self {
+        Self(_mm256_loadu_si256(w.as_ptr().cast()))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn store(self, w: &mut [u32; PAR64]) {
+        _mm256_storeu_si256(w.as_mut_ptr().cast(), self.0)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn splat(v: u32) -> Self {
+        Self(_mm256_set1_epi32(v as i32))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn add(self, rhs: Self) -> Self {
+        Self(_mm256_add_epi32(self.0, rhs.0))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn sub(self, rhs: Self) -> Self {
+        Self(_mm256_sub_epi32(self.0, rhs.0))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        Self(_mm256_xor_si256(self.0, rhs.0))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn rotl(self, r: u32) -> Self {
+        if r == 8 {
+            // byte rotation is cheaper as a shuffle
+            let mask = _mm256_setr_epi8(
+                3, 0, 1, 2, 7, 4, 5, 6, 11, 8, 9, 10, 15, 12, 13, 14, //
+                3, 0, 1, 2, 7, 4, 5, 6, 11, 8, 9, 10, 15, 12, 13, 14,
+            );
+            return Self(_mm256_shuffle_epi8(self.0, mask));
+        }
+        let l = _mm_cvtsi32_si128(r as i32);
+        let r = _mm_cvtsi32_si128(32 - r as i32);
+        Self(_mm256_or_si256(
+            _mm256_sll_epi32(self.0, l),
+            _mm256_srl_epi32(self.0, r),
+        ))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn rotr(self, r: u32) -> Self {
+        if r == 8 {
+            let mask = _mm256_setr_epi8(
+                1, 2, 3, 0, 5, 6, 7, 4, 9, 10, 11, 8, 13, 14, 15, 12, //
+                1, 2, 3, 0, 5, 6, 7, 4, 9, 10, 11, 8, 13, 14, 15, 12,
+            );
+            return Self(_mm256_shuffle_epi8(self.0, mask));
+        }
+        self.rotl(32 - r)
+    }
+}
+
+impl Lanes<u64, PAR128> for U64x4 {
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn load(w: &[u64; PAR128]) -> Self {
+        Self(_mm256_loadu_si256(w.as_ptr().cast()))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn store(self, w: &mut [u64; PAR128]) {
+        _mm256_storeu_si256(w.as_mut_ptr().cast(), self.0)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn splat(v: u64) -> Self {
+        Self(_mm256_set1_epi64x(v as i64))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn add(self, rhs: Self) -> Self {
+        Self(_mm256_add_epi64(self.0, rhs.0))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn sub(self, rhs: Self) -> Self {
+        Self(_mm256_sub_epi64(self.0, rhs.0))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        Self(_mm256_xor_si256(self.0, rhs.0))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn rotl(self, r: u32) -> Self {
+        if r == 8 {
+            // byte rotation is cheaper as a shuffle
+            let mask = _mm256_setr_epi8(
+                7, 0, 1, 2, 3, 4, 5, 6, 15, 8, 9, 10, 11, 12, 13, 14, //
+                7, 0, 1, 2, 3, 4, 5, 6, 15, 8, 9, 10, 11, 12, 13, 14,
+            );
+            return Self(_mm256_shuffle_epi8(self.0, mask));
+        }
+        let l = _mm_cvtsi32_si128(r as i32);
+        let r = _mm_cvtsi32_si128(64 - r as i32);
+        Self(_mm256_or_si256(
+            _mm256_sll_epi64(self.0, l),
+            _mm256_srl_epi64(self.0, r),
+        ))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn rotr(self, r: u32) -> Self {
+        if r == 8 {
+            let mask = _mm256_setr_epi8(
+                1, 2, 3, 4, 5, 6, 7, 0, 9, 10, 11, 12, 13, 14, 15, 8, //
+                1, 2, 3, 4, 5, 6, 7, 0, 9, 10, 11, 12, 13, 14, 15, 8,
+            );
+            return Self(_mm256_shuffle_epi8(self.0, mask));
+        }
+        self.rotl(64 - r)
+    }
+}
+
+macro_rules! define_par {
+    ($name:ident, $imp:ident, $word:ty, $lanes:ty, $par:expr) => {
+        #[inline(always)]
+        pub(super) fn $name(_token: Token, k: &[$word], blocks: &mut [[$word; 2]; $par]) {
+            #[target_feature(enable = "avx2")]
+            unsafe fn imp(k: &[$word], blocks: &mut [[$word; 2]; $par]) {
+                // SAFETY: AVX2 is enabled for this function
+                unsafe { super::$imp::<$word, $lanes, $par>(k, blocks) }
+            }
+
+            // SAFETY: `_token` proves that the CPU supports AVX2
+            unsafe { imp(k, blocks) }
+        }
+    };
+}
+
+define_par!(encrypt_par64, encrypt, u32, U32x8, PAR64);
+define_par!(decrypt_par64, decrypt, u32, U32x8, PAR64);
+define_par!(encrypt_par128, encrypt, u64, U64x4, PAR128);
+define_par!(decrypt_par128, decrypt, u64, U64x4, PAR128);
@@ -0,0 +1,138 @@
+//! NEON backend which processes eight Speck64 or four Speck128 blocks using
+//! pairs of 128-bit vectors.
+//!
+//! This module is only compiled with `target_feature = "neon"` enabled for
+//! the crate, so the methods of `U32x8` and `U64x4` can be called anywhere.
+#![allow(unsafe_code, unsafe_op_in_unsafe_fn)]
+
+use core::arch::aarch64::*;
+
+use super::{Lanes, PAR64, PAR128};
+
+/// Eight 32-bit lanes.
+#[derive(Clone, Copy)]
+struct U32x8(uint32x4_t, uint32x4_t);
+
+/// Four 64-bit lanes.
+#[derive(Clone, Copy)]
+struct U64x4(uint64x2_t, uint64x2_t);
+
+impl Lanes<u32, PAR64> for U32x8 {
+    #[inline(always)]
+    unsafe fn load(w: &[u32; PAR64]) -> Self {
+        Self(vld1q_u32(w.as_ptr()), vld1q_u32(w[4..].as_ptr()))
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, w: &mut [u32; PAR64]) {
+        vst1q_u32(w.as_mut_ptr(), self.0);
+        vst1q_u32(w[4..].as_mut_ptr(), self.1);
+    }
+
+    #[inline(always)]
+    unsafe fn splat(v: u32) -> Self {
+        let v = vdupq_n_u32(v);
+        Self(v, v)
+    }
+
+    #[inline(always)]
+    unsafe fn add(self, rhs: Self) -> Self {
+        Self(vaddq_u32(self.0, rhs.0), vaddq_u32(self.1, rhs.1))
+    }
+
+    #[inline(always)]
+    unsafe fn sub(self, rhs: Self) -> Self {
+        Self(vsubq_u32(self.0, rhs.0), vsubq_u32(self.1, rhs.1))
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        Self(veorq_u32(self.0, rhs.0), veorq_u32(self.1, rhs.1))
+    }
+
+    #[inline(always)]
+    unsafe fn rotl(self, r: u32) -> Self {
+        // `vshlq_u32` shifts right for negative shift amounts
+        let l = vdupq_n_s32(r as i32);
+        let r = vdupq_n_s32(r as i32 - 32);
+        let rot = |v| vorrq_u32(vshlq_u32(v, l), vshlq_u32(v, r));
+        Self(rot(self.0), rot(self.1))
+    }
+
+    #[inline(always)]
+    unsafe fn rotr(self, r: u32) -> Self {
+        self.rotl(32 - r)
+    }
+}
+
+impl Lanes<u64, PAR128> for U64x4 {
+    #[inline(always)]
+    unsafe fn load(w: &[u64; PAR128]) -> Self {
+        Self(vld1q_u64(w.as_ptr()), vld1q_u64(w[2..].as_ptr()))
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, w: &mut [u64; PAR128]) {
+        vst1q_u64(w.as_mut_ptr(), self.0);
+        vst1q_u64(w[2..].as_mut_ptr(), self.1);
+    }
+
+    #[inline(always)]
+    unsafe fn splat(v: u64) -> Self {
+        let v = vdupq_n_u64(v);
+        Self(v, v)
+    }
+
+    #[inline(always)]
+    unsafe fn add(self, rhs: Self) -> Self {
+        Self(vaddq_u64(self.0, rhs.0), vaddq_u64(self.1, rhs.1))
+    }
+
+    #[inline(always)]
+    unsafe fn sub(self, rhs: Self) -> Self {
+        Self(vsubq_u64(self.0, rhs.0), vsubq_u64(self.1, rhs.1))
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        Self(veorq_u64(self.0, rhs.0), veorq_u64(self.1, rhs.1))
+    }
+
+    #[inline(always)]
+    unsafe fn rotl(self, r: u32) -> Self {
+        // `vshlq_u64` shifts right for negative shift amounts
+        let l = vdupq_n_s64(r as i64);
+        let r = vdupq_n_s64(r as i64 - 64);
+        let rot = |v| vorrq_u64(vshlq_u64(v, l), vshlq_u64(v, r));
+        Self(rot(self.0), rot(self.1))
+    }
+
+    #[inline(always)]
+    unsafe fn rotr(self, r: u32) -> Self {
+        self.rotl(64 - r)
+    }
+}
+
+#[inline(always)]
+pub(super) fn encrypt_par64(k: &[u32], blocks: &mut [[u32; 2]; PAR64]) {
+    // SAFETY: NEON is enabled at compile time
+    unsafe { super::encrypt::<u32, U32x8, PAR64>(k, blocks) }
+}
+
+#[inline(always)]
+pub(super) fn decrypt_par64(k: &[u32], blocks: &mut [[u32; 2]; PAR64]) {
+    // SAFETY: NEON is enabled at compile time
+    unsafe { super::decrypt::<u32, U32x8, PAR64>(k, blocks) }
+}
+
+#[inline(always)]
+pub(super) fn encrypt_par128(k: &[u64], blocks: &mut [[u64; 2]; PAR128]) {
+    // SAFETY: NEON is enabled at compile time
+    unsafe { super::encrypt::<u64, U64x4, PAR128>(k, blocks) }
+}
+
+#[inline(always)]
+pub(super) fn decrypt_par128(k: &[u64], blocks: &mut [[u64; 2]; PAR128]) {
+    // SAFETY: NEON is enabled at compile time
+    unsafe { super::decrypt::<u64, U64x4, PAR128>(k, blocks) }
+}
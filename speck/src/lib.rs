@@ -1,6 +1,23 @@
-//! Pure Rust implementation of the [Speck] block cipher.
+//! Pure Rust implementation of the [Speck] and [Simon] block ciphers.
+//!
+//! # Backends
+//!
+//! `Speck64_*` and `Speck128_*` process eight and four blocks in parallel
+//! respectively, using AVX2 on x86 targets (with runtime CPU feature
+//! detection) and NEON on AArch64 targets. Other targets and the remaining
+//! variants use a portable software implementation.
+//!
+//! # Configuration Flags
+//!
+//! You can force the portable software backend using the `speck_backend`
+//! configuration flag set to `soft`.
+//!
+//! The flag can be enabled using `RUSTFLAGS` environment variable
+//! (e.g. `RUSTFLAGS='--cfg speck_backend="soft"'`) or by modifying
+//! `.cargo/config`.
 //!
 //! [Speck]: https://en.wikipedia.org/wiki/Speck_(cipher)
+//! [Simon]: https://en.wikipedia.org/wiki/Simon_(cipher)
 
 #![no_std]
 #![doc(
@@ -16,14 +33,27 @@ pub use cipher;
 use cipher::{
     AlgorithmName, Block, BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherDecrypt,
     BlockCipherEncBackend, BlockCipherEncClosure, BlockCipherEncrypt, BlockSizeUser, InOut, Key,
-    KeyInit, KeySizeUser, ParBlocksSizeUser, consts::*,
+    KeyInit, KeySizeUser, ParBlocks, ParBlocksSizeUser, consts::*, typenum::Unsigned,
 };
 use core::{fmt, mem::size_of};
 
 #[cfg(feature = "zeroize")]
 use cipher::zeroize::{Zeroize, ZeroizeOnDrop};
 
+mod backends;
+#[cfg(feature = "modes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "modes")))]
+pub mod modes;
+mod simon;
+
+pub use simon::{
+    Simon32_64, Simon48_72, Simon48_96, Simon64_96, Simon64_128, Simon96_96, Simon96_144,
+    Simon128_128, Simon128_192, Simon128_256,
+};
+
 macro_rules! define_speck_impl {
+    (@par_blocks_size) => { U1 };
+    (@par_blocks_size $par_blocks:ty) => { $par_blocks };
     (
         $name:ident,
         $block_size:ty,
@@ -35,7 +65,8 @@ macro_rules! define_speck_impl {
         $beta:literal,
         $mask:literal,
         $rounds:literal,
-        $doc:expr $(,)?
+        $doc:expr
+        $(, par: $par_blocks:ty, $encrypt_par:ident, $decrypt_par:ident)? $(,)?
     ) => {
         #[doc=$doc]
         #[doc = "block cipher"]
@@ -133,7 +164,7 @@ macro_rules! define_speck_impl {
         }
 
         impl ParBlocksSizeUser for $name {
-            type ParBlocksSize = U1;
+            type ParBlocksSize = define_speck_impl!(@par_blocks_size $($par_blocks)?);
         }
 
         impl BlockCipherEncrypt for $name {
@@ -162,6 +193,24 @@ macro_rules! define_speck_impl {
                 b[0..($n / 8)].copy_from_slice(&$name::to_be_bytes(x));
                 b[($n / 8)..2 * ($n / 8)].copy_from_slice(&$name::to_be_bytes(y));
             }
+
+            $(
+                #[inline]
+                fn encrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+                    let mut words = [[0; 2]; <$par_blocks as Unsigned>::USIZE];
+                    for (w, b) in words.iter_mut().zip(blocks.get_in().iter()) {
+                        w[0] = $name::from_be_bytes(&b[0..($n / 8)]);
+                        w[1] = $name::from_be_bytes(&b[($n / 8)..2 * ($n / 8)]);
+                    }
+
+                    backends::$encrypt_par(&self.k, &mut words);
+
+                    for (w, b) in words.iter().zip(blocks.get_out().iter_mut()) {
+                        b[0..($n / 8)].copy_from_slice(&$name::to_be_bytes(w[0]));
+                        b[($n / 8)..2 * ($n / 8)].copy_from_slice(&$name::to_be_bytes(w[1]));
+                    }
+                }
+            )?
         }
 
         impl BlockCipherDecrypt for $name {
@@ -190,6 +239,24 @@ macro_rules! define_speck_impl {
                 b[0..($n / 8)].copy_from_slice(&$name::to_be_bytes(x));
                 b[($n / 8)..2 * ($n / 8)].copy_from_slice(&$name::to_be_bytes(y));
             }
+
+            $(
+                #[inline]
+                fn decrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+                    let mut words = [[0; 2]; <$par_blocks as Unsigned>::USIZE];
+                    for (w, b) in words.iter_mut().zip(blocks.get_in().iter()) {
+                        w[0] = $name::from_be_bytes(&b[0..($n / 8)]);
+                        w[1] = $name::from_be_bytes(&b[($n / 8)..2 * ($n / 8)]);
+                    }
+
+                    backends::$decrypt_par(&self.k, &mut words);
+
+                    for (w, b) in words.iter().zip(blocks.get_out().iter_mut()) {
+                        b[0..($n / 8)].copy_from_slice(&$name::to_be_bytes(w[0]));
+                        b[($n / 8)..2 * ($n / 8)].copy_from_slice(&$name::to_be_bytes(w[1]));
+                    }
+                }
+            )?
         }
 
         impl fmt::Debug for $name {
@@ -266,7 +333,8 @@ define_speck_impl!(
     3,
     0xFFFFFFFF,
     26,
-    "Speck64/96"
+    "Speck64/96",
+    par: U8, encrypt_par64, decrypt_par64
 );
 define_speck_impl!(
     Speck64_128,
@@ -279,7 +347,8 @@ define_speck_impl!(
     3,
     0xFFFFFFFF,
     27,
-    "Speck64/128"
+    "Speck64/128",
+    par: U8, encrypt_par64, decrypt_par64
 );
 define_speck_impl!(
     Speck96_96,
@@ -318,7 +387,8 @@ define_speck_impl!(
     3,
     0xFFFFFFFFFFFFFFFF,
     32,
-    "Speck128/128"
+    "Speck128/128",
+    par: U4, encrypt_par128, decrypt_par128
 );
 define_speck_impl!(
     Speck128_192,
@@ -331,7 +401,8 @@ define_speck_impl!(
     3,
     0xFFFFFFFFFFFFFFFF,
     33,
-    "Speck128/192"
+    "Speck128/192",
+    par: U4, encrypt_par128, decrypt_par128
 );
 define_speck_impl!(
     Speck128_256,
@@ -344,5 +415,6 @@ define_speck_impl!(
     3,
     0xFFFFFFFFFFFFFFFF,
     34,
-    "Speck128/256"
+    "Speck128/256",
+    par: U4, encrypt_par128, decrypt_par128
 );
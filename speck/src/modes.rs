@@ -0,0 +1,524 @@
+//! CMAC and CCM for the 64-bit and 128-bit block variants.
+//!
+//! - [`Cmac`]: CMAC message authentication code as specified in
+//!   [NIST SP 800-38B], including the 64-bit block subkey constant.
+//! - [`Ccm`]: CCM authenticated encryption as specified in
+//!   [NIST SP 800-38C], with the formatting function generalized to 64-bit
+//!   blocks. The nonce and the message length field share the `n - 1` bytes
+//!   of the first block, so with a 64-bit block a 5-byte nonce leaves room
+//!   for messages of up to 65535 bytes.
+//!
+//! Both modes work with any block cipher with 64-bit or 128-bit blocks, e.g.
+//! [`Speck64_128`][crate::Speck64_128] or [`Speck128_128`][crate::Speck128_128],
+//! and are initialized with a key using [`KeyInit`][cipher::KeyInit] or
+//! with an already initialized cipher using [`InnerInit`].
+//!
+//! # Data limits
+//!
+//! The security of both modes degrades once the number of block cipher
+//! invocations under a single key approaches the birthday bound of the block
+//! size. For 64-bit blocks this bound is reachable in practice ([Sweet32]),
+//! so every instance counts the block cipher invocations it performs and
+//! refuses to process data that would exceed [`ModeBlockSize::MAX_BLOCKS`]:
+//!
+//! - 64-bit blocks: 2<sup>20</sup> invocations (8 MiB of data), the limit
+//!   NIST SP 800-67 Rev. 2 sets for the 64-bit block TDEA.
+//! - 128-bit blocks: 2<sup>48</sup> invocations.
+//!
+//! Once [`Error::DataLimitExceeded`] is returned, a new key must be
+//! established. Note that the counters only cover a single instance: keys
+//! must not be shared between several instances.
+//!
+//! CCM additionally requires that a nonce is never reused with the same key.
+//! The short nonces available with 64-bit blocks should be generated from a
+//! message counter rather than at random.
+//!
+//! # Example
+//! ```
+//! use cipher::{KeyInit, consts::{U5, U8}};
+//! use speck_cipher::{Speck64_128, modes::Ccm};
+//!
+//! let mut ccm = Ccm::<Speck64_128, U8, U5>::new(&[0x42; 16].into());
+//! let nonce = [0, 0, 0, 0, 1].into();
+//!
+//! let mut buf = *b"temperature: 21C";
+//! let tag = ccm.encrypt_in_place_detached(&nonce, b"sensor 7", &mut buf).unwrap();
+//! ccm.decrypt_in_place_detached(&nonce, b"sensor 7", &mut buf, &tag).unwrap();
+//! assert_eq!(&buf, b"temperature: 21C");
+//! ```
+//!
+//! [NIST SP 800-38B]: https://doi.org/10.6028/NIST.SP.800-38B
+//! [NIST SP 800-38C]: https://doi.org/10.6028/NIST.SP.800-38C
+//! [Sweet32]: https://sweet32.info/
+
+use cipher::{
+    Array, Block, BlockCipherEncrypt, BlockSizeUser,
+    array::ArraySize,
+    common::{InnerInit, InnerUser},
+    consts::{U8, U16},
+    typenum::Unsigned,
+};
+use core::{fmt, marker::PhantomData};
+use subtle::ConstantTimeEq;
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::Zeroize;
+
+/// Block sizes supported by [`Cmac`] and [`Ccm`].
+///
+/// This trait is sealed and implemented for [`U8`] and [`U16`].
+pub trait ModeBlockSize: ArraySize + sealed::Sealed {
+    /// Maximum number of block cipher invocations under a single key.
+    const MAX_BLOCKS: u64;
+    /// Constant used for subkey generation in CMAC.
+    #[doc(hidden)]
+    const R: u8;
+}
+
+impl ModeBlockSize for U8 {
+    const MAX_BLOCKS: u64 = 1 << 20;
+    const R: u8 = 0x1b;
+}
+
+impl ModeBlockSize for U16 {
+    const MAX_BLOCKS: u64 = 1 << 48;
+    const R: u8 = 0x87;
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::U8 {}
+    impl Sealed for super::U16 {}
+}
+
+/// Minimum length of a truncated tag accepted by [`Cmac::verify`].
+const MIN_TAG_LEN: usize = 4;
+
+/// CMAC message authentication code.
+pub struct Cmac<C>
+where
+    C: BlockSizeUser,
+    C::BlockSize: ModeBlockSize,
+{
+    cipher: C,
+    k1: Block<C>,
+    k2: Block<C>,
+    blocks: u64,
+}
+
+impl<C> Cmac<C>
+where
+    C: BlockCipherEncrypt,
+    C::BlockSize: ModeBlockSize,
+{
+    /// Number of block cipher invocations left before the key must be
+    /// replaced.
+    pub fn remaining_blocks(&self) -> u64 {
+        C::BlockSize::MAX_BLOCKS - self.blocks
+    }
+
+    /// Compute the tag of `msg`.
+    ///
+    /// Returns [`Error::DataLimitExceeded`] if processing `msg` would exceed
+    /// the data limit of the key.
+    pub fn compute(&mut self, msg: &[u8]) -> Result<Block<C>, Error> {
+        let n = C::BlockSize::USIZE;
+        reserve::<C::BlockSize>(&mut self.blocks, blocks(msg.len(), n).max(1))?;
+
+        let rem = match msg.len() % n {
+            0 if !msg.is_empty() => n,
+            rem => rem,
+        };
+        let (head, last) = msg.split_at(msg.len() - rem);
+
+        let mut state = Block::<C>::default();
+        for chunk in head.chunks_exact(n) {
+            xor(&mut state, chunk);
+            self.cipher.encrypt_block(&mut state);
+        }
+
+        xor(&mut state, last);
+        if last.len() == n {
+            xor(&mut state, &self.k1);
+        } else {
+            state[last.len()] ^= 0x80;
+            xor(&mut state, &self.k2);
+        }
+        self.cipher.encrypt_block(&mut state);
+
+        Ok(state)
+    }
+
+    /// Verify a possibly truncated `tag` of `msg` in constant time.
+    ///
+    /// Tags shorter than 4 bytes or longer than the block size are rejected.
+    /// Returns [`Error::Verification`] if the tag does not match and
+    /// [`Error::DataLimitExceeded`] if processing `msg` would exceed the data
+    /// limit of the key.
+    pub fn verify(&mut self, msg: &[u8], tag: &[u8]) -> Result<(), Error> {
+        if !(MIN_TAG_LEN..=C::BlockSize::USIZE).contains(&tag.len()) {
+            return Err(Error::Verification);
+        }
+        let expected = self.compute(msg)?;
+        if expected[..tag.len()].ct_eq(tag).into() {
+            Ok(())
+        } else {
+            Err(Error::Verification)
+        }
+    }
+}
+
+impl<C> InnerUser for Cmac<C>
+where
+    C: BlockSizeUser,
+    C::BlockSize: ModeBlockSize,
+{
+    type Inner = C;
+}
+
+impl<C> InnerInit for Cmac<C>
+where
+    C: BlockCipherEncrypt,
+    C::BlockSize: ModeBlockSize,
+{
+    #[inline]
+    fn inner_init(cipher: C) -> Self {
+        let mut l = Block::<C>::default();
+        cipher.encrypt_block(&mut l);
+        let k1 = dbl::<C::BlockSize>(&l);
+        let k2 = dbl::<C::BlockSize>(&k1);
+        Self {
+            cipher,
+            k1,
+            k2,
+            blocks: 1,
+        }
+    }
+}
+
+impl<C> fmt::Debug for Cmac<C>
+where
+    C: BlockSizeUser,
+    C::BlockSize: ModeBlockSize,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Cmac { ... }")
+    }
+}
+
+impl<C> Drop for Cmac<C>
+where
+    C: BlockSizeUser,
+    C::BlockSize: ModeBlockSize,
+{
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        {
+            self.k1.zeroize();
+            self.k2.zeroize();
+        }
+    }
+}
+
+/// CCM authenticated encryption with a tag of `M` bytes and a nonce of `N`
+/// bytes.
+///
+/// `M` must be even and between 4 and the block size, and the length field
+/// `q = n - 1 - N` (where `n` is the block size) must be between 2 and 8
+/// bytes: `N` is between 7 and 13 for 128-bit blocks and at most 5 for
+/// 64-bit blocks. Other parameters fail to compile.
+pub struct Ccm<C, M, N>
+where
+    C: BlockSizeUser,
+    C::BlockSize: ModeBlockSize,
+    M: ArraySize,
+    N: ArraySize,
+{
+    cipher: C,
+    blocks: u64,
+    _params: PhantomData<(M, N)>,
+}
+
+impl<C, M, N> Ccm<C, M, N>
+where
+    C: BlockCipherEncrypt,
+    C::BlockSize: ModeBlockSize,
+    M: ArraySize,
+    N: ArraySize,
+{
+    const BLOCK_SIZE: usize = C::BlockSize::USIZE;
+    /// Length of the message length field.
+    const Q: usize = {
+        let n = C::BlockSize::USIZE;
+        let m = M::USIZE;
+        assert!(m % 2 == 0 && m >= 4 && m <= n, "invalid CCM tag size");
+        assert!(
+            N::USIZE + 3 <= n && N::USIZE + 9 >= n,
+            "invalid CCM nonce size"
+        );
+        n - 1 - N::USIZE
+    };
+
+    /// Number of block cipher invocations left before the key must be
+    /// replaced.
+    pub fn remaining_blocks(&self) -> u64 {
+        C::BlockSize::MAX_BLOCKS - self.blocks
+    }
+
+    /// Encrypt `buf` in place and return the authentication tag.
+    ///
+    /// Returns [`Error::MessageTooLong`] if the length of `buf` does not fit
+    /// into the length field and [`Error::DataLimitExceeded`] if the
+    /// operation would exceed the data limit of the key. `buf` is left
+    /// unmodified on error.
+    pub fn encrypt_in_place_detached(
+        &mut self,
+        nonce: &Array<u8, N>,
+        ad: &[u8],
+        buf: &mut [u8],
+    ) -> Result<Array<u8, M>, Error> {
+        self.reserve(ad.len(), buf.len())?;
+
+        let tag = self.cbc_mac(nonce, ad, buf);
+        self.apply_keystream(nonce, buf);
+        Ok(self.encrypt_tag(nonce, tag))
+    }
+
+    /// Decrypt `buf` in place and verify `tag`.
+    ///
+    /// Returns [`Error::Verification`] if the tag does not match, in which
+    /// case `buf` is zeroed. Returns [`Error::MessageTooLong`] and
+    /// [`Error::DataLimitExceeded`] in the same cases as encryption, leaving
+    /// `buf` unmodified.
+    pub fn decrypt_in_place_detached(
+        &mut self,
+        nonce: &Array<u8, N>,
+        ad: &[u8],
+        buf: &mut [u8],
+        tag: &Array<u8, M>,
+    ) -> Result<(), Error> {
+        self.reserve(ad.len(), buf.len())?;
+
+        self.apply_keystream(nonce, buf);
+        let expected = self.cbc_mac(nonce, ad, buf);
+        let expected = self.encrypt_tag(nonce, expected);
+
+        if expected.ct_eq(tag).into() {
+            Ok(())
+        } else {
+            buf.fill(0);
+            Err(Error::Verification)
+        }
+    }
+
+    /// Check the message length and account for the block cipher invocations
+    /// of a single operation.
+    fn reserve(&mut self, ad_len: usize, msg_len: usize) -> Result<(), Error> {
+        let q = Self::Q;
+        if q < size_of::<usize>() && msg_len >> (8 * q) != 0 {
+            return Err(Error::MessageTooLong);
+        }
+
+        let ad_len = match ad_len {
+            0 => 0,
+            a if a < 0xff00 => a + 2,
+            a if (a as u64) < 1 << 32 => a + 6,
+            a => a + 10,
+        };
+        let n = Self::BLOCK_SIZE;
+        let msg_blocks = blocks(msg_len, n);
+        let count = 1 + blocks(ad_len, n) + msg_blocks + 1 + msg_blocks;
+        reserve::<C::BlockSize>(&mut self.blocks, count)
+    }
+
+    /// Compute the unencrypted tag of the message in `buf`.
+    fn cbc_mac(&self, nonce: &Array<u8, N>, ad: &[u8], buf: &[u8]) -> Block<C> {
+        let (n, q) = (Self::BLOCK_SIZE, Self::Q);
+
+        let mut x = Block::<C>::default();
+        let adata = if ad.is_empty() { 0 } else { 0x40 };
+        x[0] = adata | (((M::USIZE as u8 - 2) / 2) << 3) | (q as u8 - 1);
+        x[1..n - q].copy_from_slice(nonce);
+        x[n - q..].copy_from_slice(&(buf.len() as u64).to_be_bytes()[8 - q..]);
+        self.cipher.encrypt_block(&mut x);
+
+        if !ad.is_empty() {
+            let a = ad.len() as u64;
+            let mut header = [0u8; 10];
+            let header = match a {
+                a if a < 0xff00 => {
+                    header[..2].copy_from_slice(&(a as u16).to_be_bytes());
+                    &header[..2]
+                }
+                a if a < 1 << 32 => {
+                    header[..2].copy_from_slice(&[0xff, 0xfe]);
+                    header[2..6].copy_from_slice(&(a as u32).to_be_bytes());
+                    &header[..6]
+                }
+                a => {
+                    header[..2].copy_from_slice(&[0xff, 0xff]);
+                    header[2..].copy_from_slice(&a.to_be_bytes());
+                    &header[..]
+                }
+            };
+
+            let mut pos = 0;
+            self.absorb(&mut x, &mut pos, header);
+            self.absorb(&mut x, &mut pos, ad);
+            if pos != 0 {
+                self.cipher.encrypt_block(&mut x);
+            }
+        }
+
+        let mut pos = 0;
+        self.absorb(&mut x, &mut pos, buf);
+        if pos != 0 {
+            self.cipher.encrypt_block(&mut x);
+        }
+
+        x
+    }
+
+    /// XOR `data` into the CBC-MAC state `x` starting at offset `pos`,
+    /// encrypting the state every time it is filled.
+    fn absorb(&self, x: &mut Block<C>, pos: &mut usize, mut data: &[u8]) {
+        let n = Self::BLOCK_SIZE;
+        while !data.is_empty() {
+            let take = (n - *pos).min(data.len());
+            xor(&mut x[*pos..*pos + take], &data[..take]);
+            data = &data[take..];
+            *pos += take;
+            if *pos == n {
+                self.cipher.encrypt_block(x);
+                *pos = 0;
+            }
+        }
+    }
+
+    /// Counter block `i`.
+    fn ctr_block(nonce: &Array<u8, N>, i: u64) -> Block<C> {
+        let (n, q) = (Self::BLOCK_SIZE, Self::Q);
+        let mut a = Block::<C>::default();
+        a[0] = q as u8 - 1;
+        a[1..n - q].copy_from_slice(nonce);
+        a[n - q..].copy_from_slice(&i.to_be_bytes()[8 - q..]);
+        a
+    }
+
+    /// Apply the keystream starting at counter 1 to `buf`.
+    fn apply_keystream(&self, nonce: &Array<u8, N>, buf: &mut [u8]) {
+        for (i, chunk) in buf.chunks_mut(Self::BLOCK_SIZE).enumerate() {
+            let mut s = Self::ctr_block(nonce, i as u64 + 1);
+            self.cipher.encrypt_block(&mut s);
+            xor(chunk, &s);
+        }
+    }
+
+    /// Encrypt the truncated tag with counter 0.
+    fn encrypt_tag(&self, nonce: &Array<u8, N>, t: Block<C>) -> Array<u8, M> {
+        let mut s = Self::ctr_block(nonce, 0);
+        self.cipher.encrypt_block(&mut s);
+        Array::from_fn(|i| t[i] ^ s[i])
+    }
+}
+
+impl<C, M, N> InnerUser for Ccm<C, M, N>
+where
+    C: BlockSizeUser,
+    C::BlockSize: ModeBlockSize,
+    M: ArraySize,
+    N: ArraySize,
+{
+    type Inner = C;
+}
+
+impl<C, M, N> InnerInit for Ccm<C, M, N>
+where
+    C: BlockCipherEncrypt,
+    C::BlockSize: ModeBlockSize,
+    M: ArraySize,
+    N: ArraySize,
+{
+    #[inline]
+    fn inner_init(cipher: C) -> Self {
+        // Reject invalid parameters at compile time
+        let _ = Self::Q;
+        Self {
+            cipher,
+            blocks: 0,
+            _params: PhantomData,
+        }
+    }
+}
+
+impl<C, M, N> fmt::Debug for Ccm<C, M, N>
+where
+    C: BlockSizeUser,
+    C::BlockSize: ModeBlockSize,
+    M: ArraySize,
+    N: ArraySize,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Ccm { ... }")
+    }
+}
+
+/// Number of `n`-byte blocks needed for `len` bytes.
+fn blocks(len: usize, n: usize) -> u64 {
+    len.div_ceil(n) as u64
+}
+
+/// Account for `count` block cipher invocations.
+fn reserve<B: ModeBlockSize>(used: &mut u64, count: u64) -> Result<(), Error> {
+    match used.checked_add(count) {
+        Some(total) if total <= B::MAX_BLOCKS => {
+            *used = total;
+            Ok(())
+        }
+        _ => Err(Error::DataLimitExceeded),
+    }
+}
+
+/// Multiply `b` by `x` in GF(2^n).
+fn dbl<B: ModeBlockSize>(b: &Array<u8, B>) -> Array<u8, B> {
+    let mut res = Array::<u8, B>::default();
+    let mut carry = 0;
+    for (r, &v) in res.iter_mut().zip(b.iter()).rev() {
+        *r = (v << 1) | carry;
+        carry = v >> 7;
+    }
+    let last = res.len() - 1;
+    res[last] ^= B::R & 0u8.wrapping_sub(carry);
+    res
+}
+
+fn xor(buf: &mut [u8], data: &[u8]) {
+    for (a, b) in buf.iter_mut().zip(data) {
+        *a ^= b;
+    }
+}
+
+/// Error returned by [`Cmac`] and [`Ccm`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Error {
+    /// The operation would exceed the data limit of the key.
+    DataLimitExceeded,
+    /// The message is too long for the CCM length field.
+    MessageTooLong,
+    /// The authentication tag does not match.
+    Verification,
+}
+
+impl fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(match self {
+            Error::DataLimitExceeded => "DataLimitExceeded",
+            Error::MessageTooLong => "MessageTooLong",
+            Error::Verification => "Verification",
+        })
+    }
+}
+
+impl core::error::Error for Error {}
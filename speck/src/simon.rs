@@ -0,0 +1,295 @@
+//! Simon block cipher family.
+
+use cipher::{
+    AlgorithmName, Block, BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherDecrypt,
+    BlockCipherEncBackend, BlockCipherEncClosure, BlockCipherEncrypt, BlockSizeUser, InOut, Key,
+    KeyInit, KeySizeUser, ParBlocksSizeUser, consts::*,
+};
+use core::{fmt, mem::size_of};
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Key schedule constant sequences `z0`..`z4`, bit `i` of the sequence is
+/// stored at bit position `61 - i`.
+const Z: [u64; 5] = [
+    0b111110_10001001_01011000_01110011_01111101_00010010_10110000_11100110,
+    0b100011_10111110_01001100_00101101_01000111_01111100_10011000_01011010,
+    0b101011_11011100_00001101_00100110_00101000_01000111_11100101_10110011,
+    0b110110_11101011_00011001_01111000_00010010_00101001_11001101_00001111,
+    0b110100_01111001_10101101_10001000_00010111_00001100_10100100_11101111,
+];
+
+macro_rules! define_simon_impl {
+    (
+        $name:ident,
+        $block_size:ty,
+        $key_size:ty,
+        $word_type:ty,
+        $n:literal,
+        $m:literal,
+        $j:literal,
+        $mask:literal,
+        $rounds:literal,
+        $doc:expr $(,)?
+    ) => {
+        #[doc=$doc]
+        #[doc = "block cipher"]
+        #[derive(Clone)]
+        pub struct $name {
+            k: [$word_type; $rounds],
+        }
+
+        impl $name {
+            #[inline]
+            fn from_be_bytes(bytes: &[u8]) -> $word_type {
+                let mut tmp = [0u8; size_of::<$word_type>()];
+                let offset = size_of::<$word_type>() - $n / 8;
+                tmp[offset..].copy_from_slice(bytes);
+                <$word_type>::from_be_bytes(tmp)
+            }
+
+            #[inline]
+            #[allow(clippy::wrong_self_convention)]
+            fn to_be_bytes(word: $word_type) -> [u8; $n / 8] {
+                let tmp = word.to_be_bytes();
+                let offset = size_of::<$word_type>() - $n / 8;
+                tmp[offset..].try_into().unwrap()
+            }
+
+            #[inline]
+            fn rotate_right(x: $word_type, pos: $word_type) -> $word_type {
+                // We can't use $word_type.rotate_right here because the word size might be different from the word type.
+                ((x >> pos) | (x << ($n - pos))) & $mask
+            }
+
+            #[inline]
+            fn rotate_left(x: $word_type, pos: $word_type) -> $word_type {
+                // We can't use $word_type.rotate_left here because the word size might be different from the word type.
+                ((x << pos) | (x >> ($n - pos))) & $mask
+            }
+
+            #[inline]
+            fn f(x: $word_type) -> $word_type {
+                ($name::rotate_left(x, 1) & $name::rotate_left(x, 8)) ^ $name::rotate_left(x, 2)
+            }
+        }
+
+        impl KeySizeUser for $name {
+            type KeySize = $key_size;
+        }
+
+        impl KeyInit for $name {
+            fn new(key: &Key<Self>) -> Self {
+                let mut k = [0; $rounds];
+                for i in 0..$m {
+                    k[i] = $name::from_be_bytes(&key[($m - 1 - i) * ($n / 8)..($m - i) * ($n / 8)]);
+                }
+
+                for i in $m..$rounds {
+                    let mut tmp = $name::rotate_right(k[i - 1], 3);
+                    if $m == 4 {
+                        tmp ^= k[i - 3];
+                    }
+                    tmp ^= $name::rotate_right(tmp, 1);
+                    let z = (Z[$j] >> (61 - (i - $m) % 62)) & 1;
+                    k[i] = (!k[i - $m] & $mask) ^ tmp ^ (z as $word_type) ^ 3;
+                }
+
+                Self { k }
+            }
+        }
+
+        impl BlockSizeUser for $name {
+            type BlockSize = $block_size;
+        }
+
+        impl ParBlocksSizeUser for $name {
+            type ParBlocksSize = U1;
+        }
+
+        impl BlockCipherEncrypt for $name {
+            #[inline]
+            fn encrypt_with_backend(
+                &self,
+                f: impl BlockCipherEncClosure<BlockSize = Self::BlockSize>,
+            ) {
+                f.call(self)
+            }
+        }
+
+        impl BlockCipherEncBackend for $name {
+            #[inline]
+            fn encrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
+                let b = block.get_in();
+                let mut x = $name::from_be_bytes(&b[0..($n / 8)]);
+                let mut y = $name::from_be_bytes(&b[($n / 8)..2 * ($n / 8)]);
+                for k in self.k {
+                    (x, y) = (y ^ $name::f(x) ^ k, x);
+                }
+
+                let b = block.get_out();
+                b[0..($n / 8)].copy_from_slice(&$name::to_be_bytes(x));
+                b[($n / 8)..2 * ($n / 8)].copy_from_slice(&$name::to_be_bytes(y));
+            }
+        }
+
+        impl BlockCipherDecrypt for $name {
+            #[inline]
+            fn decrypt_with_backend(
+                &self,
+                f: impl BlockCipherDecClosure<BlockSize = Self::BlockSize>,
+            ) {
+                f.call(self)
+            }
+        }
+
+        impl BlockCipherDecBackend for $name {
+            #[inline]
+            fn decrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
+                let b = block.get_in();
+                let mut x = $name::from_be_bytes(&b[0..($n / 8)]);
+                let mut y = $name::from_be_bytes(&b[($n / 8)..2 * ($n / 8)]);
+                for k in self.k.into_iter().rev() {
+                    (x, y) = (y, x ^ $name::f(y) ^ k);
+                }
+
+                let b = block.get_out();
+                b[0..($n / 8)].copy_from_slice(&$name::to_be_bytes(x));
+                b[($n / 8)..2 * ($n / 8)].copy_from_slice(&$name::to_be_bytes(y));
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(concat!(stringify!($name), " { .. }"))
+            }
+        }
+
+        impl AlgorithmName for $name {
+            fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(stringify!($name))
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                #[cfg(feature = "zeroize")]
+                self.k.zeroize();
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl ZeroizeOnDrop for $name {}
+    };
+}
+
+define_simon_impl!(Simon32_64, U4, U8, u16, 16, 4, 0, 0xFFFF, 32, "Simon32/64");
+define_simon_impl!(
+    Simon48_72,
+    U6,
+    U9,
+    u32,
+    24,
+    3,
+    0,
+    0xFFFFFF,
+    36,
+    "Simon48/72"
+);
+define_simon_impl!(
+    Simon48_96,
+    U6,
+    U12,
+    u32,
+    24,
+    4,
+    1,
+    0xFFFFFF,
+    36,
+    "Simon48/96"
+);
+define_simon_impl!(
+    Simon64_96,
+    U8,
+    U12,
+    u32,
+    32,
+    3,
+    2,
+    0xFFFFFFFF,
+    42,
+    "Simon64/96"
+);
+define_simon_impl!(
+    Simon64_128,
+    U8,
+    U16,
+    u32,
+    32,
+    4,
+    3,
+    0xFFFFFFFF,
+    44,
+    "Simon64/128"
+);
+define_simon_impl!(
+    Simon96_96,
+    U12,
+    U12,
+    u64,
+    48,
+    2,
+    2,
+    0xFFFFFFFFFFFF,
+    52,
+    "Simon96/96"
+);
+define_simon_impl!(
+    Simon96_144,
+    U12,
+    U18,
+    u64,
+    48,
+    3,
+    3,
+    0xFFFFFFFFFFFF,
+    54,
+    "Simon96/144"
+);
+define_simon_impl!(
+    Simon128_128,
+    U16,
+    U16,
+    u64,
+    64,
+    2,
+    2,
+    0xFFFFFFFFFFFFFFFF,
+    68,
+    "Simon128/128"
+);
+define_simon_impl!(
+    Simon128_192,
+    U16,
+    U24,
+    u64,
+    64,
+    3,
+    3,
+    0xFFFFFFFFFFFFFFFF,
+    69,
+    "Simon128/192"
+);
+define_simon_impl!(
+    Simon128_256,
+    U16,
+    U32,
+    u64,
+    64,
+    4,
+    4,
+    0xFFFFFFFFFFFFFFFF,
+    72,
+    "Simon128/256"
+);
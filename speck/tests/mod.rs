@@ -1,6 +1,6 @@
 //! Test vectors are from The Simon and Speck Families of Lightweight Block Ciphers (Appendix C)
 
-use cipher::{BlockCipherDecrypt, BlockCipherEncrypt, KeyInit};
+use cipher::{Array, BlockCipherDecrypt, BlockCipherEncrypt, KeyInit};
 use hex_literal::hex;
 use speck_cipher::{
     Speck32_64, Speck48_72, Speck48_96, Speck64_96, Speck64_128, Speck96_96, Speck96_144,
@@ -112,3 +112,21 @@ new_test!(
     "65736f6874206e49202e72656e6f6f70",
     "4109010405c0f53e4eeeb48d9c188f43"
 );
+
+/// Speck64 and Speck128 process 8 and 4 blocks per batch, the other variants
+/// one.
+#[test]
+fn par_blocks() {
+    fn check<C: KeyInit + BlockCipherEncrypt + BlockCipherDecrypt>(par: usize) {
+        let key = Array::from_fn(|i| (i * 7 + 1) as u8);
+        par_blocks_test::check_par_blocks(&C::new(&key), par);
+    }
+
+    check::<Speck32_64>(1);
+    check::<Speck64_96>(8);
+    check::<Speck64_128>(8);
+    check::<Speck96_96>(1);
+    check::<Speck128_128>(4);
+    check::<Speck128_192>(4);
+    check::<Speck128_256>(4);
+}
@@ -0,0 +1,298 @@
+//! The modes are generic over the block cipher, so they are checked against
+//! the AES examples of NIST SP 800-38B (also RFC 4493) and SP 800-38C, and
+//! CMAC with 64-bit blocks against the TDEA examples of NIST SP 800-38B.
+//!
+//! No CMAC or CCM test vectors have been published for Speck, and none exist
+//! for CCM with 64-bit blocks. The Speck vectors below are regression values
+//! produced by this crate.
+#![cfg(feature = "modes")]
+
+use aes::Aes128;
+use cipher::{
+    Array, KeyInit,
+    consts::{U4, U5, U6, U7, U8, U12, U13, U16},
+};
+use des::{TdesEde2, TdesEde3};
+use hex_literal::hex;
+use speck_cipher::{
+    Speck64_128, Speck128_128,
+    modes::{Ccm, Cmac, Error, ModeBlockSize},
+};
+
+const KEY64: [u8; 16] = hex!("1b1a1918131211100b0a090803020100");
+const KEY128: [u8; 16] = hex!("0f0e0d0c0b0a09080706050403020100");
+
+fn msg(len: usize) -> Vec<u8> {
+    (0..len as u8).collect()
+}
+
+#[test]
+fn cmac_aes128_sp800_38b() {
+    let mut mac = Cmac::<Aes128>::new(&hex!("2b7e151628aed2a6abf7158809cf4f3c").into());
+    let msg = hex!(
+        "6bc1bee22e409f96e93d7e117393172a"
+        "ae2d8a571e03ac9c9eb76fac45af8e51"
+        "30c81c46a35ce411e5fbc1191a0a52ef"
+        "f69f2445df4f9b17ad2b417be66c3710"
+    );
+    let vectors: [(usize, [u8; 16]); 4] = [
+        (0, hex!("bb1d6929e95937287fa37d129b756746")),
+        (16, hex!("070a16b46b4d4144f79bdd9dd04a287c")),
+        (40, hex!("dfa66747de9ae63030ca32611497c827")),
+        (64, hex!("51f0bebf7e3b9d92fc49741779363cfe")),
+    ];
+    for (len, tag) in vectors {
+        assert_eq!(mac.compute(&msg[..len]).unwrap(), tag);
+        mac.verify(&msg[..len], &tag).unwrap();
+    }
+}
+
+#[test]
+fn cmac_tdea_sp800_38b() {
+    let msg = hex!(
+        "6bc1bee22e409f96e93d7e117393172a"
+        "ae2d8a571e03ac9c9eb76fac45af8e51"
+    );
+
+    // Appendix D.4, three key TDEA
+    let key = hex!("8aa83bf8cbda10620bc1bf19fbb6cd58bc313d4a371ca8b5");
+    let mut mac = Cmac::<TdesEde3>::new(&key.into());
+    let vectors: [(usize, [u8; 8]); 3] = [
+        (0, hex!("b7a688e122ffaf95")),
+        (20, hex!("743ddbe0ce2dc2ed")),
+        (32, hex!("33e6b1092400eae5")),
+    ];
+    for (len, tag) in vectors {
+        assert_eq!(mac.compute(&msg[..len]).unwrap(), tag);
+        mac.verify(&msg[..len], &tag).unwrap();
+    }
+
+    // Appendix D.3, two key TDEA
+    let key = hex!("4cf15134a2850dd58a3d10ba80570d38");
+    let mut mac = Cmac::<TdesEde2>::new(&key.into());
+    let vectors: [(usize, [u8; 8]); 3] = [
+        (0, hex!("bd2ebf9a3ba00361")),
+        (20, hex!("62dd1b471902bd4e")),
+        (32, hex!("31b1e431dabc4eb8")),
+    ];
+    for (len, tag) in vectors {
+        assert_eq!(mac.compute(&msg[..len]).unwrap(), tag);
+        mac.verify(&msg[..len], &tag).unwrap();
+    }
+}
+
+#[test]
+fn cmac_speck64_128() {
+    let mut mac = Cmac::<Speck64_128>::new(&KEY64.into());
+    let vectors: [(usize, [u8; 8]); 5] = [
+        (0, hex!("640f874e94768ce4")),
+        (3, hex!("6195fc93e16a4b11")),
+        (8, hex!("9f8d585616d07867")),
+        (16, hex!("a3e3122fdb5f3589")),
+        (40, hex!("86f32f5c484001fe")),
+    ];
+    for (len, tag) in vectors {
+        assert_eq!(mac.compute(&msg(len)).unwrap(), tag);
+        mac.verify(&msg(len), &tag).unwrap();
+        mac.verify(&msg(len), &tag[..4]).unwrap();
+    }
+}
+
+#[test]
+fn cmac_speck128_128() {
+    let mut mac = Cmac::<Speck128_128>::new(&KEY128.into());
+    let vectors: [(usize, [u8; 16]); 5] = [
+        (0, hex!("2d3911608b973f14341d5ea3e9a5d6c5")),
+        (3, hex!("8d1d8fb5cc442e02b24a15d599b04414")),
+        (16, hex!("8189c67ca5e6767fe795227b9bbc00a4")),
+        (32, hex!("df4b13bd8fec78719336d6b959f41500")),
+        (40, hex!("3e7a0de9aa8e9a1d53bdb86a92a3d2c9")),
+    ];
+    for (len, tag) in vectors {
+        assert_eq!(mac.compute(&msg(len)).unwrap(), tag);
+        mac.verify(&msg(len), &tag).unwrap();
+    }
+}
+
+#[test]
+fn cmac_rejects_bad_tags() {
+    let mut mac = Cmac::<Speck64_128>::new(&KEY64.into());
+    let mut tag = mac.compute(b"message").unwrap();
+    assert_eq!(mac.verify(b"message", &tag[..3]), Err(Error::Verification));
+    tag[7] ^= 1;
+    assert_eq!(mac.verify(b"message", &tag), Err(Error::Verification));
+    assert_eq!(mac.verify(b"massage", &tag[..7]), Err(Error::Verification));
+}
+
+fn check_ccm<C, M, N>(key: &[u8], nonce: &[u8], ad: &[u8], pt: &[u8], expected: &[u8])
+where
+    C: cipher::BlockCipherEncrypt + KeyInit,
+    C::BlockSize: ModeBlockSize,
+    M: cipher::array::ArraySize,
+    N: cipher::array::ArraySize,
+{
+    let mut ccm = Ccm::<C, M, N>::new_from_slice(key).unwrap();
+    let nonce = Array::try_from(nonce).unwrap();
+
+    let mut buf = pt.to_vec();
+    let tag = ccm.encrypt_in_place_detached(&nonce, ad, &mut buf).unwrap();
+    assert_eq!(buf, expected[..pt.len()]);
+    assert_eq!(tag[..], expected[pt.len()..]);
+
+    ccm.decrypt_in_place_detached(&nonce, ad, &mut buf, &tag)
+        .unwrap();
+    assert_eq!(buf, pt);
+
+    // any modification must be detected
+    let mut bad_tag = tag.clone();
+    bad_tag[0] ^= 1;
+    let mut buf = expected[..pt.len()].to_vec();
+    let res = ccm.decrypt_in_place_detached(&nonce, ad, &mut buf, &bad_tag);
+    assert_eq!(res, Err(Error::Verification));
+    assert!(buf.iter().all(|&b| b == 0));
+
+    if !ad.is_empty() {
+        let mut buf = expected[..pt.len()].to_vec();
+        let res = ccm.decrypt_in_place_detached(&nonce, &ad[1..], &mut buf, &tag);
+        assert_eq!(res, Err(Error::Verification));
+    }
+}
+
+#[test]
+fn ccm_aes128_sp800_38c() {
+    let key = hex!("404142434445464748494a4b4c4d4e4f");
+    let ad: Vec<u8> = (0..20).collect();
+    let pt: Vec<u8> = (0x20..0x38).collect();
+    check_ccm::<Aes128, U4, U7>(
+        &key,
+        &hex!("10111213141516"),
+        &ad[..8],
+        &pt[..4],
+        &hex!("7162015b4dac255d"),
+    );
+    check_ccm::<Aes128, U6, U8>(
+        &key,
+        &hex!("1011121314151617"),
+        &ad[..16],
+        &pt[..16],
+        &hex!("d2a1f0e051ea5f62081a7792073d593d1fc64fbfaccd"),
+    );
+    check_ccm::<Aes128, U8, U12>(
+        &key,
+        &hex!("101112131415161718191a1b"),
+        &ad,
+        &pt,
+        &hex!(
+            "e3b201a9f5b71a7a9b1ceaeccd97e70b6176aad9a4428aa5"
+            "484392fbc1b09951"
+        ),
+    );
+}
+
+#[test]
+fn ccm_speck64_128() {
+    let ad = hex!("202122232425262728292a");
+    let pt: Vec<u8> = (0x40..0x40 + 23).collect();
+    check_ccm::<Speck64_128, U8, U5>(
+        &KEY64,
+        &hex!("a0a1a2a3a4"),
+        &ad,
+        &pt,
+        &hex!("bbb0b8b12065a0a842be336a95c699cfe7ca14c77aca83e954bd3d6a9ffd9f"),
+    );
+    check_ccm::<Speck64_128, U8, U5>(
+        &KEY64,
+        &hex!("a0a1a2a3a4"),
+        &ad,
+        &[],
+        &hex!("29aeb30c36f1f86a"),
+    );
+    check_ccm::<Speck64_128, U4, U4>(
+        &KEY64,
+        &hex!("b0b1b2b3"),
+        &[],
+        &pt[..16],
+        &hex!("d75d66963174fcddbf8d1f6aa70dd41175cb9557"),
+    );
+}
+
+#[test]
+fn ccm_speck128_128() {
+    let ad = hex!("202122232425262728292a");
+    let pt: Vec<u8> = (0x40..0x40 + 32).collect();
+    check_ccm::<Speck128_128, U16, U13>(
+        &KEY128,
+        &hex!("c0c1c2c3c4c5c6c7c8c9cacbcc"),
+        &ad,
+        &pt[..23],
+        &hex!(
+            "85d320d2f6ebb7b1de7486722003c433ee16b32346a2af92f49d98834b33de0e"
+            "4ee27a4d8e5bfb"
+        ),
+    );
+    check_ccm::<Speck128_128, U8, U7>(
+        &KEY128,
+        &hex!("d0d1d2d3d4d5d6"),
+        &[],
+        &pt,
+        &hex!(
+            "23ee47082b40c2de734e25bc9f0462dd798b172a8ee10d9ebf47880ee01998a4"
+            "cf53b2419c1196ba"
+        ),
+    );
+}
+
+#[test]
+fn ccm_message_length_limit() {
+    let mut ccm = Ccm::<Speck64_128, U8, U5>::new(&KEY64.into());
+    let nonce = Array::default();
+    let mut buf = vec![0u8; 1 << 16];
+    let res = ccm.encrypt_in_place_detached(&nonce, &[], &mut buf);
+    assert_eq!(res, Err(Error::MessageTooLong));
+    assert!(buf.iter().all(|&b| b == 0));
+    assert!(
+        ccm.encrypt_in_place_detached(&nonce, &[], &mut buf[1..])
+            .is_ok()
+    );
+}
+
+#[test]
+fn data_limit_is_enforced() {
+    let limit = <cipher::consts::U8 as ModeBlockSize>::MAX_BLOCKS;
+
+    let mut mac = Cmac::<Speck64_128>::new(&KEY64.into());
+    assert_eq!(mac.remaining_blocks(), limit - 1);
+    let msg = vec![0u8; 8 * 4096];
+    while mac.remaining_blocks() >= 4096 {
+        mac.compute(&msg).unwrap();
+    }
+    let remaining = mac.remaining_blocks();
+    assert_eq!(mac.compute(&msg), Err(Error::DataLimitExceeded));
+    assert_eq!(mac.remaining_blocks(), remaining);
+    mac.compute(&msg[..8 * remaining as usize]).unwrap();
+    assert_eq!(mac.remaining_blocks(), 0);
+    assert_eq!(mac.compute(&[]), Err(Error::DataLimitExceeded));
+    assert_eq!(mac.verify(&[], &[0; 8]), Err(Error::DataLimitExceeded));
+    assert_eq!(mac.remaining_blocks(), 0);
+
+    let mut ccm = Ccm::<Speck64_128, U8, U5>::new(&KEY64.into());
+    let nonce = Array::default();
+    let mut buf = vec![0u8; 8 * 1024];
+    // 1 + 1024 blocks of CBC-MAC and 1 + 1024 blocks of CTR per message
+    assert_eq!(ccm.remaining_blocks(), limit);
+    ccm.encrypt_in_place_detached(&nonce, &[], &mut buf)
+        .unwrap();
+    assert_eq!(ccm.remaining_blocks(), limit - 2050);
+    let mut tag = Array::default();
+    while ccm.remaining_blocks() >= 2050 {
+        tag = ccm
+            .encrypt_in_place_detached(&nonce, &[], &mut buf)
+            .unwrap();
+    }
+    let snapshot = buf.clone();
+    let res = ccm.encrypt_in_place_detached(&nonce, &[], &mut buf);
+    assert_eq!(res, Err(Error::DataLimitExceeded));
+    assert_eq!(buf, snapshot);
+    let res = ccm.decrypt_in_place_detached(&nonce, &[], &mut buf, &tag);
+    assert_eq!(res, Err(Error::DataLimitExceeded));
+}
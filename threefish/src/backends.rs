@@ -0,0 +1,252 @@
+//! Multi-block Threefish backends.
+//!
+//! Blocks are processed in a word-sliced layout: lane `k` of vector `i` holds
+//! word `i` of block `k`. This way every MIX operation of a round works on all
+//! [`PAR`] blocks at once and the word permutation becomes a simple renaming
+//! of vectors.
+#![allow(unsafe_code)]
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    not(threefish_backend = "soft")
+))]
+mod avx2;
+#[cfg(all(
+    target_arch = "aarch64",
+    target_feature = "neon",
+    not(threefish_backend = "soft")
+))]
+mod neon;
+
+/// Number of blocks processed in parallel.
+pub(crate) const PAR: usize = 4;
+
+/// Vector of [`PAR`] 64-bit lanes.
+///
+/// The methods of SIMD implementations are compiled with the target features
+/// of their intrinsics, so callers must ensure that the CPU supports them.
+pub(crate) trait Lanes: Copy {
+    unsafe fn load(w: &[u64; PAR]) -> Self;
+    unsafe fn store(self, w: &mut [u64; PAR]);
+    unsafe fn splat(v: u64) -> Self;
+    unsafe fn add(self, rhs: Self) -> Self;
+    unsafe fn sub(self, rhs: Self) -> Self;
+    unsafe fn xor(self, rhs: Self) -> Self;
+    unsafe fn rotl(self, r: u32) -> Self;
+    unsafe fn rotr(self, r: u32) -> Self;
+}
+
+impl Lanes for [u64; PAR] {
+    #[inline(always)]
+    unsafe fn load(w: &[u64; PAR]) -> Self {
+        *w
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, w: &mut [u64; PAR]) {
+        *w = self;
+    }
+
+    #[inline(always)]
+    unsafe fn splat(v: u64) -> Self {
+        [v; PAR]
+    }
+
+    #[inline(always)]
+    unsafe fn add(self, rhs: Self) -> Self {
+        core::array::from_fn(|k| self[k].wrapping_add(rhs[k]))
+    }
+
+    #[inline(always)]
+    unsafe fn sub(self, rhs: Self) -> Self {
+        core::array::from_fn(|k| self[k].wrapping_sub(rhs[k]))
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        core::array::from_fn(|k| self[k] ^ rhs[k])
+    }
+
+    #[inline(always)]
+    unsafe fn rotl(self, r: u32) -> Self {
+        self.map(|v| v.rotate_left(r))
+    }
+
+    #[inline(always)]
+    unsafe fn rotr(self, r: u32) -> Self {
+        self.map(|v| v.rotate_right(r))
+    }
+}
+
+/// Transpose `blocks` into the word-sliced layout.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+unsafe fn load<V: Lanes, const N: usize>(blocks: &[[u64; N]; PAR]) -> [V; N] {
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        let mut x = [V::splat(0); N];
+        for (i, v) in x.iter_mut().enumerate() {
+            *v = V::load(&core::array::from_fn(|k| blocks[k][i]));
+        }
+        x
+    }
+}
+
+/// Transpose the word-sliced state `x` back into `blocks`.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+unsafe fn store<V: Lanes, const N: usize>(x: &[V; N], blocks: &mut [[u64; N]; PAR]) {
+    for (i, v) in x.iter().enumerate() {
+        let mut w = [0u64; PAR];
+        // SAFETY: guaranteed by the caller
+        unsafe { v.store(&mut w) };
+        for (block, w) in blocks.iter_mut().zip(w) {
+            block[i] = w;
+        }
+    }
+}
+
+/// Encrypt [`PAR`] blocks using `sk(s, i)` as word `i` of subkey `s`.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+pub(crate) unsafe fn encrypt<V: Lanes, const N: usize, const H: usize>(
+    blocks: &mut [[u64; N]; PAR],
+    rounds: usize,
+    rot: &[[u8; H]; 8],
+    perm: &[u8; N],
+    sk: impl Fn(usize, usize) -> u64,
+) {
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        let mut x: [V; N] = load(blocks);
+
+        for d in 0..rounds {
+            let x_prev = x;
+            for j in 0..H {
+                let (mut e0, mut e1) = (x_prev[2 * j], x_prev[2 * j + 1]);
+                if d % 4 == 0 {
+                    e0 = e0.add(V::splat(sk(d / 4, 2 * j)));
+                    e1 = e1.add(V::splat(sk(d / 4, 2 * j + 1)));
+                }
+                let r = rot[d % 8][j] as u32;
+                let f0 = e0.add(e1);
+                let f1 = e1.rotl(r).xor(f0);
+                x[perm[2 * j] as usize] = f0;
+                x[perm[2 * j + 1] as usize] = f1;
+            }
+        }
+
+        for (i, v) in x.iter_mut().enumerate() {
+            *v = v.add(V::splat(sk(rounds / 4, i)));
+        }
+
+        store(&x, blocks);
+    }
+}
+
+/// Decrypt [`PAR`] blocks using `sk(s, i)` as word `i` of subkey `s`.
+///
+/// # Safety
+/// The CPU must support the target features of `V`.
+#[inline(always)]
+pub(crate) unsafe fn decrypt<V: Lanes, const N: usize, const H: usize>(
+    blocks: &mut [[u64; N]; PAR],
+    rounds: usize,
+    rot: &[[u8; H]; 8],
+    perm: &[u8; N],
+    sk: impl Fn(usize, usize) -> u64,
+) {
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        let mut x: [V; N] = load(blocks);
+
+        for (i, v) in x.iter_mut().enumerate() {
+            *v = v.sub(V::splat(sk(rounds / 4, i)));
+        }
+
+        for d in (0..rounds).rev() {
+            let x_prev = x;
+            for j in 0..H {
+                let f0 = x_prev[perm[2 * j] as usize];
+                let f1 = x_prev[perm[2 * j + 1] as usize];
+                let r = rot[d % 8][j] as u32;
+                let mut e1 = f0.xor(f1).rotr(r);
+                let mut e0 = f0.sub(e1);
+                if d % 4 == 0 {
+                    e0 = e0.sub(V::splat(sk(d / 4, 2 * j)));
+                    e1 = e1.sub(V::splat(sk(d / 4, 2 * j + 1)));
+                }
+                x[2 * j] = e0;
+                x[2 * j + 1] = e1;
+            }
+        }
+
+        store(&x, blocks);
+    }
+}
+
+/// Encrypt [`PAR`] blocks using the fastest backend available on the current CPU.
+#[inline(always)]
+pub(crate) fn encrypt_par<const N: usize, const H: usize>(
+    blocks: &mut [[u64; N]; PAR],
+    rounds: usize,
+    rot: &[[u8; H]; 8],
+    perm: &[u8; N],
+    sk: impl Fn(usize, usize) -> u64,
+) {
+    cfg_if::cfg_if! {
+        if #[cfg(all(
+            target_arch = "aarch64",
+            target_feature = "neon",
+            not(threefish_backend = "soft")
+        ))] {
+            neon::encrypt_par(blocks, rounds, rot, perm, sk)
+        } else {
+            #[cfg(all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                not(threefish_backend = "soft")
+            ))]
+            if let Some(token) = avx2::Token::get() {
+                return avx2::encrypt_par(token, blocks, rounds, rot, perm, sk);
+            }
+            // SAFETY: the portable lanes use no target features
+            unsafe { encrypt::<[u64; PAR], N, H>(blocks, rounds, rot, perm, sk) }
+        }
+    }
+}
+
+/// Decrypt [`PAR`] blocks using the fastest backend available on the current CPU.
+#[inline(always)]
+pub(crate) fn decrypt_par<const N: usize, const H: usize>(
+    blocks: &mut [[u64; N]; PAR],
+    rounds: usize,
+    rot: &[[u8; H]; 8],
+    perm: &[u8; N],
+    sk: impl Fn(usize, usize) -> u64,
+) {
+    cfg_if::cfg_if! {
+        if #[cfg(all(
+            target_arch = "aarch64",
+            target_feature = "neon",
+            not(threefish_backend = "soft")
+        ))] {
+            neon::decrypt_par(blocks, rounds, rot, perm, sk)
+        } else {
+            #[cfg(all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                not(threefish_backend = "soft")
+            ))]
+            if let Some(token) = avx2::Token::get() {
+                return avx2::decrypt_par(token, blocks, rounds, rot, perm, sk);
+            }
+            // SAFETY: the portable lanes use no target features
+            unsafe { decrypt::<[u64; PAR], N, H>(blocks, rounds, rot, perm, sk) }
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! AVX2 backend which processes four blocks using 256-bit vectors.
+//!
+//! The methods of `Avx2Lanes` are compiled with AVX2 enabled, and the round
+//! functions using them are only called with a [`Token`] proving that the CPU
+//! supports it.
+#![allow(unsafe_code, unsafe_op_in_unsafe_fn)]
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::{Lanes, PAR};
+
+cpufeatures::new!(avx2_cpuid, "avx2");
+
+/// Proof that AVX2 is supported by the current CPU.
+#[derive(Clone, Copy)]
+pub(super) struct Token(());
+
+impl Token {
+    #[inline(always)]
+    pub(super) fn get() -> Option<Self> {
+        avx2_cpuid::get().then_some(Token(()))
+    }
+}
+
+/// Four 64-bit lanes.
+#[derive(Clone, Copy)]
+struct Avx2Lanes(__m256i);
+
+impl Lanes for Avx2Lanes {
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn load(w: &[u64; PAR]) -> Self {
+        Self(_mm256_loadu_si256(w.as_ptr().cast()))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn store(self, w: &mut [u64; PAR]) {
+        _mm256_storeu_si256(w.as_mut_ptr().cast(), self.0)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn splat(v: u64) -> Self {
+        Self(_mm256_set1_epi64x(v as i64))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn add(self, rhs: Self) -> Self {
+        Self(_mm256_add_epi64(self.0, rhs.0))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn sub(self, rhs: Self) -> Self {
+        Self(_mm256_sub_epi64(self.0, rhs.0))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        Self(_mm256_xor_si256(self.0, rhs.0))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn rotl(self, r: u32) -> Self {
+        let l = _mm_cvtsi32_si128(r as i32);
+        let r = _mm_cvtsi32_si128(64 - r as i32);
+        Self(_mm256_or_si256(
+            _mm256_sll_epi64(self.0, l),
+            _mm256_srl_epi64(self.0, r),
+        ))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn rotr(self, r: u32) -> Self {
+        self.rotl(64 - r)
+    }
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn encrypt_avx2<const N: usize, const H: usize>(
+    blocks: &mut [[u64; N]; PAR],
+    rounds: usize,
+    rot: &[[u8; H]; 8],
+    perm: &[u8; N],
+    sk: impl Fn(usize, usize) -> u64,
+) {
+    // SAFETY: AVX2 is enabled for this function
+    unsafe { super::encrypt::<Avx2Lanes, N, H>(blocks, rounds, rot, perm, sk) }
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn decrypt_avx2<const N: usize, const H: usize>(
+    blocks: &mut [[u64; N]; PAR],
+    rounds: usize,
+    rot: &[[u8; H]; 8],
+    perm: &[u8; N],
+    sk: impl Fn(usize, usize) -> u64,
+) {
+    // SAFETY: AVX2 is enabled for this function
+    unsafe { super::decrypt::<Avx2Lanes, N, H>(blocks, rounds, rot, perm, sk) }
+}
+
+#[inline(always)]
+pub(super) fn encrypt_par<const N: usize, const H: usize>(
+    _token: Token,
+    blocks: &mut [[u64; N]; PAR],
+    rounds: usize,
+    rot: &[[u8; H]; 8],
+    perm: &[u8; N],
+    sk: impl Fn(usize, usize) -> u64,
+) {
+    // SAFETY: `_token` proves that the CPU supports AVX2
+    unsafe { encrypt_avx2(blocks, rounds, rot, perm, sk) }
+}
+
+#[inline(always)]
+pub(super) fn decrypt_par<const N: usize, const H: usize>(
+    _token: Token,
+    blocks: &mut [[u64; N]; PAR],
+    rounds: usize,
+    rot: &[[u8; H]; 8],
+    perm: &[u8; N],
+    sk: impl Fn(usize, usize) -> u64,
+) {
+    // SAFETY: `_token` proves that the CPU supports AVX2
+    unsafe { decrypt_avx2(blocks, rounds, rot, perm, sk) }
+}
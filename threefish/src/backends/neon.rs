@@ -0,0 +1,86 @@
+//! NEON backend which processes four blocks using pairs of 128-bit vectors.
+//!
+//! The module is only compiled when NEON is enabled for the whole crate with
+//! `target_feature = "neon"`, so the methods of `NeonLanes` can be called
+//! anywhere.
+#![allow(unsafe_code, unsafe_op_in_unsafe_fn)]
+
+use core::arch::aarch64::*;
+
+use super::{Lanes, PAR};
+
+/// Four 64-bit lanes held in two vectors.
+#[derive(Clone, Copy)]
+struct NeonLanes(uint64x2_t, uint64x2_t);
+
+impl Lanes for NeonLanes {
+    #[inline(always)]
+    unsafe fn load(w: &[u64; PAR]) -> Self {
+        Self(vld1q_u64(w.as_ptr()), vld1q_u64(w[2..].as_ptr()))
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, w: &mut [u64; PAR]) {
+        vst1q_u64(w.as_mut_ptr(), self.0);
+        vst1q_u64(w[2..].as_mut_ptr(), self.1);
+    }
+
+    #[inline(always)]
+    unsafe fn splat(v: u64) -> Self {
+        let v = vdupq_n_u64(v);
+        Self(v, v)
+    }
+
+    #[inline(always)]
+    unsafe fn add(self, rhs: Self) -> Self {
+        Self(vaddq_u64(self.0, rhs.0), vaddq_u64(self.1, rhs.1))
+    }
+
+    #[inline(always)]
+    unsafe fn sub(self, rhs: Self) -> Self {
+        Self(vsubq_u64(self.0, rhs.0), vsubq_u64(self.1, rhs.1))
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, rhs: Self) -> Self {
+        Self(veorq_u64(self.0, rhs.0), veorq_u64(self.1, rhs.1))
+    }
+
+    #[inline(always)]
+    unsafe fn rotl(self, r: u32) -> Self {
+        // `vshlq_u64` shifts right for negative shift amounts
+        let l = vdupq_n_s64(r as i64);
+        let r = vdupq_n_s64(r as i64 - 64);
+        let rot = |v| vorrq_u64(vshlq_u64(v, l), vshlq_u64(v, r));
+        Self(rot(self.0), rot(self.1))
+    }
+
+    #[inline(always)]
+    unsafe fn rotr(self, r: u32) -> Self {
+        self.rotl(64 - r)
+    }
+}
+
+#[inline(always)]
+pub(super) fn encrypt_par<const N: usize, const H: usize>(
+    blocks: &mut [[u64; N]; PAR],
+    rounds: usize,
+    rot: &[[u8; H]; 8],
+    perm: &[u8; N],
+    sk: impl Fn(usize, usize) -> u64,
+) {
+    // SAFETY: NEON is enabled at compile time
+    unsafe { super::encrypt::<NeonLanes, N, H>(blocks, rounds, rot, perm, sk) }
+}
+
+#[inline(always)]
+pub(super) fn decrypt_par<const N: usize, const H: usize>(
+    blocks: &mut [[u64; N]; PAR],
+    rounds: usize,
+    rot: &[[u8; H]; 8],
+    perm: &[u8; N],
+    sk: impl Fn(usize, usize) -> u64,
+) {
+    // SAFETY: NEON is enabled at compile time
+    unsafe { super::decrypt::<NeonLanes, N, H>(blocks, rounds, rot, perm, sk) }
+}
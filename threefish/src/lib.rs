@@ -8,6 +8,29 @@
 //!
 //! USE AT YOUR OWN RISK!
 //!
+//! # Backends
+//!
+//! Multiple blocks are processed in parallel using AVX2 on x86 targets (with
+//! runtime CPU feature detection) and NEON on AArch64 targets. Other targets
+//! use a portable software implementation.
+//!
+//! # Configuration Flags
+//!
+//! You can force the portable software backend using the `threefish_backend`
+//! configuration flag set to `soft`.
+//!
+//! The flag can be enabled using `RUSTFLAGS` environment variable
+//! (e.g. `RUSTFLAGS='--cfg threefish_backend="soft"'`) or by modifying
+//! `.cargo/config`.
+//!
+//! # Serialization
+//!
+//! With the `serde` feature enabled, the fixed-tweak cipher types implement
+//! `Serialize` and `Deserialize` using their expanded subkeys. The `serde`
+//! feature also enables `zeroize`, so the subkeys of deserialized instances
+//! are erased on drop. Note that serialized data contains key material and
+//! must be protected accordingly.
+//!
 //! [Threefish]: https://en.wikipedia.org/wiki/Threefish
 
 #![no_std]
@@ -28,12 +51,15 @@ use core::fmt;
 use cipher::{
     AlgorithmName, Block, BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherDecrypt,
     BlockCipherEncBackend, BlockCipherEncClosure, BlockCipherEncrypt, BlockSizeUser, InOut, Key,
-    KeyInit, KeySizeUser, ParBlocksSizeUser,
-    consts::{U1, U32, U64, U128},
+    KeyInit, KeySizeUser, ParBlocks, ParBlocksSizeUser,
+    consts::{U4, U32, U64, U128},
 };
 
+mod backends;
 mod consts;
 
+use crate::backends::PAR;
+
 use crate::consts::{C240, P256, P512, P1024, R256, R512, R1024};
 
 #[cfg(feature = "zeroize")]
@@ -53,7 +79,7 @@ fn inv_mix(r: u8, y: (u64, u64)) -> (u64, u64) {
 
 macro_rules! impl_threefish(
     (
-        $name:ident, $rounds:expr, $n_w:expr, $block_size:ty,
+        $name:ident, $tweakable:ident, $rounds:expr, $n_w:expr, $block_size:ty,
         $rot:expr, $perm:expr, $doc_name:expr
     ) => (
         #[doc=$doc_name]
@@ -67,53 +93,223 @@ macro_rules! impl_threefish(
             /// Create new block cipher instance with the given key and tweak.
             #[inline(always)]
             pub fn new_with_tweak(key: &[u8; $n_w*8], tweak: &[u8; 16]) -> $name {
-                let mut k = [0u64; $n_w];
-                for (kv, chunk) in k[..$n_w].iter_mut().zip(key.chunks_exact(8)) {
-                    *kv = u64::from_le_bytes(chunk.try_into().unwrap());
-                }
-                let tweak = [
-                    u64::from_le_bytes(tweak[..8].try_into().unwrap()),
-                    u64::from_le_bytes(tweak[8..].try_into().unwrap()),
-                ];
+                let k = Self::words_from_bytes(key);
+                let tweak = Self::tweak_from_bytes(tweak);
                 Self::new_with_tweak_u64(&k, &tweak)
             }
 
             /// Create new block cipher instance with the given key and tweak
             /// represented in the form of array of `u64`s.
+            ///
+            /// This function is `const` and can be used to compute the key
+            /// schedule at compile time.
             #[inline(always)]
-            pub fn new_with_tweak_u64(key: &[u64; $n_w], tweak: &[u64; 2]) -> $name {
-                let mut k = [0u64; $n_w + 1];
-                k[..$n_w].copy_from_slice(key);
-                k[$n_w] = key.iter().fold(C240, core::ops::BitXor::bitxor);
-                let t = [tweak[0], tweak[1], tweak[0] ^ tweak[1]];
+            pub const fn new_with_tweak_u64(key: &[u64; $n_w], tweak: &[u64; 2]) -> $name {
+                let k = Self::extend_key(key);
+                let t = Self::extend_tweak(tweak);
 
+                // `for` loops can not be used in `const fn`
                 let mut sk = [[0u64; $n_w]; $rounds / 4 + 1];
-                for s in 0..=($rounds / 4) {
-                    for i in 0..$n_w {
-                        sk[s][i] = k[(s + i) % ($n_w + 1)];
-                        if i == $n_w - 3 {
-                            sk[s][i] = sk[s][i].wrapping_add(t[s % 3]);
-                        } else if i == $n_w - 2 {
-                            sk[s][i] = sk[s][i].wrapping_add(t[(s + 1) % 3]);
-                        } else if i == $n_w - 1 {
-                            sk[s][i] = sk[s][i].wrapping_add(s as u64);
-                        }
+                let mut s = 0;
+                while s <= $rounds / 4 {
+                    let mut i = 0;
+                    while i < $n_w {
+                        sk[s][i] = Self::subkey(&k, &t, s, i);
+                        i += 1;
                     }
+                    s += 1;
                 }
 
                 $name { sk }
             }
 
+            /// Load little-endian bytes into an array of `u64`s.
+            #[inline(always)]
+            fn words_from_bytes(bytes: &[u8; $n_w*8]) -> [u64; $n_w] {
+                let mut v = [0u64; $n_w];
+                for (vv, chunk) in v.iter_mut().zip(bytes.chunks_exact(8)) {
+                    *vv = u64::from_le_bytes(chunk.try_into().unwrap());
+                }
+                v
+            }
+
+            /// Store an array of `u64`s as little-endian bytes.
+            #[inline(always)]
+            fn words_to_bytes(v: &[u64; $n_w], bytes: &mut [u8; $n_w*8]) {
+                for (chunk, vv) in bytes.chunks_exact_mut(8).zip(v.iter()) {
+                    chunk.copy_from_slice(&vv.to_le_bytes());
+                }
+            }
+
+            #[cfg(feature = "cipher")]
+            #[inline(always)]
+            fn par_words_from_bytes(blocks: &ParBlocks<Self>) -> [[u64; $n_w]; PAR] {
+                core::array::from_fn(|i| Self::words_from_bytes(blocks[i].as_ref()))
+            }
+
+            #[cfg(feature = "cipher")]
+            #[inline(always)]
+            fn par_words_to_bytes(v: &[[u64; $n_w]; PAR], blocks: &mut ParBlocks<Self>) {
+                for (v, block) in v.iter().zip(blocks.iter_mut()) {
+                    Self::words_to_bytes(v, block.as_mut());
+                }
+            }
+
+            #[inline(always)]
+            fn tweak_from_bytes(tweak: &[u8; 16]) -> [u64; 2] {
+                [
+                    u64::from_le_bytes(tweak[..8].try_into().unwrap()),
+                    u64::from_le_bytes(tweak[8..].try_into().unwrap()),
+                ]
+            }
+
+            /// Append the key parity word to `key`.
+            #[inline(always)]
+            const fn extend_key(key: &[u64; $n_w]) -> [u64; $n_w + 1] {
+                let mut k = [0u64; $n_w + 1];
+                k[$n_w] = C240;
+                let mut i = 0;
+                while i < $n_w {
+                    k[i] = key[i];
+                    k[$n_w] ^= key[i];
+                    i += 1;
+                }
+                k
+            }
+
+            /// Append the tweak parity word to `tweak`.
+            #[inline(always)]
+            const fn extend_tweak(tweak: &[u64; 2]) -> [u64; 3] {
+                [tweak[0], tweak[1], tweak[0] ^ tweak[1]]
+            }
+
+            /// Indices `(s + i) mod (N_w + 1)` of the extended key words in
+            /// word `i` of subkey `s`.
+            const KEY_IDX: [[u8; $n_w]; $rounds / 4 + 1] = {
+                let mut idx = [[0; $n_w]; $rounds / 4 + 1];
+                let mut s = 0;
+                while s < $rounds / 4 + 1 {
+                    let mut i = 0;
+                    while i < $n_w {
+                        idx[s][i] = ((s + i) % ($n_w + 1)) as u8;
+                        i += 1;
+                    }
+                    s += 1;
+                }
+                idx
+            };
+
+            /// Indices `s mod 3` of the extended tweak words, with one extra
+            /// entry for `s + 1` of the last subkey.
+            const TWEAK_IDX: [u8; $rounds / 4 + 2] = {
+                let mut idx = [0; $rounds / 4 + 2];
+                let mut s = 0;
+                while s < $rounds / 4 + 2 {
+                    idx[s] = (s % 3) as u8;
+                    s += 1;
+                }
+                idx
+            };
+
+            /// Compute word `i` of subkey `s` from the extended key and tweak.
+            #[inline(always)]
+            const fn subkey(k: &[u64; $n_w + 1], t: &[u64; 3], s: usize, i: usize) -> u64 {
+                let v = k[Self::KEY_IDX[s][i] as usize];
+                if i == $n_w - 3 {
+                    v.wrapping_add(t[Self::TWEAK_IDX[s] as usize])
+                } else if i == $n_w - 2 {
+                    v.wrapping_add(t[Self::TWEAK_IDX[s + 1] as usize])
+                } else if i == $n_w - 1 {
+                    v.wrapping_add(s as u64)
+                } else {
+                    v
+                }
+            }
+
             /// Encrypt block in the form of array of `u64`s
             #[inline(always)]
             pub fn encrypt_block_u64(&self, block: &mut [u64; $n_w]) {
+                Self::encrypt_with_subkeys(block, |s, i| self.sk[s][i]);
+            }
+
+            /// Decrypt block in the form of array of `u64`s
+            #[inline(always)]
+            pub fn decrypt_block_u64(&self, block: &mut [u64; $n_w]) {
+                Self::decrypt_with_subkeys(block, |s, i| self.sk[s][i]);
+            }
+
+            /// Encrypt blocks in the form of arrays of `u64`s.
+            ///
+            /// Blocks are processed in parallel using SIMD instructions if
+            /// they are supported by the target CPU.
+            #[inline]
+            pub fn encrypt_blocks_u64(&self, blocks: &mut [[u64; $n_w]]) {
+                Self::encrypt_blocks_with_subkeys(blocks, |s, i| self.sk[s][i]);
+            }
+
+            /// Decrypt blocks in the form of arrays of `u64`s.
+            ///
+            /// Blocks are processed in parallel using SIMD instructions if
+            /// they are supported by the target CPU.
+            #[inline]
+            pub fn decrypt_blocks_u64(&self, blocks: &mut [[u64; $n_w]]) {
+                Self::decrypt_blocks_with_subkeys(blocks, |s, i| self.sk[s][i]);
+            }
+
+            #[inline(always)]
+            fn encrypt_blocks_with_subkeys(
+                blocks: &mut [[u64; $n_w]],
+                sk: impl Fn(usize, usize) -> u64 + Copy,
+            ) {
+                let mut chunks = blocks.chunks_exact_mut(PAR);
+                for chunk in &mut chunks {
+                    Self::encrypt_par_with_subkeys(chunk.try_into().unwrap(), sk);
+                }
+                for block in chunks.into_remainder() {
+                    Self::encrypt_with_subkeys(block, sk);
+                }
+            }
+
+            #[inline(always)]
+            fn decrypt_blocks_with_subkeys(
+                blocks: &mut [[u64; $n_w]],
+                sk: impl Fn(usize, usize) -> u64 + Copy,
+            ) {
+                let mut chunks = blocks.chunks_exact_mut(PAR);
+                for chunk in &mut chunks {
+                    Self::decrypt_par_with_subkeys(chunk.try_into().unwrap(), sk);
+                }
+                for block in chunks.into_remainder() {
+                    Self::decrypt_with_subkeys(block, sk);
+                }
+            }
+
+            #[inline(always)]
+            fn encrypt_par_with_subkeys(
+                blocks: &mut [[u64; $n_w]; PAR],
+                sk: impl Fn(usize, usize) -> u64,
+            ) {
+                backends::encrypt_par(blocks, $rounds, &$rot, &$perm, sk);
+            }
+
+            #[inline(always)]
+            fn decrypt_par_with_subkeys(
+                blocks: &mut [[u64; $n_w]; PAR],
+                sk: impl Fn(usize, usize) -> u64,
+            ) {
+                backends::decrypt_par(blocks, $rounds, &$rot, &$perm, sk);
+            }
+
+            /// Encrypt block using `sk(s, i)` as word `i` of subkey `s`.
+            #[inline(always)]
+            fn encrypt_with_subkeys(block: &mut [u64; $n_w], sk: impl Fn(usize, usize) -> u64) {
                 for d in 0..$rounds {
                     let block_prev = block.clone();
                     for j in 0..($n_w / 2) {
                         let v = (block_prev[2 * j], block_prev[2 * j + 1]);
                         let e = if d % 4 == 0 {
-                            let s0 = self.sk[d / 4][2 * j];
-                            let s1 = self.sk[d / 4][2 * j + 1];
+                            let s0 = sk(d / 4, 2 * j);
+                            let s1 = sk(d / 4, 2 * j + 1);
                             (v.0.wrapping_add(s0), v.1.wrapping_add(s1))
                         } else {
                             v
@@ -126,16 +322,16 @@ macro_rules! impl_threefish(
                     }
                 }
 
-                for (b, s) in block.iter_mut().zip(&self.sk[$rounds / 4]) {
-                    *b = b.wrapping_add(*s);
+                for (i, b) in block.iter_mut().enumerate() {
+                    *b = b.wrapping_add(sk($rounds / 4, i));
                 }
             }
 
-            /// Decrypt block in the form of array of `u64`s
+            /// Decrypt block using `sk(s, i)` as word `i` of subkey `s`.
             #[inline(always)]
-            pub fn decrypt_block_u64(&self, block: &mut [u64; $n_w]) {
-                for (b, s) in block.iter_mut().zip(&self.sk[$rounds / 4]) {
-                    *b = b.wrapping_sub(*s);
+            fn decrypt_with_subkeys(block: &mut [u64; $n_w], sk: impl Fn(usize, usize) -> u64) {
+                for (i, b) in block.iter_mut().enumerate() {
+                    *b = b.wrapping_sub(sk($rounds / 4, i));
                 }
 
                 for d in (0..$rounds).rev() {
@@ -146,8 +342,8 @@ macro_rules! impl_threefish(
                         let r = $rot[d % 8][j];
                         let (e0, e1) = inv_mix(r, f);
                         if d % 4 == 0 {
-                            let s0 = self.sk[d / 4][2 * j];
-                            let s1 = self.sk[d / 4][2 * j + 1];
+                            let s0 = sk(d / 4, 2 * j);
+                            let s1 = sk(d / 4, 2 * j + 1);
                             block[2 * j] = e0.wrapping_sub(s0);
                             block[2 * j + 1] = e1.wrapping_sub(s1);
                         } else {
@@ -180,7 +376,7 @@ macro_rules! impl_threefish(
 
         #[cfg(feature = "cipher")]
         impl ParBlocksSizeUser for $name {
-            type ParBlocksSize = U1;
+            type ParBlocksSize = U4;
         }
 
         #[cfg(feature = "cipher")]
@@ -195,18 +391,16 @@ macro_rules! impl_threefish(
         impl BlockCipherEncBackend for $name {
             #[inline]
             fn encrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
-                let mut v = [0u64; $n_w];
-                let b = block.get_in();
-                for (vv, chunk) in v.iter_mut().zip(b.chunks_exact(8)) {
-                    *vv = u64::from_le_bytes(chunk.try_into().unwrap());
-                }
-
+                let mut v = Self::words_from_bytes(block.get_in().as_ref());
                 self.encrypt_block_u64(&mut v);
+                Self::words_to_bytes(&v, block.get_out().as_mut());
+            }
 
-                let block = block.get_out();
-                for (chunk, vv) in block.chunks_exact_mut(8).zip(v.iter()) {
-                    chunk.copy_from_slice(&vv.to_le_bytes());
-                }
+            #[inline]
+            fn encrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+                let mut v = Self::par_words_from_bytes(blocks.get_in());
+                Self::encrypt_par_with_subkeys(&mut v, |s, i| self.sk[s][i]);
+                Self::par_words_to_bytes(&v, blocks.get_out());
             }
         }
 
@@ -222,18 +416,16 @@ macro_rules! impl_threefish(
         impl BlockCipherDecBackend for $name {
             #[inline]
             fn decrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
-                let mut v = [0u64; $n_w];
-                let b = block.get_in();
-                for (vv, chunk) in v.iter_mut().zip(b.chunks_exact(8)) {
-                    *vv = u64::from_le_bytes(chunk.try_into().unwrap());
-                }
-
+                let mut v = Self::words_from_bytes(block.get_in().as_ref());
                 self.decrypt_block_u64(&mut v);
+                Self::words_to_bytes(&v, block.get_out().as_mut());
+            }
 
-                let block = block.get_out();
-                for (chunk, vv) in block.chunks_exact_mut(8).zip(v.iter()) {
-                    chunk.copy_from_slice(&vv.to_le_bytes());
-                }
+            #[inline]
+            fn decrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+                let mut v = Self::par_words_from_bytes(blocks.get_in());
+                Self::decrypt_par_with_subkeys(&mut v, |s, i| self.sk[s][i]);
+                Self::par_words_to_bytes(&v, blocks.get_out());
             }
         }
 
@@ -259,9 +451,261 @@ macro_rules! impl_threefish(
 
         #[cfg(all(feature = "zeroize"))]
         impl ZeroizeOnDrop for $name {}
+
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serde::Serialize::serialize(&self.sk, serializer)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let sk = serde::Deserialize::deserialize(deserializer)?;
+                Ok($name { sk })
+            }
+        }
+
+        #[doc=$doc_name]
+        #[doc="block cipher with a tweak which can be changed without rekeying."]
+        ///
+        /// Unlike
+        #[doc=concat!("[`", stringify!($name), "`],")]
+        /// this type stores the extended key and tweak separately and derives
+        /// subkeys on the fly during encryption and decryption. This makes
+        /// changing the tweak cheap at the cost of slightly slower block
+        /// processing, which is a good trade-off for modes where the tweak
+        /// changes with every block.
+        #[derive(Clone)]
+        pub struct $tweakable {
+            k: [u64; $n_w + 1],
+            t: [u64; 3],
+        }
+
+        impl $tweakable {
+            /// Create new block cipher instance with the given key and tweak.
+            #[inline(always)]
+            pub fn new_with_tweak(key: &[u8; $n_w*8], tweak: &[u8; 16]) -> $tweakable {
+                let k = $name::words_from_bytes(key);
+                let tweak = $name::tweak_from_bytes(tweak);
+                Self::new_with_tweak_u64(&k, &tweak)
+            }
+
+            /// Create new block cipher instance with the given key and tweak
+            /// represented in the form of array of `u64`s.
+            #[inline(always)]
+            pub const fn new_with_tweak_u64(key: &[u64; $n_w], tweak: &[u64; 2]) -> $tweakable {
+                $tweakable {
+                    k: $name::extend_key(key),
+                    t: $name::extend_tweak(tweak),
+                }
+            }
+
+            /// Replace the current tweak.
+            #[inline(always)]
+            pub fn set_tweak(&mut self, tweak: &[u8; 16]) {
+                self.set_tweak_u64(&$name::tweak_from_bytes(tweak));
+            }
+
+            /// Replace the current tweak represented in the form of array of `u64`s.
+            #[inline(always)]
+            pub fn set_tweak_u64(&mut self, tweak: &[u64; 2]) {
+                self.t = $name::extend_tweak(tweak);
+            }
+
+            /// Encrypt block using the current tweak.
+            #[inline(always)]
+            pub fn encrypt_block_u64(&self, block: &mut [u64; $n_w]) {
+                $name::encrypt_with_subkeys(block, |s, i| $name::subkey(&self.k, &self.t, s, i));
+            }
+
+            /// Decrypt block using the current tweak.
+            #[inline(always)]
+            pub fn decrypt_block_u64(&self, block: &mut [u64; $n_w]) {
+                $name::decrypt_with_subkeys(block, |s, i| $name::subkey(&self.k, &self.t, s, i));
+            }
+
+            /// Encrypt blocks using the current tweak.
+            ///
+            /// Blocks are processed in parallel using SIMD instructions if
+            /// they are supported by the target CPU.
+            #[inline]
+            pub fn encrypt_blocks_u64(&self, blocks: &mut [[u64; $n_w]]) {
+                $name::encrypt_blocks_with_subkeys(blocks, |s, i| $name::subkey(&self.k, &self.t, s, i));
+            }
+
+            /// Decrypt blocks using the current tweak.
+            ///
+            /// Blocks are processed in parallel using SIMD instructions if
+            /// they are supported by the target CPU.
+            #[inline]
+            pub fn decrypt_blocks_u64(&self, blocks: &mut [[u64; $n_w]]) {
+                $name::decrypt_blocks_with_subkeys(blocks, |s, i| $name::subkey(&self.k, &self.t, s, i));
+            }
+
+            /// Encrypt block using the given tweak instead of the current one.
+            #[inline(always)]
+            pub fn encrypt_block_with_tweak(&self, block: &mut [u8; $n_w*8], tweak: &[u8; 16]) {
+                let mut v = $name::words_from_bytes(block);
+                self.encrypt_block_with_tweak_u64(&mut v, &$name::tweak_from_bytes(tweak));
+                $name::words_to_bytes(&v, block);
+            }
+
+            /// Decrypt block using the given tweak instead of the current one.
+            #[inline(always)]
+            pub fn decrypt_block_with_tweak(&self, block: &mut [u8; $n_w*8], tweak: &[u8; 16]) {
+                let mut v = $name::words_from_bytes(block);
+                self.decrypt_block_with_tweak_u64(&mut v, &$name::tweak_from_bytes(tweak));
+                $name::words_to_bytes(&v, block);
+            }
+
+            /// Encrypt block in the form of array of `u64`s using the given tweak
+            /// instead of the current one.
+            #[inline(always)]
+            pub fn encrypt_block_with_tweak_u64(&self, block: &mut [u64; $n_w], tweak: &[u64; 2]) {
+                let t = $name::extend_tweak(tweak);
+                $name::encrypt_with_subkeys(block, |s, i| $name::subkey(&self.k, &t, s, i));
+            }
+
+            /// Decrypt block in the form of array of `u64`s using the given tweak
+            /// instead of the current one.
+            #[inline(always)]
+            pub fn decrypt_block_with_tweak_u64(&self, block: &mut [u64; $n_w], tweak: &[u64; 2]) {
+                let t = $name::extend_tweak(tweak);
+                $name::decrypt_with_subkeys(block, |s, i| $name::subkey(&self.k, &t, s, i));
+            }
+        }
+
+        #[cfg(feature = "cipher")]
+        impl KeySizeUser for $tweakable {
+            type KeySize = $block_size;
+        }
+
+        #[cfg(feature = "cipher")]
+        impl KeyInit for $tweakable {
+            fn new(key: &Key<Self>) -> Self {
+                let mut tmp_key = [0u8; $n_w*8];
+                tmp_key.copy_from_slice(key);
+                Self::new_with_tweak(&tmp_key, &Default::default())
+            }
+        }
+
+        #[cfg(feature = "cipher")]
+        impl BlockSizeUser for $tweakable {
+            type BlockSize = $block_size;
+        }
+
+        #[cfg(feature = "cipher")]
+        impl ParBlocksSizeUser for $tweakable {
+            type ParBlocksSize = U4;
+        }
+
+        #[cfg(feature = "cipher")]
+        impl BlockCipherEncrypt for $tweakable {
+            #[inline]
+            fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = Self::BlockSize>) {
+                f.call(self)
+            }
+        }
+
+        #[cfg(feature = "cipher")]
+        impl BlockCipherEncBackend for $tweakable {
+            #[inline]
+            fn encrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
+                let mut v = $name::words_from_bytes(block.get_in().as_ref());
+                self.encrypt_block_u64(&mut v);
+                $name::words_to_bytes(&v, block.get_out().as_mut());
+            }
+
+            #[inline]
+            fn encrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+                let mut v = $name::par_words_from_bytes(blocks.get_in());
+                $name::encrypt_par_with_subkeys(&mut v, |s, i| $name::subkey(&self.k, &self.t, s, i));
+                $name::par_words_to_bytes(&v, blocks.get_out());
+            }
+        }
+
+        #[cfg(feature = "cipher")]
+        impl BlockCipherDecrypt for $tweakable {
+            #[inline]
+            fn decrypt_with_backend(&self, f: impl BlockCipherDecClosure<BlockSize = Self::BlockSize>) {
+                f.call(self)
+            }
+        }
+
+        #[cfg(feature = "cipher")]
+        impl BlockCipherDecBackend for $tweakable {
+            #[inline]
+            fn decrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
+                let mut v = $name::words_from_bytes(block.get_in().as_ref());
+                self.decrypt_block_u64(&mut v);
+                $name::words_to_bytes(&v, block.get_out().as_mut());
+            }
+
+            #[inline]
+            fn decrypt_par_blocks(&self, mut blocks: InOut<'_, '_, ParBlocks<Self>>) {
+                let mut v = $name::par_words_from_bytes(blocks.get_in());
+                $name::decrypt_par_with_subkeys(&mut v, |s, i| $name::subkey(&self.k, &self.t, s, i));
+                $name::par_words_to_bytes(&v, blocks.get_out());
+            }
+        }
+
+        impl fmt::Debug for $tweakable {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(concat!(stringify!($tweakable), " { ... }"))
+            }
+        }
+
+        #[cfg(feature = "cipher")]
+        impl AlgorithmName for $tweakable {
+            fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(stringify!($tweakable))
+            }
+        }
+
+        impl Drop for $tweakable {
+            fn drop(&mut self) {
+                #[cfg(all(feature = "zeroize"))]
+                {
+                    self.k.zeroize();
+                    self.t.zeroize();
+                }
+            }
+        }
+
+        #[cfg(all(feature = "zeroize"))]
+        impl ZeroizeOnDrop for $tweakable {}
     )
 );
 
-impl_threefish!(Threefish256, 72, 4, U32, R256, P256, "Threefish-256");
-impl_threefish!(Threefish512, 72, 8, U64, R512, P512, "Threefish-512");
-impl_threefish!(Threefish1024, 80, 16, U128, R1024, P1024, "Threefish-1024");
+impl_threefish!(
+    Threefish256,
+    Threefish256Tweakable,
+    72,
+    4,
+    U32,
+    R256,
+    P256,
+    "Threefish-256"
+);
+impl_threefish!(
+    Threefish512,
+    Threefish512Tweakable,
+    72,
+    8,
+    U64,
+    R512,
+    P512,
+    "Threefish-512"
+);
+impl_threefish!(
+    Threefish1024,
+    Threefish1024Tweakable,
+    80,
+    16,
+    U128,
+    R1024,
+    P1024,
+    "Threefish-1024"
+);
@@ -1,9 +1,13 @@
 //! Test vectors from:
 //! https://github.com/weidai11/cryptopp/blob/master/TestVectors/threefish.txt
 #![cfg(feature = "cipher")]
-use cipher::{Block, BlockCipherDecrypt, BlockCipherEncrypt, KeyInit};
+use cipher::{AlgorithmName, Block, BlockCipherDecrypt, BlockCipherEncrypt, KeyInit};
+use core::{fmt, marker::PhantomData};
 use hex_literal::hex;
-use threefish::{Threefish256, Threefish512, Threefish1024};
+use threefish::{
+    Threefish256, Threefish256Tweakable, Threefish512, Threefish512Tweakable, Threefish1024,
+    Threefish1024Tweakable,
+};
 
 struct Vector {
     key: &'static [u8],
@@ -13,7 +17,7 @@ struct Vector {
 }
 
 macro_rules! impl_test {
-    {$name:ident, $cipher:ty, $tests:expr,} => {
+    {$name:ident, $cipher:ty, $tweakable:ty, $tests:expr,} => {
         #[test]
         #[allow(deprecated)] // uses `clone_from_slice`
         fn $name() {
@@ -38,6 +42,30 @@ macro_rules! impl_test {
                 assert!(t.iter().all(|b| b[..] == ct[..]));
                 cipher.decrypt_blocks(&mut t);
                 assert!(t.iter().all(|b| b[..] == pt[..]));
+
+                let key = key.try_into().unwrap();
+                let tweak = tweak.unwrap_or(&[0; 16]).try_into().unwrap();
+
+                let mut cipher = <$tweakable>::new_with_tweak(key, tweak);
+                let mut t = Block::<$tweakable>::clone_from_slice(pt);
+                cipher.encrypt_block(&mut t);
+                assert_eq!(t[..], ct[..]);
+                cipher.decrypt_block(&mut t);
+                assert_eq!(t[..], pt[..]);
+
+                let mut t = pt.try_into().unwrap();
+                cipher.set_tweak(&[0xFF; 16]);
+                cipher.encrypt_block_with_tweak(&mut t, tweak);
+                assert_eq!(t[..], ct[..]);
+                cipher.decrypt_block_with_tweak(&mut t, tweak);
+                assert_eq!(t[..], pt[..]);
+
+                cipher.set_tweak(tweak);
+                let mut t = [Block::<$tweakable>::clone_from_slice(pt); 64];
+                cipher.encrypt_blocks(&mut t);
+                assert!(t.iter().all(|b| b[..] == ct[..]));
+                cipher.decrypt_blocks(&mut t);
+                assert!(t.iter().all(|b| b[..] == pt[..]));
             }
         }
     };
@@ -46,6 +74,7 @@ macro_rules! impl_test {
 impl_test! {
     threefish_256,
     Threefish256,
+    Threefish256Tweakable,
     [
         Vector {
             key: &[0; 32],
@@ -77,6 +106,7 @@ impl_test! {
 impl_test! {
     threefish_512,
     Threefish512,
+    Threefish512Tweakable,
     [
         Vector {
             key: &[0; 64],
@@ -185,6 +215,7 @@ impl_test! {
 impl_test! {
     threefish_1024,
     Threefish1024,
+    Threefish1024Tweakable,
     [
         Vector {
             key: &[0; 128],
@@ -236,3 +267,97 @@ impl_test! {
         },
     ],
 }
+
+macro_rules! impl_par_test {
+    {$name:ident, $cipher:ty, $tweakable:ty, $n_w:expr,} => {
+        #[test]
+        fn $name() {
+            let key: [u64; $n_w] = core::array::from_fn(|i| 0x0101_0101_0101_0101 * i as u64);
+            let tweak = [0x0706050403020100, 0x0F0E0D0C0B0A0908];
+            let cipher = <$cipher>::new_with_tweak_u64(&key, &tweak);
+            let tweakable = <$tweakable>::new_with_tweak_u64(&key, &tweak);
+
+            // 7 distinct blocks exercise both the parallel and the single-block paths
+            let pt: [[u64; $n_w]; 7] =
+                core::array::from_fn(|k| core::array::from_fn(|i| (k * $n_w + i) as u64));
+            let mut ct = pt;
+            for block in ct.iter_mut() {
+                cipher.encrypt_block_u64(block);
+            }
+
+            let mut t = pt;
+            cipher.encrypt_blocks_u64(&mut t);
+            assert_eq!(t, ct);
+            cipher.decrypt_blocks_u64(&mut t);
+            assert_eq!(t, pt);
+
+            let mut t = pt;
+            tweakable.encrypt_blocks_u64(&mut t);
+            assert_eq!(t, ct);
+            tweakable.decrypt_blocks_u64(&mut t);
+            assert_eq!(t, pt);
+        }
+    };
+}
+
+impl_par_test! {
+    threefish_256_par,
+    Threefish256,
+    Threefish256Tweakable,
+    4,
+}
+
+impl_par_test! {
+    threefish_512_par,
+    Threefish512,
+    Threefish512Tweakable,
+    8,
+}
+
+impl_par_test! {
+    threefish_1024_par,
+    Threefish1024,
+    Threefish1024Tweakable,
+    16,
+}
+
+#[test]
+fn const_key_schedule() {
+    const KEY: [u64; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+    const TWEAK: [u64; 2] = [9, 10];
+    const CIPHER: Threefish512 = Threefish512::new_with_tweak_u64(&KEY, &TWEAK);
+
+    let mut a = [0u64; 8];
+    let mut b = [0u64; 8];
+    CIPHER.encrypt_block_u64(&mut a);
+    Threefish512::new_with_tweak_u64(&KEY, &TWEAK).encrypt_block_u64(&mut b);
+    assert_eq!(a, b);
+}
+
+#[test]
+fn debug_hides_key_material() {
+    let cipher = Threefish256::new_with_tweak_u64(&[1, 2, 3, 4], &[5, 6]);
+    assert_eq!(format!("{cipher:?}"), "Threefish256 { ... }");
+    let cipher = Threefish256Tweakable::new_with_tweak_u64(&[1, 2, 3, 4], &[5, 6]);
+    assert_eq!(format!("{cipher:?}"), "Threefish256Tweakable { ... }");
+}
+
+#[test]
+fn algorithm_names() {
+    struct Name<T>(PhantomData<T>);
+
+    impl<T: AlgorithmName> fmt::Display for Name<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            T::write_alg_name(f)
+        }
+    }
+
+    fn name<T: AlgorithmName>() -> String {
+        Name::<T>(PhantomData).to_string()
+    }
+
+    assert_eq!(name::<Threefish256>(), "Threefish256");
+    assert_eq!(name::<Threefish256Tweakable>(), "Threefish256Tweakable");
+    assert_eq!(name::<Threefish512Tweakable>(), "Threefish512Tweakable");
+    assert_eq!(name::<Threefish1024Tweakable>(), "Threefish1024Tweakable");
+}
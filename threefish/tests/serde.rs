@@ -0,0 +1,43 @@
+#![cfg(feature = "serde")]
+use threefish::{Threefish256, Threefish512, Threefish1024};
+
+macro_rules! impl_serde_test {
+    {$name:ident, $cipher:ty, $n_w:expr,} => {
+        #[test]
+        fn $name() {
+            let key: [u64; $n_w] = core::array::from_fn(|i| 0x0101_0101_0101_0101 * i as u64);
+            let tweak = [0x0706050403020100, 0x0F0E0D0C0B0A0908];
+            let cipher = <$cipher>::new_with_tweak_u64(&key, &tweak);
+
+            let data = serde_json::to_string(&cipher).unwrap();
+            let restored: $cipher = serde_json::from_str(&data).unwrap();
+
+            let mut a = [0u64; $n_w];
+            let mut b = [0u64; $n_w];
+            cipher.encrypt_block_u64(&mut a);
+            restored.encrypt_block_u64(&mut b);
+            assert_eq!(a, b);
+
+            // truncated subkey arrays must be rejected
+            assert!(serde_json::from_str::<$cipher>("[[0]]").is_err());
+        }
+    };
+}
+
+impl_serde_test! {
+    threefish_256_serde,
+    Threefish256,
+    4,
+}
+
+impl_serde_test! {
+    threefish_512_serde,
+    Threefish512,
+    8,
+}
+
+impl_serde_test! {
+    threefish_1024_serde,
+    Threefish1024,
+    16,
+}